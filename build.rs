@@ -7,6 +7,8 @@ fn main() -> Result<()> {
     // Only rerun build script if specific resource files change
     println!("cargo:rerun-if-changed=res/cube.obj");
     println!("cargo:rerun-if-changed=res/cube.mtl");
+    println!("cargo:rerun-if-changed=res/colored_cube.obj");
+    println!("cargo:rerun-if-changed=res/colored_cube.mtl");
 
     let out_dir = env::var("OUT_DIR")?;
     let mut copy_options = CopyOptions::new();
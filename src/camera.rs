@@ -12,12 +12,15 @@ pub const OPENGL_TO_WGPU_MATRIX: cgmath::Matrix4<f32> = cgmath::Matrix4::new(
     0.0, 0.0, 0.0, 1.0,
 );
 
-// Camera constraints
-const MIN_ZOOM_DISTANCE: f32 = 0.5;
-const MAX_ZOOM_DISTANCE: f32 = 100.0;
+// Default camera constraints, see `CameraController::set_min_zoom` and `set_max_zoom`
+pub const DEFAULT_MIN_ZOOM_DISTANCE: f32 = 0.5;
+pub const DEFAULT_MAX_ZOOM_DISTANCE: f32 = 100.0;
+
+/// Default length of the eased transitions to the home view and the standard views, in seconds
+pub const DEFAULT_TRANSITION_SECONDS: f32 = 0.4;
 
 // Professional 3D orbit camera implementation
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Camera {
     // Eye position in 3D space
     pub position: Point3<f32>,
@@ -42,61 +45,116 @@ pub struct Camera {
     pub initial_target: Point3<f32>,
     pub initial_orientation: Quaternion<f32>,
     pub initial_distance: f32,
+
+    // Home view returned to by `C`, starts as the initial view and can be replaced at runtime
+    pub home_position: Point3<f32>,
+    pub home_target: Point3<f32>,
+    pub home_orientation: Quaternion<f32>,
+    pub home_distance: f32,
+
+    // Eased transition to the home or a standard view, advanced by the controller every frame
+    pub animation: Option<CameraAnimation>,
+    // Length of those transitions in seconds; 0 jumps straight to the new view
+    pub transition_duration: f32,
+}
+
+/// Eased move of the camera from one view to another, e.g. when returning home with `C`
+///
+/// The orientation is slerped, while target and distance follow the same smoothstep curve. The
+/// position is not interpolated on its own: it follows from the other three on every step, so
+/// the eye swings around the target instead of cutting straight through the scene.
+#[derive(Debug, Clone)]
+pub struct CameraAnimation {
+    pub start_target: Point3<f32>,
+    pub end_target: Point3<f32>,
+    pub start_distance: f32,
+    pub end_distance: f32,
+    pub start_orientation: Quaternion<f32>,
+    pub end_orientation: Quaternion<f32>,
+    // Right vector the tracking restarts from at the end, as if the view had been set directly
+    end_right: Vector3<f32>,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl CameraAnimation {
+    /// Share of the move done so far, eased in and out with smoothstep
+    pub fn progress(&self) -> f32 {
+        let t = (self.elapsed / self.duration).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
 }
 
 impl Camera {
     pub fn new<V: Into<Point3<f32>>>(position: V, target: Point3<f32>) -> Self {
         let position = position.into();
         
-        // Calculate initial distance from target
-        let distance = (position - target).magnitude();
-        
-        // Calculate initial orientation based on position
-        let dir = (target - position).normalize();
-        
-        // Define world up vector (Z-up for professional 3D software standard)
-        let world_up = Vector3::unit_z();
-        
-        // Calculate initial orientation quaternion
-        let orientation = Quaternion::look_at(dir, world_up);
-        
-        // Initialize stable reference frame
-        let forward = -dir;
-        let right = if forward.dot(world_up).abs() > 0.99 {
-            // If aligned with pole, pick an arbitrary but consistent right vector
-            Vector3::unit_x()
-        } else {
-            // Normal case - get perpendicular right vector
-            forward.cross(world_up).normalize()
-        };
-        let up = right.cross(forward).normalize();
-        
-        // Create reference frame matrix from orthogonal basis vectors
-        let reference_frame = Matrix3::from_cols(right, up, forward);
-        
-        // Create Camera with professional default settings
+        // Create Camera with professional default settings; `look_at` sets up the pose
         let mut cam = Self {
             position,
             target,
-            up: world_up,  // Z-up coordinate system (professional 3D software standard)
-            distance,
-            orientation,
+            up: Vector3::unit_z(),  // Z-up coordinate system (professional 3D software standard)
+            distance: 0.0,
+            orientation: Quaternion::one(),
             world_up: Vector3::unit_z(),  // Z-up for turntable orbit mode
             turntable_mode: true,  // Default to turntable mode (professional standard)
-            reference_frame,
-            last_right: right,
+            reference_frame: Matrix3::identity(),
+            last_right: -Vector3::unit_x(),
             
-            // Store initial camera settings for reset functionality
             initial_position: position,
             initial_target: target,
-            initial_orientation: orientation,
-            initial_distance: distance,
+            initial_orientation: Quaternion::one(),
+            initial_distance: 0.0,
+
+            home_position: position,
+            home_target: target,
+            home_orientation: Quaternion::one(),
+            home_distance: 0.0,
+
+            animation: None,
+            transition_duration: DEFAULT_TRANSITION_SECONDS,
         };
+        cam.look_at(position, target);
         
-        cam.update_position();
+        // Store initial camera settings for reset functionality
+        cam.initial_orientation = cam.orientation;
+        cam.initial_distance = cam.distance;
+        cam.set_home_view();
         cam
     }
 
+    /// Place the eye at `eye` looking at `target`, level with the world up axis
+    ///
+    /// Recomputes the orientation, distance, reference frame and tracked right vector the same
+    /// way the constructor does, so turntable orbiting continues from the new pose. Looking
+    /// straight along the up axis puts +Y up on screen, as in the top view. Stops a running
+    /// transition; an eye on the target keeps the current orientation.
+    pub fn look_at(&mut self, eye: Point3<f32>, target: Point3<f32>) {
+        self.animation = None;
+        let offset = eye - target;
+        self.target = target;
+        self.distance = offset.magnitude();
+        if self.distance > 0.0 {
+            let dir = offset / self.distance;
+            // Tilt the -Y start offset up or down about X, then turn it about the up axis
+            let pitch = Rad(-dir.z.clamp(-1.0, 1.0).asin());
+            let yaw = if dir.x.hypot(dir.y) > 1e-6 { Rad(dir.x.atan2(-dir.y)) } else { Rad(0.0) };
+            self.orientation = Quaternion::from_angle_z(yaw) * Quaternion::from_angle_x(pitch);
+        }
+        // A level orientation keeps the turned -X axis as its right vector, also at the poles
+        self.last_right = self.orientation.rotate_vector(-Vector3::unit_x());
+        self.update_position();
+    }
+
+    /// Eye position and target, the pose `look_at` takes
+    pub fn pose(&self) -> (Point3<f32>, Point3<f32>) {
+        (self.position, self.target)
+    }
+
     // Update the camera position based on quaternion orientation and distance
     pub fn update_position(&mut self) {
         if self.turntable_mode {
@@ -159,27 +217,190 @@ impl Camera {
     }
 
     
-    /// Reset the camera to its initial position and orientation
+    /// Reset the camera to its initial position and orientation (factory reset), eased over `transition_duration`
     pub fn reset_to_initial(&mut self) {
-        self.position = self.initial_position;
-        self.target = self.initial_target;
-        self.orientation = self.initial_orientation;
-        self.distance = self.initial_distance;
-        
+        let right = self.right_for(self.initial_orientation, Vector3::unit_x());
+        self.transition_to(self.initial_target, self.initial_orientation, self.initial_distance, right);
+    }
+
+    /// Return the camera to the home view set with `set_home_view`, eased over `transition_duration`
+    pub fn reset_to_home(&mut self) {
+        let right = self.right_for(self.home_orientation, Vector3::unit_x());
+        self.transition_to(self.home_target, self.home_orientation, self.home_distance, right);
+    }
+
+    /// Capture the current camera as the new home view
+    pub fn set_home_view(&mut self) {
+        self.home_position = self.position;
+        self.home_target = self.target;
+        self.home_orientation = self.orientation;
+        self.home_distance = self.distance;
+    }
+
+    /// Place the camera from a saved pose at once; the position is recomputed from orientation and distance
+    pub fn reset_to(&mut self, position: Point3<f32>, target: Point3<f32>, orientation: Quaternion<f32>, distance: f32) {
+        self.animation = None;
+        self.position = position;
         // Make sure the right vector is reset correctly
-        let forward = -self.orientation.rotate_vector(Vector3::unit_y());
-        let right = if forward.dot(self.world_up).abs() > 0.99 {
-            Vector3::unit_x() // Default if aligned with pole
+        let right = self.right_for(orientation, Vector3::unit_x());
+        self.apply_pose(target, orientation, distance, right);
+    }
+
+    /// Look straight down the world up axis at the current target, with +Y pointing up on screen
+    ///
+    /// Always immediate, as the top-down view switches the projection at the same time.
+    pub fn set_top_down(&mut self) {
+        self.animation = None;
+        let orientation = StandardView::Top.orientation();
+        self.apply_pose(self.target, orientation, self.distance, -Vector3::unit_x());
+    }
+
+    /// Look at the current target from the direction of `view`, keeping the target and distance
+    ///
+    /// Eased over `transition_duration`.
+    pub fn set_view(&mut self, view: StandardView) {
+        let orientation = view.orientation();
+        // At the poles the tracked right vector defines the screen orientation: +X to the right
+        let right = self.right_for(orientation, -Vector3::unit_x());
+        self.transition_to(self.target, orientation, self.distance, right);
+    }
+
+    /// Right vector to start the tracking from for `orientation`, so it is not flipped to match
+    /// the previous view; `at_pole` when looking along the world up axis
+    fn right_for(&self, orientation: Quaternion<f32>, at_pole: Vector3<f32>) -> Vector3<f32> {
+        let forward = -orientation.rotate_vector(Vector3::unit_y());
+        if forward.dot(self.world_up).abs() > 0.98 {
+            at_pole
         } else {
-            forward.cross(self.world_up).normalize() // Normal perpendicular vector
-        };
-        
+            forward.cross(self.world_up).normalize()
+        }
+    }
+
+    fn apply_pose(&mut self, target: Point3<f32>, orientation: Quaternion<f32>, distance: f32, right: Vector3<f32>) {
+        self.target = target;
+        self.orientation = orientation;
+        self.distance = distance;
         self.last_right = right;
-        
         // Update position based on orientation
         self.update_position();
     }
 
+    /// Start an eased move to the given view, or jump there when transitions are turned off
+    fn transition_to(&mut self, target: Point3<f32>, orientation: Quaternion<f32>, distance: f32, right: Vector3<f32>) {
+        if self.transition_duration <= 0.0 {
+            self.animation = None;
+            self.apply_pose(target, orientation, distance, right);
+            return;
+        }
+        self.animation = Some(CameraAnimation {
+            start_target: self.target,
+            end_target: target,
+            start_distance: self.distance,
+            end_distance: distance,
+            start_orientation: self.orientation,
+            end_orientation: orientation,
+            end_right: right,
+            elapsed: 0.0,
+            duration: self.transition_duration,
+        });
+    }
+
+    /// Move a running transition on by `dt` seconds; false once there is none (left)
+    pub fn advance_animation(&mut self, dt: f32) -> bool {
+        let Some(mut animation) = self.animation.take() else {
+            return false;
+        };
+        animation.elapsed += dt;
+        if animation.is_finished() {
+            // Land exactly on the new view
+            self.apply_pose(animation.end_target, animation.end_orientation, animation.end_distance, animation.end_right);
+            return false;
+        }
+        let t = animation.progress();
+        self.target = animation.start_target + (animation.end_target - animation.start_target) * t;
+        self.orientation = animation.start_orientation.slerp(animation.end_orientation, t);
+        self.distance = animation.start_distance + (animation.end_distance - animation.start_distance) * t;
+        self.update_position();
+        self.animation = Some(animation);
+        true
+    }
+
+    /// Stop a running transition where it is
+    pub fn stop_animation(&mut self) {
+        self.animation = None;
+    }
+
+    /// Whether a transition to another view is running
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Orbit around the target by `yaw` about the world up axis and `pitch` about the
+    /// horizontal (right) axis, applied as a single quaternion step
+    pub fn orbit(&mut self, yaw: Rad<f32>, pitch: Rad<f32>) {
+        // In a quaternion orbit system with reference frame tracking:
+        // 1. Yaw rotates around world up (Z) - unchanged
+        // 2. Pitch rotates around reference frame's tracked right vector
+        let yaw_rotation = Quaternion::from_axis_angle(self.world_up, yaw);
+
+        // Use the tracked reference right vector for stable pitch rotation, instead of
+        // computing the right vector from orientation
+        let pitch_rotation = Quaternion::from_axis_angle(self.last_right.normalize(), pitch);
+
+        // Order matters: yaw * (pitch * orientation) gives proper turntable feel
+        // Keep quaternion normalized to prevent drift
+        self.orientation = (yaw_rotation * pitch_rotation * self.orientation).normalize();
+
+        // Update camera position after rotation
+        self.update_position();
+    }
+
+    /// Turn the view around the eye (first-person look) by `yaw` and `pitch`
+    ///
+    /// Positive yaw turns right and positive pitch looks up. The target moves
+    /// with the view, keeping its distance in front of the eye.
+    pub fn look_around(&mut self, yaw: Rad<f32>, pitch: Rad<f32>) {
+        let eye = self.position;
+        // Turning the view one way is orbiting the target the other way around the eye
+        self.orbit(-yaw, -pitch);
+        self.target += eye - self.position;
+        self.position = eye;
+    }
+
+    /// Move the eye and target together along the view direction and the horizontal right vector
+    pub fn fly(&mut self, forward_amount: f32, right_amount: f32) {
+        let forward = (self.target - self.position).normalize();
+        // Looking along the up axis the cross product vanishes, so use the camera up instead
+        let right = if forward.dot(self.world_up).abs() > 0.98 {
+            forward.cross(self.up).normalize()
+        } else {
+            forward.cross(self.world_up).normalize()
+        };
+        let offset = forward * forward_amount + right * right_amount;
+        self.position += offset;
+        self.target += offset;
+    }
+
+    /// Zoom by `factor` (new distance over old) about `point`, which stays fixed on screen
+    ///
+    /// Eye and target both move toward the point; the controller keeps the distance within its zoom limits.
+    pub fn zoom_toward(&mut self, point: Point3<f32>, factor: f32) {
+        let distance = self.distance * factor;
+        self.target = point + (self.target - point) * factor;
+        self.distance = distance;
+        self.update_position();
+    }
+
+    /// Move eye and target across the view plane so that `point` keeps its place on screen
+    /// while an orthographic scale changes by `factor` (new scale over old)
+    pub fn shift_toward(&mut self, point: Point3<f32>, factor: f32) {
+        let forward = (self.target - self.position).normalize();
+        let offset = (point - self.target) * (1.0 - factor);
+        let offset = offset - forward * offset.dot(forward);
+        self.position += offset;
+        self.target += offset;
+    }
+
     pub fn calc_matrix(&self) -> Matrix4<f32> {
         // In professional 3D software, the camera view matrix is simply
         // looking from the position to the target with a consistent up vector
@@ -192,8 +413,13 @@ impl Camera {
         // Calculate view-aligned right and up vectors for panning
         let forward = (self.target - self.position).normalize();
         
-        // In Z-up world, the right vector is perpendicular to forward and world_up
-        let right = forward.cross(self.world_up).normalize();
+        // In Z-up world, the right vector is perpendicular to forward and world_up.
+        // Looking along the up axis (top-down view) that cross product vanishes, so use the camera up instead
+        let right = if forward.dot(self.world_up).abs() > 0.98 {
+            forward.cross(self.up).normalize()
+        } else {
+            forward.cross(self.world_up).normalize()
+        };
         
         // The true up vector follows the orbit-style in Z-up world
         // This ensures panning is always aligned with view orientation
@@ -212,12 +438,27 @@ impl Camera {
     }
 }
 
-// For handling perspective projection matrix
+/// Range of the vertical field of view accepted by `Projection::set_fovy`
+pub const MIN_FOVY_DEGREES: f32 = 10.0;
+pub const MAX_FOVY_DEGREES: f32 = 120.0;
+
+/// Perspective (default) or orthographic projection, where parallel edges stay parallel
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum ProjectionMode {
+    #[default]
+    Perspective,
+    /// Visible height in world units; zooming changes it instead of moving the eye
+    Orthographic { scale: f32 },
+}
+
+// For handling perspective and orthographic projection matrices
+#[derive(Debug, Clone)]
 pub struct Projection {
     pub aspect: f32,
     pub fovy: Rad<f32>,
     pub znear: f32,
     pub zfar: f32,
+    pub mode: ProjectionMode,
 }
 
 impl Projection {
@@ -227,6 +468,7 @@ impl Projection {
             fovy: fovy.into(),
             znear,
             zfar,
+            mode: ProjectionMode::Perspective,
         }
     }
 
@@ -234,11 +476,154 @@ impl Projection {
         self.aspect = width as f32 / height as f32;
     }
 
+    /// Set the vertical field of view, clamped to `MIN_FOVY_DEGREES`..=`MAX_FOVY_DEGREES`
+    pub fn set_fovy<F: Into<Rad<f32>>>(&mut self, fovy: F) {
+        let degrees = Deg::from(fovy.into()).0.clamp(MIN_FOVY_DEGREES, MAX_FOVY_DEGREES);
+        self.fovy = Deg(degrees).into();
+    }
+
     pub fn calc_matrix(&self) -> Matrix4<f32> {
-        OPENGL_TO_WGPU_MATRIX * perspective(self.fovy, self.aspect, self.znear, self.zfar)
+        match self.mode {
+            ProjectionMode::Orthographic { scale } => {
+                let half_height = scale * 0.5;
+                let half_width = half_height * self.aspect;
+                OPENGL_TO_WGPU_MATRIX * ortho(-half_width, half_width, -half_height, half_height, self.znear, self.zfar)
+            }
+            ProjectionMode::Perspective => OPENGL_TO_WGPU_MATRIX * perspective(self.fovy, self.aspect, self.znear, self.zfar),
+        }
+    }
+
+    /// Whether the projection is orthographic
+    pub fn is_orthographic(&self) -> bool {
+        matches!(self.mode, ProjectionMode::Orthographic { .. })
+    }
+
+    /// Set the near and far clipping planes, keeping `znear` positive and below `zfar`
+    pub fn set_near_far(&mut self, znear: f32, zfar: f32) {
+        self.znear = znear.max(f32::EPSILON);
+        self.zfar = zfar.max(self.znear * 2.0);
+    }
+
+    /// Height of the perspective view frustum at `distance`, used to match orthographic framing
+    pub fn view_height_at(&self, distance: f32) -> f32 {
+        2.0 * distance * (self.fovy.0 * 0.5).tan()
+    }
+}
+
+/// Canonical view directions in the Z-up world, named after the side the eye looks from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StandardView {
+    /// Looking down the Z axis, +Y up on screen
+    Top,
+    /// Looking up the Z axis, +Y down on screen
+    Bottom,
+    /// Looking along +Y, Z up
+    Front,
+    /// Looking along -Y
+    Back,
+    /// Looking along +X
+    Left,
+    /// Looking along -X
+    Right,
+    /// From the front, right and top at once, every axis tilted equally toward the eye
+    Iso,
+}
+
+impl StandardView {
+    /// Camera orientation of the view; the identity looks from the front (eye on -Y)
+    pub fn orientation(self) -> Quaternion<f32> {
+        match self {
+            // Rotating the -Y start offset by -90 degrees about X places the eye above the target
+            StandardView::Top => Quaternion::from_angle_x(Deg(-90.0)),
+            StandardView::Bottom => Quaternion::from_angle_x(Deg(90.0)),
+            StandardView::Front => Quaternion::one(),
+            StandardView::Back => Quaternion::from_angle_z(Deg(180.0)),
+            StandardView::Left => Quaternion::from_angle_z(Deg(-90.0)),
+            StandardView::Right => Quaternion::from_angle_z(Deg(90.0)),
+            // Elevation atan(1 / sqrt(2)), the angle of the cube diagonal above the ground
+            StandardView::Iso => Quaternion::from_angle_z(Deg(45.0)) * Quaternion::from_angle_x(Rad(-(0.5f32).sqrt().atan())),
+        }
+    }
+}
+
+/// Frame rate the orbit sensitivity was originally tuned at; keeps the same
+/// angle per pixel of mouse motion now that orbit no longer scales with `dt`
+const ORBIT_REFERENCE_RATE: f32 = 60.0;
+
+/// How mouse and keyboard input move the camera
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ControllerMode {
+    /// Orbit around the target; WASD pans (default)
+    #[default]
+    Orbit,
+    /// First-person fly-through: WASD moves the eye, dragging with the orbit button looks around
+    Fly,
+}
+
+/// Modifier key that must be held together with a camera drag button
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DragModifier {
+    Shift,
+    Alt,
+}
+
+/// Mouse button, optionally with a modifier key, that starts a camera drag
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MouseBinding {
+    pub button: MouseButton,
+    pub modifier: Option<DragModifier>,
+}
+
+impl MouseBinding {
+    pub fn new(button: MouseButton) -> Self {
+        Self { button, modifier: None }
+    }
+
+    /// The same button, only while `modifier` is held
+    pub fn with_modifier(self, modifier: DragModifier) -> Self {
+        Self { modifier: Some(modifier), ..self }
+    }
+}
+
+impl From<MouseButton> for MouseBinding {
+    fn from(button: MouseButton) -> Self {
+        Self::new(button)
+    }
+}
+
+/// Orbit and pan mouse bindings matching common 3D applications
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum MouseBindingPreset {
+    /// Right drag orbits, middle drag pans (default)
+    #[default]
+    Default,
+    /// Middle drag orbits, Shift + middle drag pans
+    Blender,
+    /// Alt + left drag orbits, Alt + middle drag pans
+    Maya,
+    /// Left drag orbits, right drag pans, as in most web viewers
+    WebOrbit,
+}
+
+impl MouseBindingPreset {
+    /// The (orbit, pan) bindings of the preset
+    pub fn bindings(self) -> (MouseBinding, MouseBinding) {
+        use MouseButton::{Left, Middle, Right};
+        match self {
+            MouseBindingPreset::Default => (Right.into(), Middle.into()),
+            MouseBindingPreset::Blender => (Middle.into(), MouseBinding::new(Middle).with_modifier(DragModifier::Shift)),
+            MouseBindingPreset::Maya => (
+                MouseBinding::new(Left).with_modifier(DragModifier::Alt),
+                MouseBinding::new(Middle).with_modifier(DragModifier::Alt),
+            ),
+            MouseBindingPreset::WebOrbit => (Left.into(), Right.into()),
+        }
     }
 }
 
+/// Default angle of one keyboard snap orbit step
+const DEFAULT_SNAP_ORBIT_STEP_DEGREES: f32 = 15.0;
+
 #[derive(Debug)]
 pub struct CameraController {
     // Keyboard panning
@@ -250,18 +635,25 @@ pub struct CameraController {
     // Mouse panning
     mouse_pan_x: f32,
     mouse_pan_y: f32,
-    is_panning: bool,      // Track if user is currently panning (middle button pressed)
+    is_panning: bool,      // Track if user is currently panning (pan binding held)
     
-    // Mouse orbital rotation
-    mouse_delta_x: f32,
-    mouse_delta_y: f32,
-    is_orbiting: bool,     // Track if user is currently orbiting (right button pressed)
+    // Mouse orbital rotation, accumulated in radians until the next update
+    orbit_yaw: f32,
+    orbit_pitch: f32,
+    is_orbiting: bool,     // Track if user is currently orbiting (orbit binding held)
     
-    // Orbit mode control
-    alt_pressed: bool,     // Common in 3D software: Alt key for orbit mode
+    // Mouse bindings and the modifier keys they may require
+    orbit_binding: MouseBinding,
+    pan_binding: MouseBinding,
+    alt_pressed: bool,
+    shift_pressed: bool,
+    ctrl_pressed: bool,
     
     // Zoom
     scroll: f32,
+    zoom_anchor: Option<Point3<f32>>, // Surface point under the cursor to zoom toward
+    min_zoom: f32,         // Closest distance to the target
+    max_zoom: f32,         // Farthest distance from the target
     
     // Control parameters
     speed: f32,            // General movement speed
@@ -269,8 +661,15 @@ pub struct CameraController {
     orbit_speed: f32,      // Speed multiplier for orbit rotation
     zoom_speed: f32,       // Zoom speed factor
     orbit_invert_y: bool,  // Whether to invert Y axis for orbiting (common option in 3D software)
-    max_rotation_per_frame: f32, // Maximum rotation angle per frame in radians
-    reset_camera_pressed: bool, // Flag to reset camera to initial position
+    max_rotation_per_event: f32, // Maximum rotation angle per mouse motion event in radians
+    reset_camera_pressed: bool, // Flag to return camera to the home view
+    factory_reset_pressed: bool, // Flag to reset camera to the initial startup view
+    orbit_locked: bool,    // Disable orbit rotation (e.g. in the top-down 2D view)
+    snap_orbit_step: Rad<f32>, // Angle of one keyboard snap orbit step
+    snap_steps_yaw: i32,   // Pending snap steps around the up axis
+    snap_steps_pitch: i32, // Pending snap steps around the horizontal axis
+    requested_view: Option<StandardView>, // Standard view picked on the numpad, applied on the next update
+    mode: ControllerMode,  // Orbit around the target or fly through the scene
 }
 
 impl CameraController {
@@ -283,18 +682,32 @@ impl CameraController {
             mouse_pan_x: 0.0,
             mouse_pan_y: 0.0,
             is_panning: false,
-            mouse_delta_x: 0.0,
-            mouse_delta_y: 0.0,
+            orbit_yaw: 0.0,
+            orbit_pitch: 0.0,
             is_orbiting: false,
+            orbit_binding: MouseBindingPreset::Default.bindings().0,
+            pan_binding: MouseBindingPreset::Default.bindings().1,
             alt_pressed: false,
+            shift_pressed: false,
+            ctrl_pressed: false,
             scroll: 0.0,
+            zoom_anchor: None,
+            min_zoom: DEFAULT_MIN_ZOOM_DISTANCE,
+            max_zoom: DEFAULT_MAX_ZOOM_DISTANCE,
             speed,
             sensitivity,
             orbit_speed: 1.5,    // Increased orbit speed for responsive control
             zoom_speed: 0.05,    // Reduced for softer zoom
             orbit_invert_y: false, // Standard behavior in most 3D software
-            max_rotation_per_frame: 0.1, // Limit to about 5.7 degrees per frame
+            max_rotation_per_event: 0.1, // Limit to about 5.7 degrees per motion event
             reset_camera_pressed: false,
+            factory_reset_pressed: false,
+            orbit_locked: false,
+            snap_orbit_step: Deg(DEFAULT_SNAP_ORBIT_STEP_DEGREES).into(),
+            snap_steps_yaw: 0,
+            snap_steps_pitch: 0,
+            requested_view: None,
+            mode: ControllerMode::default(),
         }
     }
 
@@ -318,37 +731,198 @@ impl CameraController {
                 self.amount_right = amount;
                 true
             }
-            // 'C' key to reset/recenter camera to initial position
+            // 'C' key to return the camera to the home view
             KeyCode::KeyC => {
                 if state == ElementState::Pressed {
                     self.reset_camera_pressed = true;
                 }
                 true
             }
-            // Alt key for orbit mode (common in 3D software)
+            // 'R' key to reset the camera to the initial startup view
+            KeyCode::KeyR => {
+                if state == ElementState::Pressed {
+                    self.factory_reset_pressed = true;
+                }
+                true
+            }
+            // Numpad 4/6 and 8/2 snap-orbit by a fixed angle (as in Blender)
+            KeyCode::Numpad4 | KeyCode::Numpad6 | KeyCode::Numpad8 | KeyCode::Numpad2 => {
+                if state == ElementState::Pressed {
+                    match key {
+                        KeyCode::Numpad4 => self.snap_steps_yaw -= 1,
+                        KeyCode::Numpad6 => self.snap_steps_yaw += 1,
+                        KeyCode::Numpad8 => self.snap_steps_pitch += 1,
+                        _ => self.snap_steps_pitch -= 1,
+                    }
+                }
+                true
+            }
+            // Numpad 1/3/7 look from the front, right and top, with Ctrl from the opposite side,
+            // and numpad 5 from the isometric corner (as in Blender)
+            KeyCode::Numpad1 | KeyCode::Numpad3 | KeyCode::Numpad7 | KeyCode::Numpad5 => {
+                if state == ElementState::Pressed {
+                    self.requested_view = Some(match (key, self.ctrl_pressed) {
+                        (KeyCode::Numpad1, false) => StandardView::Front,
+                        (KeyCode::Numpad1, true) => StandardView::Back,
+                        (KeyCode::Numpad3, false) => StandardView::Right,
+                        (KeyCode::Numpad3, true) => StandardView::Left,
+                        (KeyCode::Numpad7, false) => StandardView::Top,
+                        (KeyCode::Numpad7, true) => StandardView::Bottom,
+                        _ => StandardView::Iso,
+                    });
+                }
+                true
+            }
+            KeyCode::ControlLeft | KeyCode::ControlRight => {
+                self.ctrl_pressed = state == ElementState::Pressed;
+                true
+            }
+            // Modifier keys for mouse bindings such as Alt + left drag (Maya) or Shift + middle drag (Blender)
             KeyCode::AltLeft | KeyCode::AltRight => {
                 self.alt_pressed = state == ElementState::Pressed;
                 true
             }
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => {
+                self.shift_pressed = state == ElementState::Pressed;
+                true
+            }
             _ => false,
         }
     }
     
+    /// Enable or disable orbit rotation; pan and zoom remain available
+    pub fn set_orbit_locked(&mut self, locked: bool) {
+        self.orbit_locked = locked;
+        self.orbit_yaw = 0.0;
+        self.orbit_pitch = 0.0;
+        self.snap_steps_yaw = 0;
+        self.snap_steps_pitch = 0;
+    }
+
+    /// Current controller mode
+    pub fn mode(&self) -> ControllerMode {
+        self.mode
+    }
+
+    /// Switch between orbit and fly controls, dropping any pending rotation
+    pub fn set_mode(&mut self, mode: ControllerMode) {
+        self.mode = mode;
+        self.orbit_yaw = 0.0;
+        self.orbit_pitch = 0.0;
+    }
+
+    /// Mouse button (and modifier) that orbits, or looks around in fly mode
+    pub fn set_orbit_button(&mut self, binding: impl Into<MouseBinding>) {
+        self.orbit_binding = binding.into();
+        self.is_orbiting = false;
+    }
+
+    /// Mouse button (and modifier) that pans
+    pub fn set_pan_button(&mut self, binding: impl Into<MouseBinding>) {
+        self.pan_binding = binding.into();
+        self.is_panning = false;
+    }
+
+    /// Use the orbit and pan bindings of `preset`
+    pub fn set_mouse_preset(&mut self, preset: MouseBindingPreset) {
+        let (orbit, pan) = preset.bindings();
+        self.set_orbit_button(orbit);
+        self.set_pan_button(pan);
+    }
+
+    pub fn orbit_button(&self) -> MouseBinding {
+        self.orbit_binding
+    }
+
+    pub fn pan_button(&self) -> MouseBinding {
+        self.pan_binding
+    }
+
+    /// Whether `binding` is on `button` and its modifier, if any, is held
+    fn binding_matches(&self, binding: MouseBinding, button: MouseButton) -> bool {
+        binding.button == button
+            && match binding.modifier {
+                None => true,
+                Some(DragModifier::Shift) => self.shift_pressed,
+                Some(DragModifier::Alt) => self.alt_pressed,
+            }
+    }
+
+    /// Whether the orbit mouse button is held
+    pub fn is_orbiting(&self) -> bool {
+        self.is_orbiting
+    }
+
+    /// Whether the pan mouse button is held
+    pub fn is_panning(&self) -> bool {
+        self.is_panning
+    }
+
+    /// Fly mode applies unless orbit is locked (top-down view), which keeps pan/zoom controls
+    fn flying(&self) -> bool {
+        self.mode == ControllerMode::Fly && !self.orbit_locked
+    }
+
+    /// Whether keys, mouse drags, snap steps or the wheel are moving the camera this update
+    fn has_camera_input(&self) -> bool {
+        self.amount_right != self.amount_left
+            || self.amount_up != self.amount_down
+            || (self.is_panning && (self.mouse_pan_x != 0.0 || self.mouse_pan_y != 0.0))
+            || (self.is_orbiting && !self.orbit_locked && (self.orbit_yaw != 0.0 || self.orbit_pitch != 0.0))
+            || (!self.orbit_locked && (self.snap_steps_yaw != 0 || self.snap_steps_pitch != 0))
+            || self.scroll != 0.0
+    }
+
+    /// Closest distance to the target the camera zooms to (0.5 by default)
+    pub fn min_zoom(&self) -> f32 {
+        self.min_zoom
+    }
+
+    /// Farthest distance from the target the camera zooms to (100 by default)
+    pub fn max_zoom(&self) -> f32 {
+        self.max_zoom
+    }
+
+    /// Set the closest zoom distance, raising the farthest one if it is below
+    pub fn set_min_zoom(&mut self, distance: f32) {
+        self.min_zoom = distance.max(f32::EPSILON);
+        self.max_zoom = self.max_zoom.max(self.min_zoom);
+    }
+
+    /// Set the farthest zoom distance, e.g. for large architectural models, lowering the closest one if it is above
+    pub fn set_max_zoom(&mut self, distance: f32) {
+        self.max_zoom = distance.max(f32::EPSILON);
+        self.min_zoom = self.min_zoom.min(self.max_zoom);
+    }
+
+    /// Clamp a camera distance to the zoom limits
+    pub fn clamp_zoom(&self, distance: f32) -> f32 {
+        distance.clamp(self.min_zoom, self.max_zoom)
+    }
+
+    /// Set the angle of one keyboard snap orbit step (15 degrees by default)
+    pub fn set_snap_orbit_step<A: Into<Rad<f32>>>(&mut self, step: A) {
+        self.snap_orbit_step = step.into();
+    }
+
     // Process mouse movement for orbit and panning based on which mouse button is pressed
     pub fn process_mouse(&mut self, mouse_dx: f64, mouse_dy: f64) {
         if self.is_orbiting {
-            // Standard 3D modeling software orbit behavior with right mouse button
-            self.mouse_delta_x = mouse_dx as f32;
-            // Apply Y inversion if enabled (common option in 3D software)
-            self.mouse_delta_y = if self.orbit_invert_y {
-                -mouse_dy as f32
-            } else {
-                mouse_dy as f32
-            };
+            // Standard 3D modeling software orbit behavior while the orbit binding is held.
+            // Orbit is positional: each pixel of mouse motion turns the camera by a fixed
+            // angle, however many frames the motion is spread over. Events are accumulated
+            // and consumed by the next update, so no motion is lost or applied twice, and
+            // the clamp only limits single jumps (e.g. cursor warps), not the frame rate.
+            let radians_per_pixel = self.orbit_speed * self.sensitivity / ORBIT_REFERENCE_RATE;
+            let max = self.max_rotation_per_event;
+            self.orbit_yaw += (mouse_dx as f32 * radians_per_pixel).clamp(-max, max);
+            // Dragging up tilts the view down unless Y inversion is enabled (common option in 3D software)
+            let pitch = if self.orbit_invert_y { mouse_dy as f32 } else { -mouse_dy as f32 };
+            self.orbit_pitch += (pitch * radians_per_pixel).clamp(-max, max);
         }
         
         if self.is_panning {
-            // Middle-click drag pans the camera (standard in 3D modeling software)
+            // Dragging with the pan binding pans the camera (standard in 3D modeling software)
             self.mouse_pan_x = mouse_dx as f32;
             self.mouse_pan_y = mouse_dy as f32;
         }
@@ -356,33 +930,39 @@ impl CameraController {
     
     // Process mouse button presses
     pub fn process_mouse_button(&mut self, state: ElementState, button: MouseButton) -> bool {
-        match button {
-            // Right mouse button controls orbit rotation (standard in 3D modeling software)
-            MouseButton::Right => {
-                self.is_orbiting = state == ElementState::Pressed;
-                if !self.is_orbiting {
-                    // Reset orbit values when released
-                    self.mouse_delta_x = 0.0;
-                    self.mouse_delta_y = 0.0;
-                }
-                return true;
-            },
-            // Middle mouse button controls panning (standard in 3D modeling software)
-            MouseButton::Middle => {
-                self.is_panning = state == ElementState::Pressed;
-                if !self.is_panning {
-                    // Reset pan values when released
-                    self.mouse_pan_x = 0.0;
-                    self.mouse_pan_y = 0.0;
-                }
-                return true;
-            },
-            _ => false,
+        if state == ElementState::Pressed {
+            let orbit = self.binding_matches(self.orbit_binding, button);
+            let pan = self.binding_matches(self.pan_binding, button);
+            // On a shared button the binding with a modifier wins, e.g. Blender's Shift + middle pan
+            let start_pan = pan && (!orbit || self.pan_binding.modifier.is_some());
+            let start_orbit = orbit && !start_pan;
+            self.is_orbiting |= start_orbit;
+            self.is_panning |= start_pan;
+            return start_orbit || start_pan;
+        }
+
+        // Releasing the button ends its drag, whichever modifiers are held by then
+        let mut handled = false;
+        if button == self.orbit_binding.button {
+            // Reset orbit values when released
+            self.is_orbiting = false;
+            self.orbit_yaw = 0.0;
+            self.orbit_pitch = 0.0;
+            handled = true;
         }
+        if button == self.pan_binding.button {
+            // Reset pan values when released
+            self.is_panning = false;
+            self.mouse_pan_x = 0.0;
+            self.mouse_pan_y = 0.0;
+            handled = true;
+        }
+        handled
     }
 
     // Process scroll wheel for zoom
     pub fn process_scroll(&mut self, delta: &MouseScrollDelta) {
+        self.zoom_anchor = None;
         self.scroll = match delta {
             // Reduce scroll multiplier for softer zoom
             MouseScrollDelta::LineDelta(_, scroll) => -*scroll * 1.0,
@@ -390,15 +970,57 @@ impl CameraController {
         };
     }
 
+    /// Process a scroll that zooms toward `point` (the surface under the cursor) instead of the target
+    pub fn process_scroll_toward(&mut self, delta: &MouseScrollDelta, point: Point3<f32>) {
+        self.process_scroll(delta);
+        self.zoom_anchor = Some(point);
+    }
+
+    /// Apply a pending zoom to an orthographic projection's scale instead of the camera distance
+    ///
+    /// The eye distance does not change an orthographic image, so the scroll scales the visible
+    /// height; zooming toward the cursor also shifts the view to keep that point in place. Call
+    /// before `update_camera`, which zooms perspective projections.
+    pub fn update_projection(&mut self, camera: &mut Camera, projection: &mut Projection) {
+        let ProjectionMode::Orthographic { scale } = projection.mode else {
+            return;
+        };
+        if self.scroll == 0.0 {
+            return;
+        }
+        // Zooming takes over from a running transition
+        camera.stop_animation();
+        let factor = 1.0 + std::mem::take(&mut self.scroll) * self.zoom_speed;
+        // The zoom limits, as the heights a perspective view shows at those distances
+        let new_scale = (scale * factor)
+            .clamp(projection.view_height_at(self.min_zoom), projection.view_height_at(self.max_zoom));
+        if let Some(anchor) = self.zoom_anchor.take() {
+            camera.shift_toward(anchor, new_scale / scale);
+        }
+        projection.mode = ProjectionMode::Orthographic { scale: new_scale };
+    }
+
     // Update the professional orbit camera - Z-up turntable style (Blender/Maya)
     pub fn update_camera(&mut self, camera: &mut Camera, dt: Duration) {
         let dt = dt.as_secs_f32();
+
+        // Camera input takes over from a running transition, leaving the camera where it got to.
+        // The transition moves on before new views are requested below, so one started this
+        // frame does not skip ahead by a long first `dt` (e.g. after an idle frame)
+        if self.has_camera_input() {
+            camera.stop_animation();
+        }
+        camera.advance_animation(dt);
         
-        // Handle keyboard panning (WASD/arrow keys)
+        // Handle keyboard panning (WASD/arrow keys); in fly mode W/S move forward/back instead
         let key_pan_right = (self.amount_right - self.amount_left) * self.speed * dt;
         let key_pan_up = (self.amount_up - self.amount_down) * self.speed * dt;
         if key_pan_right != 0.0 || key_pan_up != 0.0 {
-            camera.pan(key_pan_right, key_pan_up);
+            if self.flying() {
+                camera.fly(key_pan_up, key_pan_right);
+            } else {
+                camera.pan(key_pan_right, key_pan_up);
+            }
         }
         
         // Handle mouse panning (middle button drag)
@@ -414,71 +1036,73 @@ impl CameraController {
         }
         
         // Handle orbit rotation (right button drag) - Z-up turntable style
-        if self.is_orbiting && (self.mouse_delta_x != 0.0 || self.mouse_delta_y != 0.0) {
+        if self.is_orbiting && !self.orbit_locked && (self.orbit_yaw != 0.0 || self.orbit_pitch != 0.0) {
             // In Z-up turntable mode (like Blender/Maya):
             // X mouse movement -> rotate around Z world axis (yaw)
             // Y mouse movement -> rotate around horizontal axis (pitch)
             
-            // Apply orbit with configured sensitivity
-            let orbit_multiplier = self.orbit_speed * self.sensitivity * dt;
-            
-            // Calculate raw delta values with clamping
-            let yaw_delta = (self.mouse_delta_x * orbit_multiplier)
-                .clamp(-self.max_rotation_per_frame, self.max_rotation_per_frame);
+            // Consume the rotation accumulated from mouse motion since the last update;
+            // it is already an angle, so it is not scaled by `dt`
+            let yaw_delta = std::mem::take(&mut self.orbit_yaw);
+            let pitch_delta = std::mem::take(&mut self.orbit_pitch);
                 
-            // Calculate pitch delta with inversion if configured
-            let pitch_delta = if self.orbit_invert_y {
-                self.mouse_delta_y * orbit_multiplier
+            if self.flying() {
+                // Dragging right turns right and dragging up looks up
+                camera.look_around(Rad(yaw_delta), Rad(pitch_delta));
             } else {
-                -self.mouse_delta_y * orbit_multiplier
-            };
-            
-            // Clamp pitch delta as well
-            let pitch_delta = pitch_delta
-                .clamp(-self.max_rotation_per_frame, self.max_rotation_per_frame);
-                
-            // In a quaternion orbit system with reference frame tracking:
-            // 1. Yaw rotates around world up (Z) - unchanged
-            // 2. Pitch rotates around reference frame's tracked right vector
-            
-            // First, create quaternions for the rotations
-            let yaw_rotation = Quaternion::from_axis_angle(camera.world_up, Rad(yaw_delta));
-            
-            // Instead of computing the right vector from orientation,
-            // use the tracked reference right vector for stable pitch rotation
-            let right = camera.last_right;
-            
-            // Create pitch rotation around tracked right vector
-            let pitch_rotation = Quaternion::from_axis_angle(right.normalize(), Rad(pitch_delta));
-            
-            // Apply rotations to camera orientation (pitch then yaw)
-            // Order matters: yaw * (pitch * orientation) gives proper turntable feel
-            camera.orientation = yaw_rotation * pitch_rotation * camera.orientation;
-            
-            // Keep quaternion normalized to prevent drift
-            camera.orientation = camera.orientation.normalize();
-            
-            // Update camera position after rotation
-            camera.update_position();
+                camera.orbit(Rad(yaw_delta), Rad(pitch_delta));
+            }
+        }
+
+        // Apply keyboard snap orbit steps (numpad 4/6/8/2) as exact angles
+        if !self.orbit_locked && (self.snap_steps_yaw != 0 || self.snap_steps_pitch != 0) {
+            let yaw = self.snap_orbit_step * std::mem::take(&mut self.snap_steps_yaw) as f32;
+            let pitch = self.snap_orbit_step * std::mem::take(&mut self.snap_steps_pitch) as f32;
+            if self.flying() {
+                camera.look_around(yaw, pitch);
+            } else {
+                camera.orbit(yaw, pitch);
+            }
         }
         
+        // Jump to a standard view picked on the numpad; not in the top-down view, whose orbit is locked
+        if let Some(view) = self.requested_view.take() {
+            if !self.orbit_locked {
+                camera.set_view(view);
+            }
+        }
+
         // Handle zooming with scroll wheel (standard in all 3D software)
-        if self.scroll != 0.0 {
+        if self.scroll != 0.0 && self.flying() {
+            // No fixed target to zoom towards in fly mode, so the wheel moves the eye forward/back
+            camera.fly(-self.scroll * self.zoom_speed * camera.distance, 0.0);
+            self.scroll = 0.0;
+        } else if let Some(anchor) = self.zoom_anchor.take().filter(|_| self.scroll != 0.0) {
+            let distance = self.clamp_zoom(camera.distance * (1.0 + self.scroll * self.zoom_speed));
+            camera.zoom_toward(anchor, distance / camera.distance);
+            self.scroll = 0.0;
+        } else if self.scroll != 0.0 {
             // Adjust distance with scroll (zoom in/out) with softer effect
             camera.distance *= 1.0 + self.scroll * self.zoom_speed;
             
             // Ensure camera doesn't get too close or too far
-            camera.distance = camera.distance.max(MIN_ZOOM_DISTANCE).min(MAX_ZOOM_DISTANCE);
+            camera.distance = self.clamp_zoom(camera.distance);
             
             // Reset scroll and update position
             self.scroll = 0.0;
             camera.update_position();
         }
         
-        // Handle camera reset (c key)
+        // Handle camera reset to the home view (c key)
         if self.reset_camera_pressed {
-            camera.reset_to_initial();
+            camera.reset_to_home();
             self.reset_camera_pressed = false;
         }
+
+        // Handle factory reset to the startup view (r key)
+        if self.factory_reset_pressed {
+            camera.reset_to_initial();
+            self.factory_reset_pressed = false;
+        }
     }
 }
@@ -1,4 +1,10 @@
-use crate::model::{LineVertex, LineModel};
+use crate::model_line::{LineModel, LineVertex, WideLineModel, LINE_WIDTH};
+
+// Reference grid layout, shared by the grid geometry and its bounds
+const GRID_SIZE: usize = 10; // 10x10 grid
+const GRID_SPACING: f32 = 1.0; // 1 unit spacing
+const AXIS_LENGTH: f32 = 5.0;
+const AXIS_ELEVATION: f32 = 0.02; // A slight elevation to make the axes more visible
 
 /// A simple line segment with start and end points and color
 pub struct Line {
@@ -17,8 +23,8 @@ impl Line {
         }
     }
     
-    /// Converts an array of Lines to a LineModel
-    pub fn create_line_model(device: &wgpu::Device, lines: &[Line]) -> LineModel {
+    /// Converts an array of Lines to opaque line list vertices
+    pub fn to_vertices(lines: &[Line]) -> Vec<LineVertex> {
         let mut vertices = Vec::with_capacity(lines.len() * 2);
         
         // Process each line into vertices
@@ -26,85 +32,178 @@ impl Line {
             // Add start vertex
             vertices.push(LineVertex {
                 position: line.start,
-                color: line.color,
+                color: [line.color[0], line.color[1], line.color[2], 1.0],
             });
             
             // Add end vertex
             vertices.push(LineVertex {
                 position: line.end,
-                color: line.color,
+                color: [line.color[0], line.color[1], line.color[2], 1.0],
             });
         }
         
+        vertices
+    }
+
+    /// Converts an array of Lines to a LineModel
+    pub fn create_line_model(device: &wgpu::Device, lines: &[Line]) -> LineModel {
         // Create the line model
-        LineModel::new(device, "line_model", &vertices)
+        LineModel::new(device, "line_model", &Self::to_vertices(lines))
     }
 }
 
-/// Creates a 10x10 grid of lines on the XZ plane with 1 unit spacing, centered at origin
-pub fn create_grid_lines(device: &wgpu::Device) -> LineModel {
+/// Which parts of the reference grid are built; everything is shown by default
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GridVisibility {
+    pub grid_x_lines: bool, // Grid lines parallel to the X axis
+    pub grid_y_lines: bool, // Grid lines parallel to the Y axis
+    pub x_axis: bool,
+    pub y_axis: bool,
+    pub z_axis: bool, // The vertical axis
+}
+
+impl Default for GridVisibility {
+    fn default() -> Self {
+        Self { grid_x_lines: true, grid_y_lines: true, x_axis: true, y_axis: true, z_axis: true }
+    }
+}
+
+impl GridVisibility {
+    /// Grid lines without the axes
+    pub fn grid_only() -> Self {
+        Self { x_axis: false, y_axis: false, z_axis: false, ..Self::default() }
+    }
+
+    /// Axes without the grid lines
+    pub fn axes_only() -> Self {
+        Self { grid_x_lines: false, grid_y_lines: false, ..Self::default() }
+    }
+
+    /// Whether anything is left to draw
+    pub fn any(&self) -> bool {
+        self.grid_x_lines || self.grid_y_lines || self.x_axis || self.y_axis || self.z_axis
+    }
+
+    /// Next preset for the grid key: everything, no vertical axis, axes only, grid only
+    ///
+    /// Custom combinations go back to everything.
+    pub fn next_preset(self) -> Self {
+        let presets = [
+            Self::default(),
+            Self { z_axis: false, ..Self::default() },
+            Self::axes_only(),
+            Self::grid_only(),
+        ];
+        let index = presets.iter().position(|preset| *preset == self);
+        index.map_or(presets[0], |index| presets[(index + 1) % presets.len()])
+    }
+}
+
+/// Default grid tint: uniform grey
+pub fn plain_grid_color(_position: [f32; 3]) -> [f32; 3] {
+    [0.7, 0.7, 0.7]
+}
+
+/// Creates a 10x10 grid of lines on the ground plane with 1 unit spacing, centered on the up (Z) axis
+///
+/// `color_fn` gives the color of every grid vertex from its position in the grid plane
+/// (before `height` is applied), e.g. `plain_grid_color` or a tint fading with the
+/// distance from the center. Grid lines are split at every crossing so gradients follow
+/// the cells. The colored axes are not affected. The grid and its axes are lifted by
+/// `height` along the world up axis, e.g. to sit under a model whose base is not at 0.
+/// Only the grid lines and axes enabled in `visibility` are built.
+pub fn create_grid_lines(
+    device: &wgpu::Device,
+    color_fn: impl Fn([f32; 3]) -> [f32; 3],
+    height: f32,
+    visibility: GridVisibility,
+) -> WideLineModel {
     let mut lines = Vec::new();
+    let mut vertices = Vec::new();
     
     // Define grid parameters
-    let grid_size = 10; // 10x10 grid
-    let grid_spacing = 1.0; // 1 unit spacing
+    let grid_size = GRID_SIZE;
+    let grid_spacing = GRID_SPACING;
     
     // Calculate grid start and end to center the grid
     let half_size = (grid_size as f32 * grid_spacing) / 2.0;
     let grid_start = -half_size;
-    let grid_end = half_size;
     
     // Define colors for each axis
     let x_axis_color = [1.0, 0.0, 0.0]; // Red for X axis
     let y_axis_color = [0.0, 1.0, 0.0]; // Green for Y axis
     let z_axis_color = [0.0, 0.0, 1.0]; // Blue for Z axis
-    let grid_color = [0.7, 0.7, 0.7]; // Grey color for grid lines
     
     // A slight elevation to make the axes more visible
-    let axis_elevation = 0.02;
+    let axis_elevation = AXIS_ELEVATION;
     
-    // Create grid lines along X and Z axes with grey color for all of them
+    // Create grid lines along X and Z axes, one segment per cell between shared crossing
+    // vertices colored per vertex; vertex (i, j) sits at index i * (grid_size + 1) + j
     for i in 0..=grid_size {
-        let pos = grid_start + (i as f32 * grid_spacing);
-        
-        // Lines parallel to X axis (varying Z)
-        lines.push(Line::new(
-            [pos, grid_start, 0.0], 
-            [pos, grid_end, 0.0],
-            grid_color
-        ));
-        
-        // Lines parallel to Z axis (varying X)
-        lines.push(Line::new(
-            [grid_start, pos, 0.0], 
-            [grid_end, pos, 0.0],
-            grid_color
-        ));
+        for j in 0..=grid_size {
+            let position = [grid_start + i as f32 * grid_spacing, grid_start + j as f32 * grid_spacing, 0.0];
+            let [r, g, b] = color_fn(position);
+            vertices.push(LineVertex::new(position, [r, g, b, 1.0]));
+        }
+    }
+    let crossing = |i: usize, j: usize| (i * (grid_size + 1) + j) as u32;
+    let mut indices = Vec::new();
+    for i in 0..=grid_size {
+        for j in 0..grid_size {
+            // Lines parallel to Y axis (varying Y)
+            if visibility.grid_y_lines {
+                indices.extend([crossing(i, j), crossing(i, j + 1)]);
+            }
+            
+            // Lines parallel to X axis (varying X)
+            if visibility.grid_x_lines {
+                indices.extend([crossing(j, i), crossing(j + 1, i)]);
+            }
+        }
     }
     
     // Add X axis (red) from origin extending in positive X
-    lines.push(Line::new(
-        [0.0, 0.0, axis_elevation],  // start at origin, slightly elevated
-        [5.0, 0.0, axis_elevation],  // extend 5 units along positive X axis
-        x_axis_color
-    ));
+    if visibility.x_axis {
+        lines.push(Line::new(
+            [0.0, 0.0, axis_elevation],  // start at origin, slightly elevated
+            [AXIS_LENGTH, 0.0, axis_elevation],  // extend 5 units along positive X axis
+            x_axis_color
+        ));
+    }
     
     // Add Y axis (green) extending upward from origin
-    lines.push(Line::new(
-        [0.0, 0.0, axis_elevation],           // start at origin
-        [0.0, 5.0, axis_elevation],           // extend 5 units up along Y axis
-        y_axis_color
-    ));
+    if visibility.y_axis {
+        lines.push(Line::new(
+            [0.0, 0.0, axis_elevation],           // start at origin
+            [0.0, AXIS_LENGTH, axis_elevation],           // extend 5 units up along Y axis
+            y_axis_color
+        ));
+    }
     
     // Add Z axis (blue) extending in positive Z
-    lines.push(Line::new(
-        [0.0, 0.0, axis_elevation], // start at origin, slightly elevated
-        [0.0, 0.0, 5.0+axis_elevation], // extend 5 units along positive Z axis
-        z_axis_color
-    ));
+    if visibility.z_axis {
+        lines.push(Line::new(
+            [0.0, 0.0, axis_elevation], // start at origin, slightly elevated
+            [0.0, 0.0, AXIS_LENGTH + axis_elevation], // extend 5 units along positive Z axis
+            z_axis_color
+        ));
+    }
     
-    // Convert lines to a LineModel
-    Line::create_line_model(device, &lines)
+    // Convert grid and axes to screen-space quads drawn with the faded grid pipeline; their
+    // width comes from `GlobalsUniform::grid_line_width`, not from the vertices
+    let axis_start = vertices.len() as u32;
+    vertices.extend(Line::to_vertices(&lines));
+    indices.extend(axis_start..vertices.len() as u32);
+    for vertex in &mut vertices {
+        vertex.position[2] += height;
+    }
+    WideLineModel::new_indexed(device, "line_model", &vertices, &indices, LINE_WIDTH)
+}
+
+/// Axis-aligned bounds (min, max) of the reference grid and its axes at plane `height`
+pub fn grid_bounds(height: f32) -> ([f32; 3], [f32; 3]) {
+    let half_size = (GRID_SIZE as f32 * GRID_SPACING) / 2.0;
+    ([-half_size, -half_size, height], [half_size, half_size, height + AXIS_LENGTH + AXIS_ELEVATION])
 }
 
 /// Creates coordinate system axes
@@ -137,27 +236,24 @@ pub fn create_axes(device: &wgpu::Device, size: f32, origin: [f32; 3], colors: [
 
 /// Creates a 3D boundary box from min/max corners
 pub fn create_boundary_box(device: &wgpu::Device, min: [f32; 3], max: [f32; 3], color: [f32; 3]) -> LineModel {
-    let mut lines = Vec::new();
-    
-    // Bottom face edges
-    lines.push(Line::new([min[0], min[1], min[2]], [max[0], min[1], min[2]], color));
-    lines.push(Line::new([max[0], min[1], min[2]], [max[0], min[1], max[2]], color));
-    lines.push(Line::new([max[0], min[1], max[2]], [min[0], min[1], max[2]], color));
-    lines.push(Line::new([min[0], min[1], max[2]], [min[0], min[1], min[2]], color));
-    
-    // Top face edges
-    lines.push(Line::new([min[0], max[1], min[2]], [max[0], max[1], min[2]], color));
-    lines.push(Line::new([max[0], max[1], min[2]], [max[0], max[1], max[2]], color));
-    lines.push(Line::new([max[0], max[1], max[2]], [min[0], max[1], max[2]], color));
-    lines.push(Line::new([min[0], max[1], max[2]], [min[0], max[1], min[2]], color));
+    // Corner i takes max along X if bit 0 is set, Y for bit 1 and Z for bit 2
+    let vertices: Vec<LineVertex> = (0..8)
+        .map(|i| {
+            let corner = [0, 1, 2].map(|axis| if i & (1 << axis) != 0 { max[axis] } else { min[axis] });
+            LineVertex::new(corner, [color[0], color[1], color[2], 1.0])
+        })
+        .collect();
     
-    // Vertical edges
-    lines.push(Line::new([min[0], min[1], min[2]], [min[0], max[1], min[2]], color));
-    lines.push(Line::new([max[0], min[1], min[2]], [max[0], max[1], min[2]], color));
-    lines.push(Line::new([max[0], min[1], max[2]], [max[0], max[1], max[2]], color));
-    lines.push(Line::new([min[0], min[1], max[2]], [min[0], max[1], max[2]], color));
+    let indices: [u32; 24] = [
+        // Bottom face edges
+        0, 1, 1, 5, 5, 4, 4, 0,
+        // Top face edges
+        2, 3, 3, 7, 7, 6, 6, 2,
+        // Vertical edges
+        0, 2, 1, 3, 5, 7, 4, 6,
+    ];
     
-    Line::create_line_model(device, &lines)
+    LineModel::new_indexed(device, "boundary_box", &vertices, &indices)
 }
 
 /// Creates lines approximating a parametric curve
@@ -202,21 +298,15 @@ pub fn create_helix(device: &wgpu::Device, radius: f32, height: f32, turns: f32,
         ]
     };
     
-    let mut lines = Vec::new();
+    // One vertex per point along the helix, with a gradient color based on the position
+    let vertices: Vec<LineVertex> = (0..=total_segments)
+        .map(|i| {
+            let h = i as f32 / total_segments as f32;
+            LineVertex::new(helix(i as f32), [h, 1.0 - h, 0.5, 1.0])
+        })
+        .collect();
     
-    // Create lines connecting points along the helix
-    for i in 0..total_segments {
-        let p1 = helix(i as f32);
-        let p2 = helix((i + 1) as f32);
-        
-        // Create a gradient color based on the position along the helix
-        let h = i as f32 / total_segments as f32;
-        let color = [h, 1.0 - h, 0.5];
-        
-        lines.push(Line::new(p1, p2, color));
-    }
-    
-    Line::create_line_model(device, &lines)
+    LineModel::new_indexed(device, "helix", &vertices, &polyline_indices(vertices.len()))
 }
 
 /// Creates a 3D helix polyline with the lines array approach
@@ -224,3 +314,51 @@ pub fn create_helix_polyline(device: &wgpu::Device) -> LineModel {
     // Create a helix with specific parameters
     create_helix(device, 3.0, 10.0, 5.0, 20)
 }
+
+/// Colormap from blue at the start of a trajectory (t = 0) to red at its end (t = 1)
+pub fn colormap_blue_to_red(t: f32) -> [f32; 4] {
+    let t = t.clamp(0.0, 1.0);
+    [t, 0.0, 1.0 - t, 1.0]
+}
+
+/// Viridis colormap from dark purple (t = 0) to yellow (t = 1) as a polynomial fit, in linear color
+/// like the vertex colors, e.g. for a legend of data colored with it
+pub fn colormap_viridis(t: f32) -> [f32; 4] {
+    const C: [[f32; 3]; 7] = [
+        [0.277_727_33, 0.005_407_344_5, 0.334_099_8],
+        [0.105_093_04, 1.404_613_5, 1.384_590_2],
+        [-0.330_861_83, 0.214_847_56, 0.095_095_16],
+        [-4.634_230_4, -5.799_101, -19.332_441],
+        [6.228_27, 14.179_933, 56.690_55],
+        [4.776_385, -13.745_145, -65.353_03],
+        [-5.435_456, 4.645_852_6, 26.312_435],
+    ];
+    let t = t.clamp(0.0, 1.0);
+    let channel = |i: usize| {
+        let srgb = C.iter().rev().fold(0.0, |acc, c| acc * t + c[i]);
+        srgb.clamp(0.0, 1.0).powf(2.2)
+    };
+    [channel(0), channel(1), channel(2), 1.0]
+}
+
+/// Creates a continuous polyline through `points`, colored by time along the path
+///
+/// Consecutive points are connected, and each vertex gets `colormap(t)` with
+/// `t = index / (points.len() - 1)`, so colors blend smoothly from start to end.
+pub fn create_trajectory(device: &wgpu::Device, points: &[[f32; 3]], colormap: impl Fn(f32) -> [f32; 4]) -> LineModel {
+    let last_index = points.len().saturating_sub(1).max(1) as f32;
+    let vertices: Vec<LineVertex> = points.iter()
+        .enumerate()
+        .map(|(i, &position)| LineVertex {
+            position,
+            color: colormap(i as f32 / last_index),
+        })
+        .collect();
+
+    LineModel::new_indexed(device, "trajectory", &vertices, &polyline_indices(vertices.len()))
+}
+
+/// Line list indices connecting `vertex_count` vertices in order into one polyline
+fn polyline_indices(vertex_count: usize) -> Vec<u32> {
+    (1..vertex_count as u32).flat_map(|i| [i - 1, i]).collect()
+}
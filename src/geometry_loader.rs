@@ -4,17 +4,17 @@
 //! It supports loading meshes, lines, points, pipes, and polygons from a
 //! standardized JSON format.
 
-use std::fs::File;
-use std::io::BufReader;
+use cgmath::{Deg, Matrix, Matrix3, Matrix4, Point3, SquareMatrix, Transform, Vector3};
 use serde::{Deserialize, Serialize};
 use wgpu::util::DeviceExt;
 use cfg_if::cfg_if;
 
 use crate::model::{Mesh, Model, ModelVertex};
 
-use crate::model_point::{PointVertex, QuadPointModel};
-use crate::model_pipe::{PipeSegment, PipeModel};
+use crate::model_point::{self, PointVertex, QuadPointModel};
+use crate::model_pipe::{PipeCapStyle, PipeSegment, PipeModel};
 use crate::model_polygon::{PolygonVertex, PolygonModel};
+use crate::model_line::{self, LineVertex, WideLineModel};
 // Texture module no longer used
 
 // Helper functions for tangent space calculation
@@ -50,6 +50,31 @@ fn cross_product(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
     ]
 }
 
+/// Smooth per-vertex normals: the area-weighted sum of the adjacent face normals
+///
+/// Face normals follow the counter-clockwise winding, like back-face culling, so a
+/// mesh marked with `flip_winding` gets outward normals once its indices are flipped.
+fn compute_smooth_normals(vertices: &[MeshVertexData], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![[0.0f32; 3]; vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+        if a >= vertices.len() || b >= vertices.len() || c >= vertices.len() {
+            continue;
+        }
+        let (pa, pb, pc) = (vertices[a].position, vertices[b].position, vertices[c].position);
+        let edge1 = [pb[0] - pa[0], pb[1] - pa[1], pb[2] - pa[2]];
+        let edge2 = [pc[0] - pa[0], pc[1] - pa[1], pc[2] - pa[2]];
+        // The cross product length is twice the triangle area, weighting larger faces more
+        let face_normal = cross_product(&edge1, &edge2);
+        for index in [a, b, c] {
+            for axis in 0..3 {
+                normals[index][axis] += face_normal[axis];
+            }
+        }
+    }
+    normals.iter().map(normalize).collect()
+}
+
 /// Normalize a vector to unit length
 fn normalize(v: &[f32; 3]) -> [f32; 3] {
     let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
@@ -65,7 +90,7 @@ fn normalize(v: &[f32; 3]) -> [f32; 3] {
 }
 
 // Main structure that contains all geometry data from JSON
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GeometryData {
     pub metadata: Metadata,
     pub meshes: Option<Vec<MeshData>>,
@@ -73,36 +98,368 @@ pub struct GeometryData {
     pub lines: Option<Vec<LineData>>,
     pub pipes: Option<Vec<PipeData>>,
     pub polygons: Option<Vec<PolygonData>>,
+    #[serde(skip)]
+    pub origin_offset: [f64; 3], // Subtracted from every position on load; add it back for original coordinates
+}
+
+/// Color of mesh vertices without a color, unless changed with `State::set_default_mesh_color`
+pub const DEFAULT_MESH_COLOR: [f32; 3] = [0.7, 0.7, 0.7];
+
+/// Distance from the origin beyond which f32 positions lose millimetre precision;
+/// scenes reaching past it are recentered on load unless `metadata.recenter` is false,
+/// as are OpenModel geometries (`model::openmodel_origin_offset`)
+pub(crate) const RECENTER_THRESHOLD: f32 = 10_000.0;
+
+impl GeometryData {
+    /// Whether positions should be recentered: as set by `metadata.recenter`, otherwise
+    /// when any coordinate is beyond `RECENTER_THRESHOLD`
+    fn needs_recentering(&self) -> bool {
+        self.metadata.recenter.unwrap_or_else(|| {
+            self.bounds().is_some_and(|(min, max)| {
+                min.iter().chain(&max).any(|coordinate| coordinate.abs() > RECENTER_THRESHOLD)
+            })
+        })
+    }
+
+    /// Rotate all positions and normals by `metadata.import_rotation`, if set
+    ///
+    /// The Euler angles are applied about X, then Y, then Z (world axes), e.g.
+    /// `[90, 0, 0]` converts Y-up data to this viewer's Z-up convention. The rotation
+    /// is baked once here so nothing is transformed per frame.
+    pub fn apply_import_rotation(&mut self) {
+        let Some([x, y, z]) = self.metadata.import_rotation else {
+            return;
+        };
+        let rotation = Matrix3::from_angle_z(Deg(z)) * Matrix3::from_angle_y(Deg(y)) * Matrix3::from_angle_x(Deg(x));
+        // A pure rotation is orthonormal, so normals rotate exactly like positions
+        let rotate = |v: &mut [f32; 3]| *v = (rotation * Vector3::from(*v)).into();
+
+        // Rotating (p - offset) equals rotating p minus the rotated offset
+        let [x, y, z] = [x as f64, y as f64, z as f64];
+        let rotation_f64 = Matrix3::from_angle_z(Deg(z)) * Matrix3::from_angle_y(Deg(y)) * Matrix3::from_angle_x(Deg(x));
+        self.origin_offset = (rotation_f64 * Vector3::from(self.origin_offset)).into();
+
+        self.for_each_vector(rotate, rotate);
+    }
+
+    /// Transform all positions by `matrix` and the normals, tangents and bitangents by its
+    /// inverse transpose, e.g. to place one file of a composed scene
+    ///
+    /// Point sizes and pipe radii are left unchanged.
+    pub fn transform(&mut self, matrix: Matrix4<f32>) {
+        let linear = Matrix3::from_cols(matrix.x.truncate(), matrix.y.truncate(), matrix.z.truncate());
+        let normal_matrix = linear.invert().map_or(linear, |inverse| inverse.transpose());
+        self.for_each_vector(
+            |position| *position = matrix.transform_point(Point3::from(*position)).into(),
+            |direction| *direction = normalize(&(normal_matrix * Vector3::from(*direction)).into()),
+        );
+    }
+
+    /// Call `position` with every position and `direction` with every normal, tangent and bitangent
+    fn for_each_vector(&mut self, position: impl Fn(&mut [f32; 3]), direction: impl Fn(&mut [f32; 3])) {
+        for mesh in self.meshes.iter_mut().flatten() {
+            for vertex in &mut mesh.vertices {
+                position(&mut vertex.position);
+                if let Some(normal) = &mut vertex.normal {
+                    direction(normal);
+                }
+                if let Some(tangent) = &mut vertex.tangent {
+                    direction(tangent);
+                }
+                if let Some(bitangent) = &mut vertex.bitangent {
+                    direction(bitangent);
+                }
+            }
+        }
+        for point_set in self.points.iter_mut().flatten() {
+            for vertex in &mut point_set.vertices {
+                position(&mut vertex.position);
+            }
+        }
+        for line_set in self.lines.iter_mut().flatten() {
+            for vertex in &mut line_set.vertices {
+                position(&mut vertex.position);
+            }
+        }
+        for pipe_set in self.pipes.iter_mut().flatten() {
+            for segment in &mut pipe_set.segments {
+                position(&mut segment.start);
+                position(&mut segment.end);
+            }
+        }
+        for polygon_set in self.polygons.iter_mut().flatten() {
+            for vertex in polygon_set.polygons.iter_mut().flat_map(|polygon| &mut polygon.vertices) {
+                position(&mut vertex.position);
+            }
+        }
+    }
+
+    /// Axis-aligned bounds (min, max) of all positions, `None` when there is no geometry
+    pub fn bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        let mesh_positions = self.meshes.iter().flatten()
+            .flat_map(|mesh| mesh.vertices.iter().map(|vertex| vertex.position));
+        let point_positions = self.points.iter().flatten()
+            .flat_map(|set| set.vertices.iter().map(|vertex| vertex.position));
+        let line_positions = self.lines.iter().flatten()
+            .flat_map(|set| set.vertices.iter().map(|vertex| vertex.position));
+        let pipe_positions = self.pipes.iter().flatten()
+            .flat_map(|set| set.segments.iter().flat_map(|segment| [segment.start, segment.end]));
+        let polygon_positions = self.polygons.iter().flatten()
+            .flat_map(|set| set.polygons.iter().flat_map(|polygon| polygon.vertices.iter().map(|vertex| vertex.position)));
+
+        mesh_positions
+            .chain(point_positions)
+            .chain(line_positions)
+            .chain(pipe_positions)
+            .chain(polygon_positions)
+            .fold(None, |bounds: Option<([f32; 3], [f32; 3])>, p| {
+                let (mut min, mut max) = bounds.unwrap_or((p, p));
+                for i in 0..3 {
+                    min[i] = min[i].min(p[i]);
+                    max[i] = max[i].max(p[i]);
+                }
+                Some((min, max))
+            })
+    }
+
+    /// Divide 0-255 colors by 255, as set by `metadata.color_range` or detected by a component above 1
+    ///
+    /// Covers mesh vertex and face colors, point, line, pipe and polygon colors. Afterwards
+    /// `color_range` is `Float`, so the geometry is not scaled again when saved and reloaded.
+    /// Files with 0-255 colors that are all 0 or 1 are only read right with `"color_range": "u8"`.
+    pub fn normalize_color_range(&mut self) {
+        let u8_range = self.metadata.color_range.map_or_else(
+            || {
+                let mut above_one = false;
+                self.for_each_color(|color| above_one |= color.iter().any(|&c| c > 1.0));
+                above_one
+            },
+            |range| range == ColorRange::U8,
+        );
+        if u8_range {
+            self.for_each_color(|color| *color = color.map(|c| c / 255.0));
+        }
+        self.metadata.color_range = Some(ColorRange::Float);
+    }
+
+    /// Call `visit` with every color of every geometry type
+    fn for_each_color(&mut self, mut visit: impl FnMut(&mut [f32; 3])) {
+        for mesh in self.meshes.iter_mut().flatten() {
+            for color in mesh.vertices.iter_mut().filter_map(|vertex| vertex.color.as_mut()) {
+                visit(color);
+            }
+            for color in mesh.face_colors.iter_mut().flatten() {
+                visit(color);
+            }
+        }
+        for vertex in self.points.iter_mut().flatten().flat_map(|set| &mut set.vertices) {
+            visit(&mut vertex.color);
+        }
+        for vertex in self.lines.iter_mut().flatten().flat_map(|set| &mut set.vertices) {
+            visit(&mut vertex.color);
+        }
+        for segment in self.pipes.iter_mut().flatten().flat_map(|set| &mut set.segments) {
+            visit(&mut segment.color);
+            if let Some(end_color) = &mut segment.end_color {
+                visit(end_color);
+            }
+        }
+        for polygon_set in self.polygons.iter_mut().flatten() {
+            for vertex in polygon_set.polygons.iter_mut().flat_map(|polygon| &mut polygon.vertices) {
+                visit(&mut vertex.color);
+            }
+        }
+    }
+
+    /// Reverse the triangle winding of meshes marked with `flip_winding`
+    ///
+    /// Swapping two indices per triangle turns clockwise triangles counter-clockwise,
+    /// which the back-face culled mesh pipelines treat as front facing.
+    pub fn apply_winding_flips(&mut self) {
+        for mesh in self.meshes.iter_mut().flatten().filter(|mesh| mesh.flip_winding == Some(true)) {
+            for triangle in mesh.indices.chunks_exact_mut(3) {
+                triangle.swap(1, 2);
+            }
+        }
+    }
+}
+
+/// Totals of the geometry loaded from a JSON file, for sanity-checking imports
+///
+/// Counts all mesh and polygon sets and, like the loader, only the first point and pipe set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GeometryCounts {
+    pub mesh_vertices: usize,
+    pub mesh_triangles: usize,
+    pub points: usize,
+    pub pipe_segments: usize,
+    pub polygon_triangles: usize,
+}
+
+impl GeometryCounts {
+    /// Compute the totals from parsed geometry data
+    pub fn from_geometry_data(geometry_data: &GeometryData) -> Self {
+        let meshes = geometry_data.meshes.as_deref().unwrap_or_default();
+        Self {
+            mesh_vertices: meshes.iter().map(|mesh| mesh.vertices.len()).sum(),
+            mesh_triangles: meshes.iter().map(|mesh| mesh.indices.len() / 3).sum(),
+            points: geometry_data.points.as_ref()
+                .and_then(|sets| sets.first())
+                .map_or(0, |set| set.vertices.len()),
+            pipe_segments: geometry_data.pipes.as_ref()
+                .and_then(|sets| sets.first())
+                .map_or(0, |set| set.segments.len()),
+            polygon_triangles: geometry_data.polygons.as_deref().unwrap_or_default().iter()
+                .flat_map(|set| &set.polygons)
+                .map(|polygon| polygon.indices.len() / 3)
+                .sum(),
+        }
+    }
+
+    /// Whether nothing has been loaded
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for GeometryCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} mesh vertices, {} mesh triangles, {} points, {} pipe segments, {} polygon triangles",
+            self.mesh_vertices, self.mesh_triangles, self.points, self.pipe_segments, self.polygon_triangles
+        )
+    }
+}
+
+/// Problems found in one mesh by `validate_mesh`, to explain why it renders oddly
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MeshReport {
+    pub name: String,
+    pub vertices: usize,
+    pub triangles: usize,
+    pub degenerate_triangles: usize, // Zero area: repeated or collinear corners, drawn as nothing (holes)
+    pub duplicate_vertices: usize,   // Vertices at the position of an earlier one
+    pub non_manifold_edges: usize,   // Edges (between positions) shared by more than two triangles
+}
+
+impl MeshReport {
+    /// Whether none of the checks found a problem
+    pub fn is_clean(&self) -> bool {
+        self.degenerate_triangles == 0 && self.duplicate_vertices == 0 && self.non_manifold_edges == 0
+    }
+}
+
+impl std::fmt::Display for MeshReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mesh '{}': {} vertices, {} triangles, {} degenerate triangles, {} duplicate vertices, {} non-manifold edges",
+            self.name, self.vertices, self.triangles, self.degenerate_triangles, self.duplicate_vertices, self.non_manifold_edges
+        )
+    }
+}
+
+/// Count degenerate triangles, duplicate vertices and non-manifold edges of a mesh
+///
+/// Vertices are compared by exact position. Duplicates are common and harmless when
+/// they carry different normals or colors (hard edges), so edges are counted between
+/// positions rather than indices: a split seam is not reported as an open edge and a
+/// fin of three faces is found even when each face has its own vertices.
+pub fn validate_mesh(mesh_data: &MeshData) -> MeshReport {
+    let key = |position: [f32; 3]| position.map(|c| if c == 0.0 { 0 } else { c.to_bits() }); // -0.0 equals 0.0
+    let mut first_at_position = std::collections::HashMap::new();
+    let welded: Vec<u32> = mesh_data.vertices.iter()
+        .enumerate()
+        .map(|(i, vertex)| *first_at_position.entry(key(vertex.position)).or_insert(i as u32))
+        .collect();
+    let duplicate_vertices = mesh_data.vertices.len() - first_at_position.len();
+
+    let mut degenerate_triangles = 0;
+    let mut edge_faces: std::collections::HashMap<(u32, u32), usize> = std::collections::HashMap::new();
+    for triangle in mesh_data.indices.chunks_exact(3) {
+        let Some(corners) = triangle.iter()
+            .map(|&index| welded.get(index as usize).copied())
+            .collect::<Option<Vec<u32>>>() else {
+            degenerate_triangles += 1; // Out of range indices draw nothing useful either
+            continue;
+        };
+        let [a, b, c] = [0, 1, 2].map(|i| mesh_data.vertices[corners[i] as usize].position);
+        let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let ac = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+        let normal = cross_product(&ab, &ac);
+        let double_area = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        // Relative to the edge lengths, so tiny but valid triangles are not flagged
+        let scale = ab.iter().chain(ac.iter()).map(|d| d * d).sum::<f32>();
+        if double_area <= f32::EPSILON * scale || scale == 0.0 {
+            degenerate_triangles += 1;
+            continue;
+        }
+        for (start, end) in [(corners[0], corners[1]), (corners[1], corners[2]), (corners[2], corners[0])] {
+            *edge_faces.entry((start.min(end), start.max(end))).or_insert(0) += 1;
+        }
+    }
+
+    MeshReport {
+        name: mesh_data.name.clone(),
+        vertices: mesh_data.vertices.len(),
+        triangles: mesh_data.indices.len() / 3,
+        degenerate_triangles,
+        duplicate_vertices,
+        non_manifold_edges: edge_faces.values().filter(|&&faces| faces > 2).count(),
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Metadata {
     pub version: String,
     pub description: String,
     pub created: String,
+    pub import_rotation: Option<[f32; 3]>, // Optional Euler angles in degrees, baked in on load
+    pub recenter: Option<bool>, // Subtract the scene center before the f32 cast; unset recenters only far-away data
+    pub color_range: Option<ColorRange>, // Scale of the colors; unset reads them as 0-255 when any exceeds 1
+}
+
+/// Scale of the colors in a geometry file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorRange {
+    /// Integers from 0 to 255, as written by many exporters
+    U8,
+    /// Floats from 0 to 1, as the shaders use them
+    Float,
 }
 
 // Mesh Data Structures
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MeshData {
     pub name: String,
     pub vertices: Vec<MeshVertexData>,
     pub indices: Vec<u32>,
     pub material: Option<MaterialData>,
     pub face_colors: Option<Vec<[f32; 3]>>, // Add optional face colors array
+    pub flip_winding: Option<bool>, // Set for clockwise-wound meshes so they are not culled inside-out
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl MeshData {
+    /// Whether the mesh has no triangles to draw
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() || self.indices.len() < 3
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MeshVertexData {
     pub position: [f32; 3],
+    #[serde(default)]
     pub tex_coords: [f32; 2],
-    pub normal: [f32; 3],
+    pub normal: Option<[f32; 3]>,     // Computed from the triangles when missing
     pub tangent: Option<[f32; 3]>,    // Made optional
     pub bitangent: Option<[f32; 3]>,  // Made optional
     pub color: Option<[f32; 3]>, // Add optional per-vertex color
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MaterialData {
     pub name: String,
     pub diffuse_texture: String,
@@ -110,68 +467,106 @@ pub struct MaterialData {
 }
 
 // Point Data Structures
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PointData {
     pub name: String,
     pub vertices: Vec<PointVertexData>,
+    pub keep_fraction: Option<f32>, // Optional, draws only this random fraction (0 to 1) of the points
+    pub seed: Option<u64>, // Seed of the `keep_fraction` sampling, `model_point::DEFAULT_POINT_SEED` when unset
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PointVertexData {
     pub position: [f32; 3],
     pub color: [f32; 3],
+    pub alpha: Option<f32>, // Optional opacity, defaults to 1.0
     pub size: f32,
 }
 
 // Line Data Structures
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LineData {
     pub name: String,
     pub vertices: Vec<LineVertexData>,
+    pub width: Option<f32>, // Optional screen-space width in pixels
+}
+
+impl LineData {
+    /// Whether the set has no complete segment to draw
+    pub fn is_empty(&self) -> bool {
+        self.vertices.len() < 2
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LineVertexData {
     pub position: [f32; 3],
     pub color: [f32; 3],
+    pub alpha: Option<f32>, // Optional opacity, defaults to 1.0
 }
 
 // Pipe Data Structures
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PipeData {
     pub name: String,
     pub segments: Vec<PipeSegmentData>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl PipeData {
+    /// Whether the set has no segments to draw
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PipeSegmentData {
     pub start: [f32; 3],
     pub end: [f32; 3],
     pub color: [f32; 3],
+    pub end_color: Option<[f32; 3]>, // Optional, blends from `color` at the start to this color at the end
     pub radius: f32,
 }
 
 // Polygon Data Structures
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PolygonData {
     pub name: String,
     pub polygons: Vec<PolygonMeshData>,
+    pub lit: Option<bool>, // Optional, defaults to lit; unlit sets use only vertex color
+}
+
+impl PolygonData {
+    /// Whether no polygon in the set has a triangle to draw
+    pub fn is_empty(&self) -> bool {
+        self.polygons.iter().all(|polygon| polygon.vertices.is_empty() || polygon.indices.len() < 3)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PolygonMeshData {
     pub vertices: Vec<PolygonVertexData>,
     pub indices: Vec<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PolygonVertexData {
     pub position: [f32; 3],
     pub color: [f32; 3],
+    pub alpha: Option<f32>, // Optional opacity, defaults to 1.0
+}
+
+/// Whether `path` is an `http://` or `https://` URL rather than a file path
+pub fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
 }
 
 #[cfg(target_arch = "wasm32")]
-fn format_url(file_name: &str) -> reqwest::Url {
+pub(crate) fn format_url(file_name: &str) -> reqwest::Url {
+    // Absolute URLs are fetched as they are
+    if let Some(url) = is_url(file_name).then(|| reqwest::Url::parse(file_name).ok()).flatten() {
+        return url;
+    }
     let window = web_sys::window().unwrap();
     let location = window.location();
     let origin = location.origin().unwrap();
@@ -180,8 +575,18 @@ fn format_url(file_name: &str) -> reqwest::Url {
     base.join(file_name).unwrap()
 }
 
-/// Load geometry data from a JSON file
+/// Load geometry data from a JSON file, or a binary geometry file for paths ending in `.bin`
 pub async fn load_geometry_file(path: &str) -> Result<GeometryData, Box<dyn std::error::Error>> {
+    if is_binary_geometry_path(path) {
+        return load_geometry_binary(path).await;
+    }
+    let json_text = read_text_file(path).await?;
+    let geometry_data = parse_geometry_json(path, &json_text)?;
+    Ok(geometry_data)
+}
+
+/// Read a text file from disk or an `http(s)://` URL, or from the served assets folder on the web
+pub async fn read_text_file(path: &str) -> Result<String, Box<dyn std::error::Error>> {
     cfg_if! {
         if #[cfg(target_arch = "wasm32")] {
             // For WASM, extract just the filename from the path
@@ -195,67 +600,380 @@ pub async fn load_geometry_file(path: &str) -> Result<GeometryData, Box<dyn std:
                 .await?
                 .text()
                 .await?;
-            let geometry_data: GeometryData = serde_json::from_str(&json_text)?;
         } else {
-            // For native, use the full path as-is
-            let file_path = std::path::Path::new(path);
-            let file = File::open(file_path)?;
-            let reader = BufReader::new(file);
-            let geometry_data: GeometryData = serde_json::from_reader(reader)?;
+            // For native, fetch URLs and use file paths as-is
+            let json_text = if is_url(path) {
+                reqwest::blocking::get(path)?.error_for_status()?.text()?
+            } else {
+                std::fs::read_to_string(std::path::Path::new(path))?
+            };
         }
     }
+    Ok(json_text)
+}
+
+/// Stage of a geometry load reported to progress callbacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadStage {
+    Reading,
+    Parsing,
+    Done,
+}
+
+/// Progress of a geometry load
+#[derive(Debug, Clone, Copy)]
+pub struct LoadProgress {
+    pub stage: LoadStage,
+    pub bytes_read: u64,
+    pub total_bytes: Option<u64>, // None when the size is unknown (e.g. no Content-Length)
+}
+
+impl LoadProgress {
+    /// Fraction of bytes read in [0, 1], if the total size is known
+    pub fn fraction(&self) -> Option<f32> {
+        match self.stage {
+            LoadStage::Done => Some(1.0),
+            _ => self.total_bytes
+                .filter(|&total| total > 0)
+                .map(|total| (self.bytes_read as f64 / total as f64).min(1.0) as f32),
+        }
+    }
+}
+
+/// Load geometry data like `load_geometry_file`, reporting progress while the file is read
+///
+/// On native the file or `http(s)://` response is read in chunks; on WASM the response body is streamed.
+pub async fn load_geometry_file_with_progress<F: FnMut(LoadProgress)>(
+    path: &str,
+    mut on_progress: F,
+) -> Result<GeometryData, Box<dyn std::error::Error>> {
+    let bytes = read_with_progress(path, &mut on_progress).await?;
+    let bytes_read = bytes.len() as u64;
+
+    on_progress(LoadProgress { stage: LoadStage::Parsing, bytes_read, total_bytes: Some(bytes_read) });
+    let geometry_data = if is_binary_geometry_path(path) {
+        parse_geometry_binary(path, &bytes)?
+    } else {
+        parse_geometry_json(path, &String::from_utf8(bytes)?)?
+    };
+    on_progress(LoadProgress { stage: LoadStage::Done, bytes_read, total_bytes: Some(bytes_read) });
+
     Ok(geometry_data)
 }
 
+/// Read a geometry file or URL in chunks, calling `on_progress` after each chunk (native)
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_with_progress<F: FnMut(LoadProgress)>(
+    path: &str,
+    on_progress: &mut F,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    use std::io::Read;
+    const CHUNK_SIZE: usize = 256 * 1024;
+
+    let (mut file, total_bytes): (Box<dyn Read>, Option<u64>) = if is_url(path) {
+        let response = reqwest::blocking::get(path)?.error_for_status()?;
+        let total_bytes = response.content_length();
+        (Box::new(response), total_bytes)
+    } else {
+        let file = std::fs::File::open(path)?;
+        let total_bytes = file.metadata().ok().map(|metadata| metadata.len());
+        (Box::new(file), total_bytes)
+    };
+    let mut bytes = Vec::with_capacity(total_bytes.unwrap_or(0) as usize);
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+
+    on_progress(LoadProgress { stage: LoadStage::Reading, bytes_read: 0, total_bytes });
+    loop {
+        let count = file.read(&mut chunk)?;
+        if count == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&chunk[..count]);
+        on_progress(LoadProgress { stage: LoadStage::Reading, bytes_read: bytes.len() as u64, total_bytes });
+    }
+
+    Ok(bytes)
+}
+
+/// Stream a geometry file with fetch, calling `on_progress` after each received chunk (WASM)
+#[cfg(target_arch = "wasm32")]
+async fn read_with_progress<F: FnMut(LoadProgress)>(
+    path: &str,
+    on_progress: &mut F,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{ReadableStreamDefaultReader, Response};
+
+    let file_name = path.strip_prefix("assets/").unwrap_or(path);
+    let url = format_url(file_name);
+
+    let window = web_sys::window().ok_or("No browser window available")?;
+    let response: Response = JsFuture::from(window.fetch_with_str(url.as_str()))
+        .await
+        .map_err(|e| format!("Fetch failed: {:?}", e))?
+        .dyn_into()
+        .map_err(|e| format!("Fetch did not return a response: {:?}", e))?;
+
+    if !response.ok() {
+        return Err(format!("Failed to fetch {}: HTTP {}", url, response.status()).into());
+    }
+
+    let total_bytes = response.headers().get("content-length").ok().flatten()
+        .and_then(|length| length.parse::<u64>().ok());
+    let body = response.body().ok_or("Response has no body")?;
+    let reader: ReadableStreamDefaultReader = body.get_reader().dyn_into()
+        .map_err(|e| format!("Failed to get stream reader: {:?}", e))?;
+
+    let mut bytes = Vec::with_capacity(total_bytes.unwrap_or(0) as usize);
+    on_progress(LoadProgress { stage: LoadStage::Reading, bytes_read: 0, total_bytes });
+    loop {
+        let result = JsFuture::from(reader.read())
+            .await
+            .map_err(|e| format!("Failed to read response: {:?}", e))?;
+        let done = js_sys::Reflect::get(&result, &"done".into())
+            .ok()
+            .and_then(|value| value.as_bool())
+            .unwrap_or(true);
+        if done {
+            break;
+        }
+        let value = js_sys::Reflect::get(&result, &"value".into())
+            .map_err(|e| format!("Failed to read chunk: {:?}", e))?;
+        bytes.extend(js_sys::Uint8Array::new(&value).to_vec());
+        on_progress(LoadProgress { stage: LoadStage::Reading, bytes_read: bytes.len() as u64, total_bytes });
+    }
+
+    Ok(bytes)
+}
+
+/// Parse geometry JSON, reporting syntax errors with their line/column and the offending source
+///
+/// Scenes far from the origin are recentered in f64 before positions are cast to f32
+/// (see `GeometryData::origin_offset`). Any `metadata.import_rotation` and per-mesh
+/// `flip_winding` are applied to the parsed geometry, and 0-255 colors are scaled to 0-1.
+pub fn parse_geometry_json(source: &str, json_text: &str) -> Result<GeometryData, String> {
+    let mut geometry_data: GeometryData = serde_json::from_str(json_text).map_err(|e| {
+        let message = describe_json_error(source, json_text, &e);
+        #[cfg(target_arch = "wasm32")]
+        {
+            web_sys::console::error_1(&message.clone().into());
+        }
+        message
+    })?;
+    if geometry_data.needs_recentering() {
+        // Parse again keeping full f64 precision, so only large-coordinate files pay for it
+        let mut json: serde_json::Value = serde_json::from_str(json_text).map_err(|e| e.to_string())?;
+        let origin_offset = recenter_json_positions(&mut json);
+        geometry_data = serde_json::from_value(json).map_err(|e| format!("Invalid geometry JSON in {}: {}", source, e))?;
+        geometry_data.origin_offset = origin_offset;
+        log::info!("Recentered {} by {:?} to keep f32 precision", source, origin_offset);
+    }
+    geometry_data.apply_import_rotation();
+    geometry_data.apply_winding_flips();
+    geometry_data.normalize_color_range();
+    Ok(geometry_data)
+}
+
+/// First bytes of a binary geometry file and the layout version that follows them
+const BINARY_GEOMETRY_MAGIC: &[u8; 4] = b"WGVG";
+const BINARY_GEOMETRY_VERSION: u32 = 1;
+/// Magic, version and the three f64 of `GeometryData::origin_offset`
+const BINARY_GEOMETRY_HEADER_SIZE: usize = 4 + 4 + 3 * 8;
+
+/// Whether `path` names a binary geometry file (`.bin`) rather than JSON
+pub fn is_binary_geometry_path(path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    std::path::Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("bin"))
+}
+
+/// Encode geometry in the compact binary format read by `parse_geometry_binary`
+///
+/// The layout is the magic `WGVG`, a little-endian u32 version and the f64 origin
+/// offset, followed by the bincode encoding of `geometry`. Geometry is stored as it is
+/// in memory, so a file loaded from JSON keeps its recentering, import rotation and
+/// winding flips without them being applied again.
+pub fn encode_geometry_binary(geometry: &GeometryData) -> Result<Vec<u8>, String> {
+    let body = bincode::serialize(geometry).map_err(|e| format!("Failed to encode geometry: {}", e))?;
+    let mut bytes = Vec::with_capacity(BINARY_GEOMETRY_HEADER_SIZE + body.len());
+    bytes.extend_from_slice(BINARY_GEOMETRY_MAGIC);
+    bytes.extend_from_slice(&BINARY_GEOMETRY_VERSION.to_le_bytes());
+    for coordinate in geometry.origin_offset {
+        bytes.extend_from_slice(&coordinate.to_le_bytes());
+    }
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Decode geometry written by `encode_geometry_binary`
+pub fn parse_geometry_binary(source: &str, bytes: &[u8]) -> Result<GeometryData, String> {
+    use std::convert::TryInto;
+
+    if bytes.len() < BINARY_GEOMETRY_HEADER_SIZE || &bytes[..4] != BINARY_GEOMETRY_MAGIC {
+        return Err(format!("{} is not a binary geometry file", source));
+    }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    if version != BINARY_GEOMETRY_VERSION {
+        return Err(format!(
+            "{} has binary geometry version {}, expected {}",
+            source, version, BINARY_GEOMETRY_VERSION
+        ));
+    }
+    let mut origin_offset = [0.0; 3];
+    for (i, coordinate) in origin_offset.iter_mut().enumerate() {
+        let start = 8 + i * 8;
+        *coordinate = f64::from_le_bytes(bytes[start..start + 8].try_into().unwrap());
+    }
+
+    let mut geometry_data: GeometryData = bincode::deserialize(&bytes[BINARY_GEOMETRY_HEADER_SIZE..])
+        .map_err(|e| format!("Invalid binary geometry in {}: {}", source, e))?;
+    geometry_data.origin_offset = origin_offset;
+    Ok(geometry_data)
+}
+
+/// Load geometry from a binary file or `http(s)://` URL (fetched from the assets folder on the web)
+///
+/// Much faster to load than the equivalent JSON for large meshes and point clouds.
+pub async fn load_geometry_binary(path: &str) -> Result<GeometryData, Box<dyn std::error::Error>> {
+    let bytes = read_with_progress(path, &mut |_| {}).await?;
+    Ok(parse_geometry_binary(path, &bytes)?)
+}
+
+/// Write geometry to a binary file for faster loading with `load_geometry_binary`
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_geometry_binary(geometry: &GeometryData, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path, encode_geometry_binary(geometry)?)?;
+    Ok(())
+}
+
+/// Call `visit` with every position array (`[x, y, z]`) in raw geometry JSON
+fn visit_json_positions(geometry: &mut serde_json::Value, visit: &mut dyn FnMut(&mut serde_json::Value)) {
+    fn items<'v>(value: &'v mut serde_json::Value, key: &str) -> impl Iterator<Item = &'v mut serde_json::Value> {
+        value.get_mut(key).and_then(serde_json::Value::as_array_mut).into_iter().flatten()
+    }
+    fn fields(item: &mut serde_json::Value, keys: &[&str], visit: &mut dyn FnMut(&mut serde_json::Value)) {
+        for key in keys {
+            if let Some(position) = item.get_mut(*key) {
+                visit(position);
+            }
+        }
+    }
+
+    let sets: [(&str, &str, &[&str]); 4] = [
+        ("meshes", "vertices", &["position"]),
+        ("points", "vertices", &["position"]),
+        ("lines", "vertices", &["position"]),
+        ("pipes", "segments", &["start", "end"]),
+    ];
+    for (set_key, item_key, keys) in sets {
+        for set in items(geometry, set_key) {
+            for item in items(set, item_key) {
+                fields(item, keys, visit);
+            }
+        }
+    }
+    for set in items(geometry, "polygons") {
+        for polygon in items(set, "polygons") {
+            for vertex in items(polygon, "vertices") {
+                fields(vertex, &["position"], visit);
+            }
+        }
+    }
+}
+
+/// Move the center of the f64 bounds of all positions to the origin and return that center
+fn recenter_json_positions(geometry: &mut serde_json::Value) -> [f64; 3] {
+    let mut min = [f64::MAX; 3];
+    let mut max = [f64::MIN; 3];
+    visit_json_positions(geometry, &mut |position| {
+        for (i, coordinate) in position.as_array().into_iter().flatten().take(3).enumerate() {
+            if let Some(coordinate) = coordinate.as_f64() {
+                min[i] = min[i].min(coordinate);
+                max[i] = max[i].max(coordinate);
+            }
+        }
+    });
+    let center: [f64; 3] = std::array::from_fn(|i| if min[i] <= max[i] { (min[i] + max[i]) * 0.5 } else { 0.0 });
+
+    visit_json_positions(geometry, &mut |position| {
+        for (i, coordinate) in position.as_array_mut().into_iter().flatten().take(3).enumerate() {
+            if let Some(value) = coordinate.as_f64() {
+                *coordinate = serde_json::Value::from(value - center[i]);
+            }
+        }
+    });
+    center
+}
+
+/// Format a serde_json error with its position and a snippet of the lines leading up to it
+fn describe_json_error(source: &str, json_text: &str, error: &serde_json::Error) -> String {
+    const CONTEXT_LINES: usize = 2;
+    const SNIPPET_WIDTH: usize = 100;
+
+    let line = error.line();
+    let column = error.column();
+    let mut message = format!(
+        "Invalid geometry JSON in {} at line {}, column {}: {}",
+        source, line, column, error
+    );
+
+    // Line 0 means the error is not tied to a position (e.g. an I/O error)
+    if line == 0 {
+        return message;
+    }
+
+    // Keep the caret visible on long (e.g. minified) lines by windowing around the column
+    let window_start = column.saturating_sub(SNIPPET_WIDTH * 3 / 5);
+    let first_line = line.saturating_sub(CONTEXT_LINES).max(1);
+    for (index, text) in json_text.lines().enumerate().skip(first_line - 1).take(line - first_line + 1) {
+        let visible: String = text.chars().skip(window_start).take(SNIPPET_WIDTH).collect();
+        message.push_str(&format!("\n{:>6} | {}", index + 1, visible));
+    }
+    let caret_offset = column.saturating_sub(1).saturating_sub(window_start);
+    message.push_str(&format!("\n{:>6} | {}^", "", " ".repeat(caret_offset)));
+
+    message
+}
+
 /// Convert JSON mesh data to a Model
 pub fn create_model_from_mesh_data(
     device: &wgpu::Device, 
     _queue: &wgpu::Queue,  // Kept for compatibility but unused
     mesh_data: &MeshData,
+    default_color: [f32; 3], // Color of vertices without one, see `State::default_mesh_color`
+    normalize_normals: bool, // Rescale the normals to unit length, see `State::set_normalize_normals`
     _texture_bind_group_layout: &wgpu::BindGroupLayout  // Kept for compatibility but unused
 ) -> Result<Model, Box<dyn std::error::Error>> {
+    // Empty meshes keep their place in the model list but own no GPU buffers,
+    // so nothing zero-sized is created or drawn
+    if mesh_data.is_empty() {
+        log::warn!("Mesh '{}' has no triangles; it will not be drawn", mesh_data.name);
+        return Ok(Model::with_edges(Vec::new(), Vec::new()));
+    }
+    
     let mut meshes = Vec::new();
     // Materials removed - not needed for texture-free pipeline
     
-    // Convert vertices with color handling
-    let mut vertices: Vec<ModelVertex> = mesh_data.vertices.iter()
-        .map(|v| {
-            // Default tangent space vectors based on normal
-            // These are arbitrary but consistent given a normal
-            let default_tangent = calculate_default_tangent(&v.normal);
-            let default_bitangent = calculate_default_bitangent(&v.normal, &default_tangent);
-            
-            ModelVertex {
-                position: v.position,
-                tex_coords: v.tex_coords,
-                normal: v.normal,
-                tangent: v.tangent.unwrap_or(default_tangent),  // Use default if not provided
-                bitangent: v.bitangent.unwrap_or(default_bitangent),  // Use default if not provided
-                color: v.color.unwrap_or([0.7, 0.7, 0.7]), // Default color if not provided
-            }
-        })
-        .collect();
+    let mut vertices = mesh_vertices(mesh_data, default_color, normalize_normals);
     
-    // Handle per-face colors if provided
-    if let Some(face_colors) = &mesh_data.face_colors {
-        if face_colors.len() * 3 <= mesh_data.indices.len() / 3 {
-            // Apply face colors to vertices
-            for (face_idx, color) in face_colors.iter().enumerate() {
-                let idx_base = face_idx * 3; // Each face has 3 vertices
-                if idx_base + 2 < mesh_data.indices.len() {
-                    // Get the three vertex indices for this face
-                    let v1_idx = mesh_data.indices[idx_base] as usize;
-                    let v2_idx = mesh_data.indices[idx_base + 1] as usize;
-                    let v3_idx = mesh_data.indices[idx_base + 2] as usize;
-                    
-                    // Apply the face color to all three vertices
-                    if v1_idx < vertices.len() { vertices[v1_idx].color = *color; }
-                    if v2_idx < vertices.len() { vertices[v2_idx].color = *color; }
-                    if v3_idx < vertices.len() { vertices[v3_idx].color = *color; }
-                }
+    // Handle per-face colors if provided (one per triangle)
+    let indices = match &mesh_data.face_colors {
+        Some(face_colors) => {
+            let triangle_count = mesh_data.indices.len() / 3;
+            if face_colors.len() != triangle_count {
+                log::warn!(
+                    "Mesh '{}' has {} face colors for {} triangles",
+                    mesh_data.name,
+                    face_colors.len(),
+                    triangle_count
+                );
             }
+            assign_face_colors(&mut vertices, &mesh_data.indices, face_colors)
         }
-    }
+        None => mesh_data.indices.clone(),
+    };
     
     // Create vertex buffer
     let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -267,15 +985,22 @@ pub fn create_model_from_mesh_data(
     // Create index buffer
     let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
         label: Some(&format!("{} Index Buffer", mesh_data.name)),
-        contents: bytemuck::cast_slice(&mesh_data.indices),
+        contents: bytemuck::cast_slice(&indices),
         usage: wgpu::BufferUsages::INDEX,
     });
     
+    // Edges use the original indices; the vertices split for face colors sit at the same positions
+    let (edge_index_buffer, num_edge_elements) =
+        crate::model::create_edge_index_buffer(device, &mesh_data.name, &mesh_data.indices);
+    
     let mesh = Mesh {
         _name: mesh_data.name.clone(),
         vertex_buffer,
         index_buffer,
-        num_elements: mesh_data.indices.len() as u32,
+        num_elements: indices.len() as u32,
+        edge_index_buffer,
+        num_edge_elements,
+        bounds: crate::model::vertex_bounds(&vertices),
         // material field removed - not needed for texture-free pipeline
     };
     
@@ -284,10 +1009,62 @@ pub fn create_model_from_mesh_data(
     // Create edge visualization by converting to OpenModel mesh and extracting edges
     let edge_meshes = create_edge_meshes_from_mesh_data(device, mesh_data);
     
-    Ok(Model { 
-        meshes,
-        edge_meshes,
-    })
+    Ok(Model::with_edges(meshes, edge_meshes))
+}
+
+/// GPU vertices of a JSON mesh, with computed normals and `default_color` where they are missing
+fn mesh_vertices(mesh_data: &MeshData, default_color: [f32; 3], normalize_normals: bool) -> Vec<ModelVertex> {
+    // Position-only meshes get smooth normals from their triangles
+    let computed_normals = mesh_data.vertices.iter()
+        .any(|v| v.normal.is_none())
+        .then(|| compute_smooth_normals(&mesh_data.vertices, &mesh_data.indices));
+    
+    // Convert vertices with color handling
+    mesh_data.vertices.iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let normal = v.normal
+                .or_else(|| computed_normals.as_ref().map(|normals| normals[i]))
+                .unwrap_or([0.0, 0.0, 1.0]);
+            let normal = if normalize_normals { normalize(&normal) } else { normal };
+            
+            // Default tangent space vectors based on normal
+            // These are arbitrary but consistent given a normal
+            let default_tangent = calculate_default_tangent(&normal);
+            let default_bitangent = calculate_default_bitangent(&normal, &default_tangent);
+            
+            ModelVertex {
+                position: v.position,
+                tex_coords: v.tex_coords,
+                normal,
+                tangent: v.tangent.unwrap_or(default_tangent),  // Use default if not provided
+                bitangent: v.bitangent.unwrap_or(default_bitangent),  // Use default if not provided
+                color: v.color.unwrap_or(default_color), // Default color if not provided
+            }
+        })
+        .collect()
+}
+
+/// Give every triangle three vertices of its own carrying its face color and return the new indices
+///
+/// Colors interpolate smoothly across triangles, so a vertex shared by faces of different
+/// colors would blend them. The copies are appended, keeping the original vertices and
+/// indices valid, e.g. for the edge buffer. Triangles without a face color are left as they are.
+fn assign_face_colors(vertices: &mut Vec<ModelVertex>, indices: &[u32], face_colors: &[[f32; 3]]) -> Vec<u32> {
+    let mut indices = indices.to_vec();
+    let original_count = vertices.len();
+    for (triangle, color) in indices.chunks_exact_mut(3).zip(face_colors) {
+        if triangle.iter().any(|&index| index as usize >= original_count) {
+            continue;
+        }
+        for index in triangle.iter_mut() {
+            let mut vertex = vertices[*index as usize];
+            vertex.color = *color;
+            vertices.push(vertex);
+            *index = (vertices.len() - 1) as u32;
+        }
+    }
+    indices
 }
 
 /// Create edge visualization meshes from mesh data
@@ -324,7 +1101,8 @@ fn create_edge_meshes_from_mesh_data(device: &wgpu::Device, mesh_data: &MeshData
             device, 
             &format!("{}_edge_{}", mesh_data.name, i), 
             edge_pipe,
-            [0.0, 0.0, 0.0] // Black color for edges
+            [0.0, 0.0, 0.0], // Black color for edges
+            [0.0; 3], // Built from the loaded positions, which are recentered already
         );
         edge_meshes.push(edge_mesh);
     }
@@ -332,7 +1110,14 @@ fn create_edge_meshes_from_mesh_data(device: &wgpu::Device, mesh_data: &MeshData
     edge_meshes
 }
 
+/// Combine a JSON RGB color with its optional alpha (opaque when missing)
+fn rgba(color: [f32; 3], alpha: Option<f32>) -> [f32; 4] {
+    [color[0], color[1], color[2], alpha.unwrap_or(1.0)]
+}
+
 /// Convert JSON point data to a QuadPointModel
+///
+/// Sets with a `keep_fraction` are decimated first, keeping the same points for the same `seed`.
 pub fn create_quad_point_model_from_point_data(
     device: &wgpu::Device,
     point_data: &PointData
@@ -341,21 +1126,50 @@ pub fn create_quad_point_model_from_point_data(
     let points: Vec<PointVertex> = point_data.vertices.iter()
         .map(|v| PointVertex {
             position: v.position,
-            color: v.color,
+            color: rgba(v.color, v.alpha),
             size: v.size,
         })
         .collect();
+    let points = match point_data.keep_fraction {
+        Some(keep_fraction) => {
+            let seed = point_data.seed.unwrap_or(model_point::DEFAULT_POINT_SEED);
+            model_point::decimate_points(&points, keep_fraction, seed)
+        }
+        None => points,
+    };
     
-    // Create QuadPointModel
-    QuadPointModel::new(device, &point_data.name, &points)
+    // Create QuadPointModel with the local density of each point for the density color mode
+    QuadPointModel::with_density(device, &point_data.name, &points, model_point::DENSITY_GRID_RESOLUTION)
 }
 
 
 
-/// Convert JSON pipe data to a PipeModel
+/// Convert JSON line data to a WideLineModel
+///
+/// Vertices are consumed in pairs (line list). Sets without a `width` use the
+/// default `model_line::LINE_WIDTH`.
+pub fn create_wide_line_model_from_line_data(
+    device: &wgpu::Device,
+    line_data: &LineData
+) -> WideLineModel {
+    let (vertices, width) = line_set_vertices(line_data);
+    WideLineModel::new(device, &line_data.name, &vertices, width)
+}
+
+/// Line-list vertices of a JSON line set and its width in pixels
+fn line_set_vertices(line_data: &LineData) -> (Vec<LineVertex>, f32) {
+    let vertices = line_data.vertices.iter()
+        .map(|v| LineVertex::new(v.position, rgba(v.color, v.alpha)))
+        .collect();
+    (vertices, line_data.width.unwrap_or(model_line::LINE_WIDTH))
+}
+
+/// Convert JSON pipe data to a PipeModel, multiplying every segment radius by `radius_scale`
 pub fn create_pipe_model_from_pipe_data(
     device: &wgpu::Device,
-    pipe_data: &PipeData
+    pipe_data: &PipeData,
+    radius_scale: f32,
+    cap_style: PipeCapStyle,
 ) -> PipeModel {
     println!("DEBUG: Converting {} pipe segments from JSON", pipe_data.segments.len());
     // Convert pipe segment data to PipeSegment format
@@ -364,20 +1178,17 @@ pub fn create_pipe_model_from_pipe_data(
             start: s.start,
             end: s.end,
             color: s.color,
-            radius: s.radius,
+            end_color: s.end_color,
+            radius: s.radius * radius_scale,
         })
         .collect();
     
     // Create PipeModel
-    PipeModel::new(device, &pipe_data.name, &segments)
+    PipeModel::with_cap_style(device, &pipe_data.name, &segments, cap_style)
 }
 
-/// Convert JSON polygon data to a PolygonModel
-pub fn create_polygon_model_from_polygon_data(
-    device: &wgpu::Device,
-    polygon_data: &PolygonData
-) -> PolygonModel {
-    // Convert all polygons to flat lists of vertices and indices
+/// Vertices and indices of every polygon of a set in one list, keeping each triangle's winding
+fn polygon_set_vertices(polygon_data: &PolygonData) -> (Vec<PolygonVertex>, Vec<u32>) {
     let mut all_vertices = Vec::new();
     let mut all_indices = Vec::new();
     let mut vertex_offset = 0;
@@ -387,7 +1198,7 @@ pub fn create_polygon_model_from_polygon_data(
         let vertices: Vec<PolygonVertex> = polygon.vertices.iter()
             .map(|v| PolygonVertex {
                 position: v.position,
-                color: v.color,
+                color: rgba(v.color, v.alpha),
             })
             .collect();
         
@@ -402,6 +1213,442 @@ pub fn create_polygon_model_from_polygon_data(
         vertex_offset += polygon.vertices.len() as u32;
     }
     
+    (all_vertices, all_indices)
+}
+
+/// Convert JSON polygon data to a PolygonModel
+pub fn create_polygon_model_from_polygon_data(
+    device: &wgpu::Device,
+    polygon_data: &PolygonData
+) -> PolygonModel {
+    let (all_vertices, all_indices) = polygon_set_vertices(polygon_data);
+    
     // Create PolygonModel
-    PolygonModel::new(device, &polygon_data.name, &all_vertices, &all_indices)
+    let mut polygon_model = PolygonModel::new(device, &polygon_data.name, &all_vertices, &all_indices);
+    polygon_model.lit = polygon_data.lit.unwrap_or(true);
+    polygon_model
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(position: [f32; 3]) -> ModelVertex {
+        ModelVertex {
+            position,
+            tex_coords: [0.0, 0.0],
+            normal: [0.0, 0.0, 1.0],
+            tangent: [1.0, 0.0, 0.0],
+            bitangent: [0.0, 1.0, 0.0],
+            color: [0.5, 0.5, 0.5],
+        }
+    }
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for axis in 0..3 {
+            assert!((actual[axis] - expected[axis]).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn position_only_meshes_get_outward_smooth_normals_and_the_default_color() {
+        let geometry = parse_geometry_json(
+            "test_position_only_tetrahedron.json",
+            include_str!("../assets/test_position_only_tetrahedron.json"),
+        )
+        .unwrap();
+        let mesh = &geometry.meshes.as_ref().unwrap()[0];
+        let vertices = mesh_vertices(mesh, DEFAULT_MESH_COLOR, false);
+
+        assert_eq!(vertices.len(), 4);
+        for vertex in &vertices {
+            // A regular tetrahedron around the origin: every vertex normal points away from it
+            assert_close(vertex.normal, normalize(&vertex.position));
+            assert_eq!(vertex.color, DEFAULT_MESH_COLOR);
+        }
+    }
+
+    #[test]
+    fn given_normals_and_colors_are_kept_next_to_computed_ones() {
+        let geometry = parse_geometry_json(
+            "quad.json",
+            r#"{
+                "metadata": { "version": "1.0", "description": "", "created": "" },
+                "meshes": [{
+                    "name": "quad",
+                    "vertices": [
+                        { "position": [0.0, 0.0, 0.0], "normal": [1.0, 0.0, 0.0], "color": [0.2, 0.4, 0.6] },
+                        { "position": [1.0, 0.0, 0.0] },
+                        { "position": [1.0, 1.0, 0.0] },
+                        { "position": [0.0, 1.0, 0.0] }
+                    ],
+                    "indices": [0, 1, 2, 0, 2, 3]
+                }]
+            }"#,
+        )
+        .unwrap();
+        let mesh = &geometry.meshes.as_ref().unwrap()[0];
+        let vertices = mesh_vertices(mesh, [0.9, 0.1, 0.1], false);
+
+        assert_close(vertices[0].normal, [1.0, 0.0, 0.0]);
+        assert_eq!(vertices[0].color, [0.2, 0.4, 0.6]);
+        for vertex in &vertices[1..] {
+            // Counter-clockwise seen from +Z
+            assert_close(vertex.normal, [0.0, 0.0, 1.0]);
+            assert_eq!(vertex.color, [0.9, 0.1, 0.1]);
+        }
+    }
+
+    #[test]
+    fn empty_geometry_parses_and_loads() {
+        let geometry = pollster::block_on(load_geometry_file("assets/test_empty_geometry.json")).unwrap();
+        let meshes = geometry.meshes.as_ref().unwrap();
+        assert!(meshes[0].is_empty());
+        assert!(!meshes[1].is_empty());
+        assert!(geometry.lines.as_ref().unwrap()[0].is_empty());
+        assert!(geometry.pipes.as_ref().unwrap()[0].is_empty());
+        assert!(geometry.polygons.as_ref().unwrap()[0].is_empty());
+
+        // Only the triangle counts, and the CPU side of loading handles the empty sets
+        let counts = GeometryCounts::from_geometry_data(&geometry);
+        assert_eq!((counts.mesh_vertices, counts.mesh_triangles, counts.points), (3, 1, 0));
+        assert_eq!(geometry.bounds(), Some(([0.0, 0.0, 0.0], [1.0, 1.0, 0.0])));
+        assert!(mesh_vertices(&meshes[0], DEFAULT_MESH_COLOR, false).is_empty());
+        assert_eq!(validate_mesh(&meshes[0]).degenerate_triangles, 0);
+    }
+
+    #[test]
+    fn geometry_without_any_sets_parses() {
+        let geometry = parse_geometry_json(
+            "empty.json",
+            r#"{ "metadata": { "version": "1.0", "description": "", "created": "" } }"#,
+        )
+        .unwrap();
+        assert!(GeometryCounts::from_geometry_data(&geometry).is_empty());
+        assert_eq!(geometry.bounds(), None);
+    }
+
+    #[test]
+    fn polygon_sets_keep_the_winding_of_each_polygon_and_are_drawn_double_sided() {
+        let geometry = parse_geometry_json(
+            "test_polygon_winding.json",
+            include_str!("../assets/test_polygon_winding.json"),
+        )
+        .unwrap();
+        let (vertices, indices) = polygon_set_vertices(&geometry.polygons.as_ref().unwrap()[0]);
+
+        // Two squares of two triangles each, the second offset past the first's vertices
+        assert_eq!(vertices.len(), 8);
+        assert_eq!(indices, vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]);
+        assert!(vertices.iter().all(|vertex| vertex.color[3] == 1.0));
+
+        // Z of the triangle normals: counter-clockwise (+Z) on the left, clockwise (-Z) on the right
+        let normal_z = |triangle: &[u32]| {
+            let [a, b, c] = [0, 1, 2].map(|corner| vertices[triangle[corner] as usize].position);
+            cross_product(&[b[0] - a[0], b[1] - a[1], b[2] - a[2]], &[c[0] - a[0], c[1] - a[1], c[2] - a[2]])[2]
+        };
+        let windings: Vec<f32> = indices.chunks_exact(3).map(|triangle| normal_z(triangle).signum()).collect();
+        assert_eq!(windings, vec![1.0, 1.0, -1.0, -1.0]);
+
+        // So neither winding may be culled
+        assert_eq!(crate::model_polygon::PRIMITIVE_STATE.cull_mode, None);
+    }
+
+    #[test]
+    fn validate_mesh_counts_degenerate_triangles_duplicate_vertices_and_non_manifold_edges() {
+        let geometry = parse_geometry_json(
+            "mesh_validation.json",
+            r#"{
+                "metadata": { "version": "1.0", "description": "", "created": "" },
+                "meshes": [{
+                    "name": "mesh_validation",
+                    "vertices": [
+                        { "position": [0.0, 0.0, 0.0] },
+                        { "position": [1.0, 0.0, 0.0] },
+                        { "position": [0.5, 1.0, 0.0] },
+                        { "position": [0.5, -1.0, 0.0] },
+                        { "position": [0.5, 0.0, 1.0] },
+                        { "position": [1.0, 0.0, 0.0] }
+                    ],
+                    "indices": [0, 1, 2, 1, 0, 3, 0, 1, 4, 0, 1, 5]
+                }]
+            }"#,
+        )
+        .unwrap();
+        // Vertex 5 repeats vertex 1, which makes the last triangle degenerate; the edge
+        // from the origin to x = 1 has three proper triangles on it
+        let report = validate_mesh(&geometry.meshes.as_ref().unwrap()[0]);
+
+        assert_eq!((report.vertices, report.triangles), (6, 4));
+        assert_eq!(report.degenerate_triangles, 1);
+        assert_eq!(report.duplicate_vertices, 1);
+        assert_eq!(report.non_manifold_edges, 1);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn validate_mesh_finds_nothing_on_a_closed_mesh() {
+        let geometry = parse_geometry_json(
+            "test_position_only_tetrahedron.json",
+            include_str!("../assets/test_position_only_tetrahedron.json"),
+        )
+        .unwrap();
+        assert!(validate_mesh(&geometry.meshes.as_ref().unwrap()[0]).is_clean());
+    }
+
+    fn rotated_mesh_json(recenter: bool) -> String {
+        format!(
+            r#"{{
+                "metadata": {{ "version": "1.0", "description": "", "created": "", "import_rotation": [90.0, 0.0, 0.0], "recenter": {} }},
+                "meshes": [{{
+                    "name": "y_up",
+                    "vertices": [
+                        {{ "position": [1.0, 2.0, 3.0], "normal": [0.0, 1.0, 0.0] }},
+                        {{ "position": [3.0, 4.0, 5.0], "normal": [0.0, 0.0, 1.0] }},
+                        {{ "position": [1.0, 4.0, 3.0], "normal": [1.0, 0.0, 0.0] }}
+                    ],
+                    "indices": [0, 1, 2]
+                }}]
+            }}"#,
+            recenter
+        )
+    }
+
+    #[test]
+    fn import_rotation_turns_positions_and_normals() {
+        let geometry = parse_geometry_json("y_up.json", &rotated_mesh_json(false)).unwrap();
+        let vertices = &geometry.meshes.as_ref().unwrap()[0].vertices;
+
+        // 90 degrees about X takes (x, y, z) to (x, -z, y): Y up becomes Z up
+        assert_close(vertices[0].position, [1.0, -3.0, 2.0]);
+        assert_close(vertices[1].position, [3.0, -5.0, 4.0]);
+        assert_close(vertices[0].normal.unwrap(), [0.0, 0.0, 1.0]);
+        assert_close(vertices[1].normal.unwrap(), [0.0, -1.0, 0.0]);
+        assert_close(vertices[2].normal.unwrap(), [1.0, 0.0, 0.0]);
+        assert_eq!(geometry.origin_offset, [0.0; 3]);
+    }
+
+    #[test]
+    fn import_rotation_turns_the_origin_offset() {
+        let geometry = parse_geometry_json("y_up.json", &rotated_mesh_json(true)).unwrap();
+        let offset = geometry.origin_offset;
+
+        // The center [2, 3, 4] of the bounds, rotated like the positions
+        for (axis, &expected) in [2.0, -4.0, 3.0].iter().enumerate() {
+            assert!((offset[axis] - expected).abs() < 1e-9, "{:?}", offset);
+        }
+        // Adding the offset back gives the rotated original coordinates
+        let position = geometry.meshes.as_ref().unwrap()[0].vertices[0].position;
+        assert_close(std::array::from_fn(|i| position[i] + offset[i] as f32), [1.0, -3.0, 2.0]);
+    }
+
+    #[test]
+    fn far_coordinates_are_recentered_in_f64() {
+        let geometry = parse_geometry_json(
+            "survey.json",
+            r#"{
+                "metadata": { "version": "1.0", "description": "", "created": "" },
+                "points": [{
+                    "name": "survey_points",
+                    "vertices": [
+                        { "position": [512344.75, 5412345.5, 101.25], "color": [1.0, 0.0, 0.0], "size": 1.0 },
+                        { "position": [512345.25, 5412346.0, 100.75], "color": [1.0, 0.0, 0.0], "size": 1.0 },
+                        { "position": [512345.0, 5412345.0, 101.0], "color": [1.0, 0.0, 0.0], "size": 1.0 }
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(geometry.origin_offset, [512345.0, 5412345.5, 101.0]);
+        let positions: Vec<[f32; 3]> = geometry.points.as_ref().unwrap()[0].vertices.iter().map(|vertex| vertex.position).collect();
+        // Exact: the f32 cast happens after the subtraction, not on the raw coordinates
+        assert_eq!(positions, vec![[-0.25, 0.0, 0.25], [0.25, 0.5, -0.25], [0.0, -0.5, 0.0]]);
+    }
+
+    #[test]
+    fn near_coordinates_are_not_recentered() {
+        let geometry = parse_geometry_json("y_up.json", &rotated_mesh_json(false).replace(r#", "recenter": false"#, "")).unwrap();
+        assert_eq!(geometry.origin_offset, [0.0; 3]);
+    }
+
+    #[test]
+    fn line_set_widths_reach_the_wide_line_quads() {
+        let geometry = parse_geometry_json(
+            "lines.json",
+            r#"{
+                "metadata": { "version": "1.0", "description": "", "created": "" },
+                "lines": [
+                    {
+                        "name": "wide",
+                        "vertices": [
+                            { "position": [0.0, 0.0, 0.0], "color": [1.0, 0.0, 0.0] },
+                            { "position": [1.0, 0.0, 0.0], "color": [1.0, 0.0, 0.0] }
+                        ],
+                        "width": 6.0
+                    },
+                    {
+                        "name": "default",
+                        "vertices": [
+                            { "position": [0.0, 1.0, 0.0], "color": [0.0, 1.0, 0.0] },
+                            { "position": [1.0, 1.0, 0.0], "color": [0.0, 1.0, 0.0] },
+                            { "position": [1.0, 1.0, 0.0], "color": [0.0, 1.0, 0.0] },
+                            { "position": [1.0, 2.0, 0.0], "color": [0.0, 1.0, 0.0] }
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let line_sets = geometry.lines.as_ref().unwrap();
+
+        let (vertices, width) = line_set_vertices(&line_sets[0]);
+        assert_eq!(width, 6.0);
+        let (quads, indices) = model_line::wide_line_quads(vertices.chunks_exact(2).map(|pair| (&pair[0], &pair[1])), width);
+        assert_eq!((quads.len(), indices.len()), (4, 6));
+        assert!(quads.iter().all(|quad| quad.width == 6.0));
+
+        let (vertices, width) = line_set_vertices(&line_sets[1]);
+        assert_eq!(width, model_line::LINE_WIDTH);
+        let (quads, indices) = model_line::wide_line_quads(vertices.chunks_exact(2).map(|pair| (&pair[0], &pair[1])), width);
+        assert_eq!((quads.len(), indices.len()), (8, 12));
+        assert!(quads.iter().all(|quad| quad.width == model_line::LINE_WIDTH));
+    }
+
+    #[test]
+    fn binary_geometry_round_trips_with_its_origin_offset() {
+        let geometry = parse_geometry_json(
+            "survey.json",
+            r#"{
+                "metadata": { "version": "1.0", "description": "survey", "created": "" },
+                "points": [{
+                    "name": "survey_points",
+                    "vertices": [
+                        { "position": [512344.75, 5412345.5, 101.25], "color": [1.0, 0.0, 0.0], "size": 1.0 },
+                        { "position": [512345.25, 5412346.0, 100.75], "color": [0.0, 1.0, 0.0], "alpha": 0.5, "size": 2.0 }
+                    ],
+                    "keep_fraction": 0.5,
+                    "seed": 7
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let bytes = encode_geometry_binary(&geometry).unwrap();
+        let decoded = parse_geometry_binary("survey.bin", &bytes).unwrap();
+
+        assert_eq!(decoded.origin_offset, geometry.origin_offset);
+        assert_ne!(decoded.origin_offset, [0.0; 3]);
+        assert_eq!(decoded.metadata.description, "survey");
+        assert_eq!(decoded.metadata.color_range, Some(ColorRange::Float));
+        let (original, decoded) = (&geometry.points.as_ref().unwrap()[0], &decoded.points.as_ref().unwrap()[0]);
+        assert_eq!((decoded.keep_fraction, decoded.seed), (Some(0.5), Some(7)));
+        for (original, decoded) in original.vertices.iter().zip(&decoded.vertices) {
+            assert_eq!((decoded.position, decoded.color, decoded.alpha, decoded.size), (original.position, original.color, original.alpha, original.size));
+        }
+    }
+
+    #[test]
+    fn binary_geometry_with_another_magic_or_version_is_rejected() {
+        let geometry = parse_geometry_json("empty.json", r#"{ "metadata": { "version": "1.0", "description": "", "created": "" } }"#).unwrap();
+        let bytes = encode_geometry_binary(&geometry).unwrap();
+        assert!(parse_geometry_binary("empty.bin", &bytes).is_ok());
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[..4].copy_from_slice(b"GLTF");
+        assert!(parse_geometry_binary("wrong_magic.bin", &wrong_magic).unwrap_err().contains("not a binary geometry file"));
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[4..8].copy_from_slice(&(BINARY_GEOMETRY_VERSION + 1).to_le_bytes());
+        assert!(parse_geometry_binary("wrong_version.bin", &wrong_version).unwrap_err().contains("version"));
+
+        assert!(parse_geometry_binary("short.bin", &bytes[..BINARY_GEOMETRY_HEADER_SIZE - 1]).is_err());
+    }
+
+    #[test]
+    fn face_colors_split_shared_vertices() {
+        // Two triangles of a quad share the diagonal vertices 0 and 2
+        let mut vertices = vec![
+            vertex([0.0, 0.0, 0.0]),
+            vertex([1.0, 0.0, 0.0]),
+            vertex([1.0, 1.0, 0.0]),
+            vertex([0.0, 1.0, 0.0]),
+        ];
+        let red = [1.0, 0.0, 0.0];
+        let blue = [0.0, 0.0, 1.0];
+        let indices = assign_face_colors(&mut vertices, &[0, 1, 2, 0, 2, 3], &[red, blue]);
+
+        assert_eq!(indices, vec![4, 5, 6, 7, 8, 9]);
+        assert_eq!(vertices.len(), 10);
+        for (triangle, color) in indices.chunks_exact(3).zip([red, blue]) {
+            assert!(triangle.iter().all(|&index| vertices[index as usize].color == color));
+        }
+        // Positions and winding are kept, the originals stay in place for the edges
+        assert_eq!(vertices[7].position, [0.0, 0.0, 0.0]);
+        assert_eq!(vertices[9].position, [0.0, 1.0, 0.0]);
+        assert_eq!(vertices[0].color, [0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn triangles_without_face_color_keep_their_vertices() {
+        let mut vertices = vec![vertex([0.0, 0.0, 0.0]), vertex([1.0, 0.0, 0.0]), vertex([0.0, 1.0, 0.0])];
+        let indices = assign_face_colors(&mut vertices, &[0, 1, 2, 2, 1, 0], &[[1.0, 0.0, 0.0]]);
+
+        assert_eq!(indices, vec![3, 4, 5, 2, 1, 0]);
+        assert_eq!(vertices.len(), 6);
+    }
+
+    fn point_colors(color_range: Option<&str>, color: [f32; 3]) -> (Vec<[f32; 3]>, Option<ColorRange>) {
+        let range = color_range.map_or(String::new(), |range| format!(r#", "color_range": "{}""#, range));
+        let json = format!(
+            r#"{{
+                "metadata": {{ "version": "1.0", "description": "", "created": ""{} }},
+                "points": [{{
+                    "name": "points",
+                    "vertices": [
+                        {{ "position": [0.0, 0.0, 0.0], "color": [0.0, 0.0, 0.0], "size": 1.0 }},
+                        {{ "position": [1.0, 0.0, 0.0], "color": {:?}, "size": 1.0 }}
+                    ]
+                }}]
+            }}"#,
+            range, color
+        );
+        let geometry = parse_geometry_json("points.json", &json).unwrap();
+        let colors = geometry.points.as_ref().unwrap()[0].vertices.iter().map(|vertex| vertex.color).collect();
+        (colors, geometry.metadata.color_range)
+    }
+
+    #[test]
+    fn u8_color_range_scales_every_geometry_type() {
+        let geometry = parse_geometry_json("test_u8_colors.json", include_str!("../assets/test_u8_colors.json")).unwrap();
+
+        assert_eq!(geometry.metadata.color_range, Some(ColorRange::Float));
+        assert_close(geometry.points.as_ref().unwrap()[0].vertices[0].color, [1.0, 128.0 / 255.0, 0.0]);
+        assert_close(geometry.lines.as_ref().unwrap()[0].vertices[0].color, [0.0, 160.0 / 255.0, 160.0 / 255.0]);
+        let polygon = &geometry.polygons.as_ref().unwrap()[0].polygons[0];
+        assert_close(polygon.vertices[0].color, [128.0 / 255.0, 0.0, 200.0 / 255.0]);
+    }
+
+    #[test]
+    fn float_color_range_keeps_colors() {
+        assert_eq!(point_colors(Some("float"), [0.25, 0.5, 1.0]), (vec![[0.0; 3], [0.25, 0.5, 1.0]], Some(ColorRange::Float)));
+        // Out of range values are left for the shader to clamp rather than read as 0-255
+        assert_eq!(point_colors(Some("float"), [2.0, 0.5, 1.0]).0[1], [2.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn unset_color_range_is_detected_from_components_above_one() {
+        let (colors, color_range) = point_colors(None, [255.0, 51.0, 0.0]);
+        assert_close(colors[1], [1.0, 0.2, 0.0]);
+        assert_eq!(color_range, Some(ColorRange::Float));
+
+        assert_eq!(point_colors(None, [0.25, 0.5, 1.0]).0[1], [0.25, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn u8_colors_of_only_zeros_and_ones_need_the_explicit_range() {
+        // Detection cannot tell 0-255 colors of 0 and 1 from floats, so they read as white
+        assert_eq!(point_colors(None, [1.0, 1.0, 0.0]).0[1], [1.0, 1.0, 0.0]);
+        assert_close(point_colors(Some("u8"), [1.0, 1.0, 0.0]).0[1], [1.0 / 255.0, 1.0 / 255.0, 0.0]);
+    }
 }
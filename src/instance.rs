@@ -1,18 +1,31 @@
 // Import only what's needed
 use crate::model;
 
+#[derive(Debug, Clone, Copy)]
 pub struct Instance {
     pub position: cgmath::Vector3<f32>,
     pub rotation: cgmath::Quaternion<f32>,
+    pub color: [f32; 3], // Multiplied into the mesh color; white leaves it unchanged
 }
 
 impl Instance {
+    /// Instance with the mesh's own colors (white tint)
+    pub fn new(position: cgmath::Vector3<f32>, rotation: cgmath::Quaternion<f32>) -> Self {
+        Self { position, rotation, color: [1.0, 1.0, 1.0] }
+    }
+
+    /// Tint this instance, e.g. to color markers of a scatter plot by value
+    pub fn with_color(self, color: [f32; 3]) -> Self {
+        Self { color, ..self }
+    }
+
     pub fn to_raw(&self) -> InstanceRaw {
         InstanceRaw {
             model: (cgmath::Matrix4::from_translation(self.position)
                 * cgmath::Matrix4::from(self.rotation))
             .into(),
             normal: cgmath::Matrix3::from(self.rotation).into(),
+            color: self.color,
         }
     }
 }
@@ -23,6 +36,7 @@ impl Instance {
 pub struct InstanceRaw {
     model: [[f32; 4]; 4],
     normal: [[f32; 3]; 3],
+    color: [f32; 3],
 }
 
 impl model::Vertex for InstanceRaw {
@@ -74,6 +88,12 @@ impl model::Vertex for InstanceRaw {
                     shader_location: 11,
                     format: wgpu::VertexFormat::Float32x3,
                 },
+                // Instance color; 12 is taken by the vertex color
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 25]>() as wgpu::BufferAddress,
+                    shader_location: 13,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
             ],
         }
     }
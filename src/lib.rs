@@ -10,6 +10,32 @@ pub enum RenderMode {
     Polygons = 5,
 }
 
+impl RenderMode {
+    /// All render modes, in digit key order (the discriminant is the index)
+    pub fn all() -> &'static [RenderMode] {
+        &[
+            RenderMode::All,
+            RenderMode::Points,
+            RenderMode::Lines,
+            RenderMode::RegularLines,
+            RenderMode::Meshes,
+            RenderMode::Polygons,
+        ]
+    }
+
+    /// Human readable name, e.g. for a mode selector
+    pub fn label(&self) -> &'static str {
+        match self {
+            RenderMode::All => "All",
+            RenderMode::Points => "Points",
+            RenderMode::Lines => "Lines",
+            RenderMode::RegularLines => "Regular Lines",
+            RenderMode::Meshes => "Meshes",
+            RenderMode::Polygons => "Polygons",
+        }
+    }
+}
+
 mod camera;
 mod instance;
 mod model_line;
@@ -27,8 +53,24 @@ mod lib_geometry_manager;
 mod lib_app;
 mod lib_render;
 mod lib_state;
+mod lib_view;
+mod lib_debug_draw;
+mod lib_legend;
+mod lib_labels;
+mod lib_backplate;
+mod lib_background;
+mod lib_target_gizmo;
+mod lib_scene;
+mod lib_depth_pick;
+mod lib_depth_overlay;
+mod lib_outline;
+mod lib_split_view;
+mod lib_frame_pacing;
+mod lib_help_overlay;
+#[cfg(not(target_arch = "wasm32"))]
+mod lib_snapshot;
+pub mod prelude;
 
-use cgmath::prelude::*;
 use winit::{
     event::*,
     window::Window,
@@ -38,7 +80,27 @@ use winit::{
 use wasm_bindgen::prelude::*;
 
 // Re-export State from lib_state module
-pub use lib_state::State;
+pub use lib_state::{BackgroundClear, DisplaySettings, HiddenLineMode, State, SurfaceColorSpace, ViewerConfig};
+pub use geometry_loader::{load_geometry_file, parse_geometry_json, validate_mesh, GeometryCounts, LoadProgress, LoadStage, MeshReport};
+pub use geometry_loader::{encode_geometry_binary, load_geometry_binary, parse_geometry_binary};
+#[cfg(not(target_arch = "wasm32"))]
+pub use geometry_loader::export_geometry_binary;
+pub use geometry_loader::{
+    ColorRange, GeometryData, LineData, LineVertexData, MeshData, MeshVertexData, Metadata, PipeData, PipeSegmentData,
+    PointData, PointVertexData, PolygonData, PolygonMeshData, PolygonVertexData,
+};
+pub use lib_pipeline::BlendMode;
+pub use lib_background::BackgroundTheme;
+pub use geometry_generator::GridVisibility;
+pub use camera::{Camera, CameraAnimation, ControllerMode, DragModifier, MouseBinding, MouseBindingPreset, ProjectionMode, StandardView};
+pub use model_point::{PointColorMode, PointShape};
+pub use lib_render::{LightOrbit, LightSource, MeshShadingMode, MAX_LIGHTS};
+pub use instance::Instance;
+pub use model_pipe::PipeCapStyle;
+pub use lib_view::{CameraSettings, LightSettings, ViewSettings};
+pub use lib_scene::{SceneDescription, SceneFileEntry, SceneTransform};
+#[cfg(not(target_arch = "wasm32"))]
+pub use lib_snapshot::TurntableOptions;
 
 // create_render_pipeline function has been moved to pipeline.rs module
 
@@ -47,16 +109,35 @@ impl<'a> State<'a> {
         self.window
     }
 
+    /// The GPU device the viewer renders with
+    ///
+    /// For embedding code that creates its own buffers, textures or pipelines to use
+    /// alongside the viewer's; resources must come from this device to be compatible.
+    /// After a lost device is recovered the viewer runs on a new device, so such
+    /// resources have to be created again. Prefer this over the `device` field, which
+    /// may stop being public.
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    /// The queue the viewer submits its frames to
+    ///
+    /// Use it to upload data to resources created with [`State::device`]; work submitted
+    /// here is ordered with the viewer's own frames. Prefer this over the `queue` field.
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
-            self.projection.resize(new_size.width, new_size.height);
-            
             // Update aspect ratio in camera uniform
             self.camera_uniform.update_aspect_ratio(new_size.width as f32, new_size.height as f32);
             
             self.size = new_size;
             self.config.width = new_size.width;
             self.config.height = new_size.height;
+            // Each projection follows its viewport, half the window in split view
+            lib_split_view::resize_projections(self);
             self.surface.configure(&self.device, &self.config);
             
             // Create new depth texture directly without texture module
@@ -107,7 +188,7 @@ impl<'a> State<'a> {
                 sample_count: 4, // 4x MSAA for web compatibility
                 dimension: wgpu::TextureDimension::D2,
                 format: wgpu::TextureFormat::Depth32Float,
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING, // Sampled by the depth picker
                 view_formats: &[wgpu::TextureFormat::Depth32Float],
             });
 
@@ -121,36 +202,735 @@ impl<'a> State<'a> {
 
     fn update(&mut self, dt: std::time::Duration) {
         // UPDATED!
+        self.camera_controller.update_projection(&mut self.camera, &mut self.projection);
         self.camera_controller.update_camera(&mut self.camera, dt);
+        lib_target_gizmo::update_target_gizmo(self, dt.as_secs_f32());
+        lib_depth_pick::update_cursor_readout(self);
+        lib_view::draw_scene_extents(self);
+        lib_help_overlay::update_help_overlay(self, dt.as_secs_f32());
+        if self.settings.auto_near_far {
+            lib_view::fit_near_far(self);
+        }
         self.camera_uniform.update_view_proj(&self.camera, &self.projection);
+        self.camera_uniform.update_clip_plane(self.settings.clip_plane);
+        self.cursor_readout.track_view(self.camera_uniform.view_proj());
         self.queue.write_buffer(
             &self.camera_buffer,
             0,
             bytemuck::cast_slice(&[self.camera_uniform]),
         );
 
-        // Update the light
-        let old_position: cgmath::Vector3<_> = self.light_uniform.position.into();
-        self.light_uniform.position =
-            (cgmath::Quaternion::from_axis_angle((0.0, 1.0, 0.0).into(), cgmath::Deg(1.0))
-                * old_position)
-                .into();
+        // Advance the shader clock
+        self.globals_uniform.advance_time(dt.as_secs_f32());
         self.queue.write_buffer(
-            &self.light_buffer,
+            &self.globals_buffer,
             0,
-            bytemuck::cast_slice(&[self.light_uniform]),
+            bytemuck::cast_slice(&[self.globals_uniform]),
         );
+
+        // Orbit the lights, unless stopped for stills
+        if let Some(orbit) = self.settings.light_orbit {
+            self.light_uniform.orbit(&orbit, dt.as_secs_f32());
+            self.write_light_buffer();
+        }
     }
     
-    /// Load geometry data from a JSON file
+    /// Load geometry data from a JSON file or `http(s)://` URL
     async fn load_geometries_from_file(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
         lib_geometry_manager::load_geometries_from_file(self, path).await
     }
 
+    /// Load geometry data from a JSON file, reporting progress for large files
+    ///
+    /// `on_progress` is called while the file is read (bytes read / total) and
+    /// once more when parsing starts and finishes.
+    pub async fn load_geometries_from_file_with_progress<F: FnMut(LoadProgress)>(
+        &mut self,
+        path: &str,
+        on_progress: F,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        lib_geometry_manager::load_geometries_from_file_with_progress(self, path, on_progress).await
+    }
+
+    /// Load a scene file listing several geometry files with transforms and visibility
+    ///
+    /// Replaces the loaded geometry; see `lib_scene` for the file format.
+    pub async fn load_scene(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        lib_scene::load_scene(self, path).await
+    }
+
+    /// Names and visibility of the entries of the loaded scene, in file order
+    pub fn scene_entries(&self) -> Vec<(String, bool)> {
+        self.scene
+            .iter()
+            .flat_map(|scene| &scene.entries)
+            .map(|entry| (entry.name.clone(), entry.visible))
+            .collect()
+    }
+
+    /// Show or hide one entry of the loaded scene
+    pub fn set_scene_entry_visible(&mut self, index: usize, visible: bool) -> Result<(), String> {
+        lib_scene::set_scene_entry_visible(self, index, visible)
+    }
+
+    /// Append a mesh built from raw vertex/index arrays - delegates to the geometry manager
+    ///
+    /// Missing normals are computed from the triangles and missing colors default to grey.
+    pub fn add_mesh(
+        &mut self,
+        name: &str,
+        positions: &[[f32; 3]],
+        normals: Option<&[[f32; 3]]>,
+        colors: Option<&[[f32; 3]]>,
+        indices: &[u32],
+    ) -> Result<(), String> {
+        lib_geometry_manager::add_mesh(self, name, positions, normals, colors, indices)
+    }
+
+    /// Remove mesh `index` (0 is the first mesh) and free its GPU buffers - delegates to the geometry manager
+    ///
+    /// Later meshes move down one index, so indices passed to e.g. `set_mesh_wireframe` shift too.
+    pub fn remove_mesh(&mut self, index: usize) -> Result<(), String> {
+        lib_geometry_manager::remove_mesh(self, index)
+    }
+
+    /// Remove the first mesh named `name` (the JSON or `add_mesh` name)
+    pub fn remove_mesh_by_name(&mut self, name: &str) -> Result<(), String> {
+        let index = lib_geometry_manager::mesh_index(self, name).ok_or_else(|| format!("No mesh named '{}'", name))?;
+        lib_geometry_manager::remove_mesh(self, index)
+    }
+
+    /// Remove polygon set `index` (0 is the first set) and free its GPU buffers
+    ///
+    /// Points and pipes are single sets; clear them by loading other geometry.
+    pub fn remove_polygon_set(&mut self, index: usize) -> Result<(), String> {
+        lib_geometry_manager::remove_polygon_set(self, index)
+    }
+
+    /// Remove the first polygon set named `name`
+    pub fn remove_polygon_set_by_name(&mut self, name: &str) -> Result<(), String> {
+        let index = lib_geometry_manager::polygon_set_index(self, name)
+            .ok_or_else(|| format!("No polygon set named '{}'", name))?;
+        lib_geometry_manager::remove_polygon_set(self, index)
+    }
+
+    /// Center and size of the bounds of the loaded geometry, the box used for framing and
+    /// near/far fitting; `None` before any geometry is loaded
+    pub fn scene_bounds(&self) -> Option<(cgmath::Point3<f32>, [f32; 3])> {
+        lib_view::scene_extents(self)
+    }
+
+    /// Draw the scene bounds as a box with a crosshair at its center, and print the center and size
+    pub fn set_show_scene_extents(&mut self, show: bool) {
+        lib_view::set_show_scene_extents(self, show);
+    }
+
+    /// Frame all drawn geometry: aim at the center of its bounds from the distance that fits it in the view
+    ///
+    /// Keeps the view direction; returns false and leaves the camera untouched when nothing is loaded.
+    pub fn fit_view_to_scene(&mut self) -> bool {
+        lib_view::fit_view_to_scene(self)
+    }
+
+    /// Place the camera eye at `eye` looking at `target`, e.g. to script camera moves
+    ///
+    /// The camera stays level with the Z up axis, and orbiting continues from the new pose.
+    pub fn look_at(&mut self, eye: cgmath::Point3<f32>, target: cgmath::Point3<f32>) {
+        self.camera.look_at(eye, target);
+    }
+
+    /// Camera eye position and target
+    pub fn camera_pose(&self) -> (cgmath::Point3<f32>, cgmath::Point3<f32>) {
+        self.camera.pose()
+    }
+
+    /// Closest and farthest camera distance from the target when zooming (0.5 and 100 by default)
+    ///
+    /// Fitting the view raises the farthest distance for models too large for it.
+    pub fn set_zoom_limits(&mut self, min: f32, max: f32) {
+        self.camera_controller.set_max_zoom(max);
+        self.camera_controller.set_min_zoom(min);
+    }
+
+    /// Look at the camera target from a standard direction (top, front, iso, ...), keeping the distance
+    ///
+    /// The camera eases into the view over the next frames (see `set_camera_transition`).
+    /// Ignored in the locked 2D top-down view, like the numpad keys.
+    pub fn set_standard_view(&mut self, view: StandardView) {
+        lib_view::set_standard_view(self, view);
+    }
+
+    /// Length in seconds of the eased camera moves to the home, startup and standard views (0.4 by default)
+    ///
+    /// 0 jumps straight to the new view. Applies to both cameras in split view.
+    pub fn set_camera_transition(&mut self, seconds: f32) {
+        self.camera.transition_duration = seconds.max(0.0);
+        if let Some(split) = &mut self.split_view {
+            split.other_camera.transition_duration = seconds.max(0.0);
+        }
+    }
+
+    /// Set the vertical field of view in degrees, clamped to 10-120, e.g. to match the lens of a reference photo
+    pub fn set_field_of_view(&mut self, degrees: f32) {
+        self.projection.set_fovy(cgmath::Deg(degrees));
+    }
+
+    /// Vertical field of view in degrees
+    pub fn field_of_view(&self) -> f32 {
+        cgmath::Deg::from(self.projection.fovy).0
+    }
+
+    /// Switch to an orthographic projection (parallel edges stay parallel) or back to perspective
+    ///
+    /// The framing at the camera target is kept. While orthographic, zooming changes the
+    /// visible height (`ProjectionMode::Orthographic::scale`) instead of moving the eye.
+    pub fn set_orthographic(&mut self, orthographic: bool) {
+        lib_view::set_orthographic(self, orthographic);
+    }
+
+    /// Current projection mode
+    pub fn projection_mode(&self) -> ProjectionMode {
+        self.projection.mode
+    }
+
+    /// Draw a line for the current frame only - delegates to the debug draw module
+    pub fn debug_line(&mut self, a: [f32; 3], b: [f32; 3], color: [f32; 4]) {
+        self.debug_draw.line(a, b, color);
+    }
+
+    /// Draw a point marker for the current frame only - delegates to the debug draw module
+    pub fn debug_point(&mut self, position: [f32; 3], color: [f32; 4], size: f32) {
+        self.debug_draw.point(position, color, size);
+    }
+
+    /// Attach a text label to a world position - drawn until `clear_labels` is called
+    pub fn add_label(&mut self, position: [f32; 3], text: &str) {
+        self.labels.add(position, text, lib_labels::LABEL_COLOR);
+    }
+
+    /// Attach a text label drawn in `color`
+    pub fn add_label_with_color(&mut self, position: [f32; 3], text: &str, color: [f32; 4]) {
+        self.labels.add(position, text, color);
+    }
+
+    /// Remove all text labels
+    pub fn clear_labels(&mut self) {
+        self.labels.clear();
+    }
+
+    /// Hide labels behind geometry (`true`) or always draw them on top (`false`, default)
+    pub fn set_label_depth_test(&mut self, enabled: bool) {
+        self.labels.depth_test = enabled;
+    }
+
+    /// Show a color legend for colormapped data: a gradient bar from `colormap(0)` at `min` to
+    /// `colormap(1)` at `max`, labeled with both values, in the bottom right corner
+    ///
+    /// E.g. `set_legend(0.0, 12.5, geometry_generator::colormap_viridis)` for points colored by height.
+    pub fn set_legend(&mut self, min: f32, max: f32, colormap: impl Fn(f32) -> [f32; 4]) {
+        self.legend = Some(lib_legend::Legend::new(&self.device, min, max, colormap));
+    }
+
+    /// Hide the color legend
+    pub fn clear_legend(&mut self) {
+        self.legend = None;
+    }
+
+    /// Show the image at `path` behind the scene at `opacity` (0..1) - delegates to the backplate module
+    pub async fn set_backplate(&mut self, path: &str, opacity: f32) -> Result<(), Box<dyn std::error::Error>> {
+        lib_backplate::set_backplate(self, path, opacity).await
+    }
+
+    /// Fade the backplate image in or out
+    pub fn set_backplate_opacity(&mut self, opacity: f32) {
+        lib_backplate::set_backplate_opacity(self, opacity);
+    }
+
+    /// Show or hide the backplate image without unloading it
+    pub fn set_backplate_visible(&mut self, visible: bool) {
+        if let Some(backplate) = &mut self.backplate {
+            backplate.visible = visible;
+        }
+    }
+
+    /// Unload the backplate image
+    pub fn clear_backplate(&mut self) {
+        self.backplate = None;
+    }
+
+    /// Orbit around the target (default) or fly through the scene first-person
+    pub fn set_controller_mode(&mut self, mode: ControllerMode) {
+        self.camera_controller.set_mode(mode);
+    }
+
+    /// Orbit and pan with the mouse bindings of Blender, Maya, web viewers or this viewer (default)
+    ///
+    /// Single bindings can be changed with `camera_controller.set_orbit_button` and `set_pan_button`.
+    pub fn set_mouse_preset(&mut self, preset: MouseBindingPreset) {
+        self.camera_controller.set_mouse_preset(preset);
+    }
+
+    /// Clear each frame to a color (alpha below 1 for a transparent overlay), a gradient, or keep the previous frame
+    ///
+    /// Natively, a transparent background also needs a window created with transparency.
+    pub fn set_background_clear(&mut self, background_clear: BackgroundClear) {
+        lib_state::set_background_clear(self, background_clear);
+    }
+
+    /// Apply a preset background (solid color or gradient) - delegates to the background module
+    pub fn set_background_theme(&mut self, theme: BackgroundTheme) {
+        lib_background::set_background_theme(self, theme);
+    }
+
+    /// Apply the theme after the current one, as the K key does
+    pub fn cycle_background_theme(&mut self) -> BackgroundTheme {
+        let theme = self.settings.background_theme.next();
+        self.set_background_theme(theme);
+        theme
+    }
+
+    /// Current camera and lights, as saved in a view file
+    pub fn view_settings(&self) -> ViewSettings {
+        lib_view::view_settings(self)
+    }
+
+    /// Restore a camera and light setup from `view_settings`
+    pub fn apply_view_settings(&mut self, settings: &ViewSettings) -> Result<(), String> {
+        lib_view::apply_view_settings(self, settings)
+    }
+
+    /// Show or hide the crosshair at the orbit pivot while orbiting/panning (on by default)
+    pub fn set_target_gizmo_enabled(&mut self, enabled: bool) {
+        self.target_gizmo.enabled = enabled;
+    }
+
+    /// Size the target crosshair (half axis length as a fraction of the camera distance)
+    /// and set how many seconds it takes to fade out after a drag
+    pub fn set_target_gizmo_style(&mut self, size: f32, fade_duration: f32) {
+        self.target_gizmo.size = size.max(0.0);
+        self.target_gizmo.fade_duration = fade_duration.max(0.0);
+    }
+
+    /// Add a point light and return its index; at most `MAX_LIGHTS` lights are lit
+    pub fn add_light(&mut self, position: [f32; 3], color: [f32; 3]) -> Result<usize, String> {
+        let index = self
+            .light_uniform
+            .add(LightSource::new(position, color))
+            .ok_or_else(|| format!("Cannot add light: all {} light slots are in use", MAX_LIGHTS))?;
+        self.write_light_buffer();
+        Ok(index)
+    }
+
+    /// Remove the light at `index`; lights after it move down one index
+    pub fn remove_light(&mut self, index: usize) -> Result<(), String> {
+        let count = self.light_uniform.count;
+        self.light_uniform
+            .remove(index)
+            .ok_or_else(|| format!("Light index {} out of range ({} lights)", index, count))?;
+        self.write_light_buffer();
+        Ok(())
+    }
+
+    /// Move and recolor the light at `index`
+    pub fn set_light(&mut self, index: usize, position: [f32; 3], color: [f32; 3]) -> Result<(), String> {
+        let count = self.light_uniform.count;
+        let light = self
+            .light_uniform
+            .active_mut()
+            .get_mut(index)
+            .ok_or_else(|| format!("Light index {} out of range ({} lights)", index, count))?;
+        *light = LightSource::new(position, color);
+        self.write_light_buffer();
+        Ok(())
+    }
+
+    /// Orbit the lights at `radius` from `axis` (or at their own distance with `None`),
+    /// turning `speed` degrees per second around `axis` through the origin
+    ///
+    /// By default the lights turn 60 degrees per second around the up axis.
+    pub fn set_light_orbit(&mut self, radius: Option<f32>, speed: f32, axis: [f32; 3]) {
+        self.settings.light_orbit = Some(LightOrbit { radius, speed, axis });
+    }
+
+    /// Stop (`false`) or restart with the default orbit (`true`) the light animation;
+    /// stopped lights stay where they are
+    pub fn set_light_orbit_enabled(&mut self, enabled: bool) {
+        self.settings.light_orbit = enabled.then(|| self.settings.light_orbit.unwrap_or_default());
+    }
+
+    /// The current light orbit, `None` while the lights stand still
+    pub fn light_orbit(&self) -> Option<LightOrbit> {
+        self.settings.light_orbit
+    }
+
+    /// Upload the lights to the GPU
+    fn write_light_buffer(&self) {
+        self.queue.write_buffer(
+            &self.light_buffer,
+            0,
+            bytemuck::cast_slice(&[self.light_uniform]),
+        );
+    }
+
+    /// Scale every pipe radius (JSON pipes and pipes converted from lines) and rebuild the pipes
+    pub fn set_pipe_radius_scale(&mut self, scale: f32) {
+        lib_geometry_manager::set_pipe_radius_scale(self, scale);
+    }
+
+    /// Close pipe ends flat or with rounded caps that also fill the joints between segments
+    pub fn set_pipe_cap_style(&mut self, cap_style: PipeCapStyle) {
+        lib_geometry_manager::set_pipe_cap_style(self, cap_style);
+    }
+
+    /// Show or hide the on-screen help with the key bindings, frame rate and geometry counts (F1)
+    pub fn set_help_overlay_visible(&mut self, visible: bool) {
+        self.help_overlay.visible = visible;
+    }
+
+    /// Zoom toward the surface under the cursor when scrolling (default), or toward the camera target
+    ///
+    /// Over the background, in fly mode and on the web the target is always used.
+    pub fn set_zoom_to_cursor(&mut self, enabled: bool) {
+        self.settings.zoom_to_cursor = enabled;
+    }
+
+    /// Replace the frame with the depth buffer as grayscale (black at the near plane, white at the far plane)
+    pub fn set_depth_overlay(&mut self, visible: bool) {
+        self.depth_overlay.visible = visible;
+    }
+
+    /// World point drawn at a window position (physical pixels) in the last frame
+    ///
+    /// Returns `None` over the background and on the web.
+    pub fn pick_world_point(&self, x: f32, y: f32) -> Option<[f32; 3]> {
+        lib_depth_pick::pick_world_point(self, [x, y]).map(Into::into)
+    }
+
+    /// World position under the cursor in the last rendered frame, refreshed as the mouse moves
+    ///
+    /// Viewer coordinates: add `origin_offset` for the coordinates of recentered files.
+    /// Returns `None` over the background, outside the window, while the readout is
+    /// disabled and on the web.
+    pub fn cursor_world_pos(&self) -> Option<cgmath::Point3<f32>> {
+        self.cursor_readout.position.filter(|_| self.cursor_readout.enabled)
+    }
+
+    /// Turn the cursor readout on or off, reading the depth back at most once per
+    /// `interval_secs` (default 0.1) while the cursor moves
+    ///
+    /// Each readback waits for the GPU, so a short interval can cost frame rate.
+    pub fn set_cursor_readout(&mut self, enabled: bool, interval_secs: f32) {
+        self.cursor_readout.enabled = enabled;
+        self.cursor_readout.interval = instant::Duration::from_secs_f32(interval_secs.max(0.0));
+        if enabled {
+            self.cursor_readout.invalidate();
+        } else {
+            self.cursor_readout.position = None;
+        }
+    }
+
+    /// Show two viewports side by side, each with its own camera, or return to a single view
+    ///
+    /// Both viewports start from the current camera. Mouse and keyboard input go to the
+    /// viewport under the cursor; turning split view off keeps the left viewport's camera.
+    pub fn set_split_view(&mut self, enabled: bool) {
+        lib_split_view::set_split_view(self, enabled);
+    }
+
+    /// Whether split view is on
+    pub fn split_view(&self) -> bool {
+        self.split_view.is_some()
+    }
+
+    /// Color points by their vertex color (default) or by local density through a colormap
+    pub fn set_point_color_mode(&mut self, mode: PointColorMode) {
+        self.globals_uniform.set_point_color_mode(mode);
+    }
+
+    /// Draw points as flat discs (default) or as lit spheres that intersect each other
+    pub fn set_point_shape(&mut self, shape: PointShape) {
+        self.settings.point_shape = shape;
+    }
+
+    /// Current point shape
+    pub fn point_shape(&self) -> PointShape {
+        self.settings.point_shape
+    }
+
+    /// Shade meshes with their lit colors (default), or show their texture coordinates as red/green
+    /// or the length of their normals as grey (see `MeshShadingMode`)
+    pub fn set_mesh_shading_mode(&mut self, mode: MeshShadingMode) {
+        self.globals_uniform.set_mesh_shading_mode(mode);
+    }
+
+    /// Scale the per-vertex point sizes by `size`, or `None` to return to the automatic size
+    ///
+    /// The automatic size is derived on load from the spacing of the points relative to the
+    /// scene bounds; an override is kept for later loads.
+    pub fn set_point_size(&mut self, size: Option<f32>) {
+        self.settings.point_size_override = size.map(|size| size.max(0.0));
+        self.globals_uniform.point_size = self.settings.point_size_override.unwrap_or(self.auto_point_size);
+    }
+
+    /// Color meshes loaded from now on where their vertices have no color (default grey 0.7)
+    ///
+    /// Applies to JSON meshes and OBJ faces without a material; geometry already loaded keeps its colors.
+    pub fn set_default_mesh_color(&mut self, color: [f32; 3]) {
+        self.settings.default_mesh_color = color;
+    }
+
+    /// Rescale the normals of meshes loaded from now on to unit length (default off)
+    ///
+    /// For files whose normals are not unit length, which shade too bright or too dark;
+    /// `MeshShadingMode::NormalLength` shows which meshes are affected.
+    pub fn set_normalize_normals(&mut self, normalize: bool) {
+        self.settings.normalize_normals = normalize;
+    }
+
+    /// Multiply the final color of everything drawn by `exposure` (default 1.0, clamped to 0.05..=20)
+    ///
+    /// Brightens a scene that renders too dark without changing its colors or lights.
+    /// The background clear color is not affected.
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.globals_uniform.set_exposure(exposure);
+    }
+
+    /// The current exposure multiplier
+    pub fn exposure(&self) -> f32 {
+        self.globals_uniform.exposure
+    }
+
+    /// Limit the frame rate to `fps` frames per second, or `None` to redraw as fast as possible (default)
+    ///
+    /// Native only; on the web the browser paces frames.
+    pub fn set_fps_cap(&mut self, fps: Option<u32>) {
+        self.frame_pacer.fps_cap = fps;
+    }
+
+    /// Drop to `fps` frames per second after `idle_after_secs` seconds without input, or `None` to keep the active rate
+    ///
+    /// Saves power while the window is left open; the first input returns to the active rate at once.
+    pub fn set_idle_fps_cap(&mut self, fps: Option<u32>, idle_after_secs: f32) {
+        self.frame_pacer.idle_fps_cap = fps;
+        self.frame_pacer.idle_after = instant::Duration::from_secs_f32(idle_after_secs.max(0.0));
+    }
+
+    /// Turn depth writes of points, lines, pipes and polygons on or off in `mode` (on by default)
+    ///
+    /// With depth writes off, e.g. for a dense point cloud, nearer geometry no longer hides
+    /// farther geometry drawn after it. Meshes always write depth.
+    pub fn set_depth_write(&mut self, mode: RenderMode, enabled: bool) {
+        self.settings.depth_write.set(mode, enabled);
+    }
+
+    /// Whether points, lines, pipes and polygons write depth in `mode`
+    pub fn depth_write(&self, mode: RenderMode) -> bool {
+        self.settings.depth_write.get(mode)
+    }
+
+    /// Blend the point cloud with `mode` (additive for glowing particles on dark backgrounds)
+    pub fn set_point_blend_mode(&mut self, mode: BlendMode) {
+        if let Some(model) = &mut self.quad_point_model {
+            model.blend_mode = mode;
+        }
+    }
+
+    /// Blend the lines loaded from JSON with `mode`; the grid keeps its faded alpha blending
+    pub fn set_line_blend_mode(&mut self, mode: BlendMode) {
+        for model in &mut self.content_line_models {
+            model.blend_mode = mode;
+        }
+    }
+
+    /// Treat `front_face` winding as front facing for all meshes - rebuilds the mesh pipelines
+    pub fn set_mesh_front_face(&mut self, front_face: wgpu::FrontFace) {
+        lib_state::set_mesh_front_face(self, front_face);
+    }
+
+    /// Rebuild the scene pipelines from the WGSL files in `src/shaders`, without recompiling
+    ///
+    /// Once called, later pipeline rebuilds read the files too. Shader errors are logged and
+    /// the previous pipelines are kept.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn reload_shaders(&mut self) {
+        lib_state::reload_shaders(self);
+    }
+
+    /// Overlay the edges of mesh `index` (0 is the first mesh) in `color` - delegates to the state module
+    pub fn set_mesh_wireframe(&mut self, index: usize, enabled: bool, color: [f32; 4]) -> Result<(), String> {
+        lib_state::set_mesh_wireframe(self, index, enabled, color)
+    }
+
+    /// Outline mesh `index` (0 is the first mesh) `width` pixels wide in `color`, e.g. to
+    /// highlight a picked object; a width of 0 removes the outline
+    pub fn set_outlined(&mut self, mesh_index: usize, color: [f32; 4], width: f32) -> Result<(), String> {
+        lib_outline::set_outlined(self, mesh_index, color, width)
+    }
+
+    /// Draw mesh `index` (0 is the first mesh) translucent at `opacity` (0 to 1); overlapping
+    /// translucent meshes are sorted back to front by their bounds centers every frame
+    pub fn set_mesh_opacity(&mut self, index: usize, opacity: f32) -> Result<(), String> {
+        lib_state::set_mesh_opacity(self, index, opacity)
+    }
+
+    /// Draw mesh `index` (0 is the first mesh) once per instance, e.g. to scatter copies of a
+    /// loaded tree or bolt; an empty list hides the mesh
+    pub fn set_model_instances(&mut self, index: usize, instances: Vec<Instance>) -> Result<(), String> {
+        lib_state::set_model_instances(self, index, Some(instances))
+    }
+
+    /// Draw mesh `index` with the shared instances again, as before `set_model_instances`
+    pub fn clear_model_instances(&mut self, index: usize) -> Result<(), String> {
+        lib_state::set_model_instances(self, index, None)
+    }
+
+    /// Tint the shared instances (`State::instances`, used by meshes without their own), one color
+    /// per instance; colors are multiplied into the mesh colors, so white (the default) changes nothing
+    ///
+    /// For meshes drawn with their own instances, set `Instance::color` in `set_model_instances`.
+    pub fn set_instance_colors(&mut self, colors: &[[f32; 3]]) -> Result<(), String> {
+        lib_state::set_instance_colors(self, colors)
+    }
+
+    /// Rebuild the reference grid with per-vertex colors from `color_fn` (axes keep their colors)
+    ///
+    /// `color_fn` receives positions in the grid plane, so the colors do not change with the grid height.
+    pub fn set_grid_colors(&mut self, color_fn: impl Fn([f32; 3]) -> [f32; 3] + 'static) {
+        self.settings.grid_color_fn = Box::new(color_fn);
+        self.rebuild_grid();
+    }
+
+    /// Move the reference grid and its axes to `height` along the world up (Z) axis, e.g. to
+    /// the base of a model that does not sit at the origin; the near/far fit follows it
+    pub fn set_grid_height(&mut self, height: f32) {
+        self.settings.grid_height = height;
+        self.rebuild_grid();
+    }
+
+    /// Choose which grid lines and axes are drawn, e.g. the grid without the vertical axis
+    /// or the axes without the grid, for clean captures
+    pub fn set_grid_visibility(&mut self, visibility: GridVisibility) {
+        self.settings.grid_visibility = visibility;
+        self.rebuild_grid();
+    }
+
+    /// Grid lines and axes currently drawn
+    pub fn grid_visibility(&self) -> GridVisibility {
+        self.settings.grid_visibility
+    }
+
+    fn rebuild_grid(&mut self) {
+        self.line_model = Some(geometry_generator::create_grid_lines(&self.device, &self.settings.grid_color_fn, self.settings.grid_height, self.settings.grid_visibility));
+    }
+
+    /// Draw the grid lines `width` pixels wide at any zoom (default 1.5, clamped to 0.5..=10)
+    ///
+    /// The width is applied in screen space, so grid lines do not fatten when zooming in.
+    pub fn set_grid_line_width(&mut self, width: f32) {
+        self.globals_uniform.set_grid_line_width(width);
+    }
+
+    /// Render mode currently in use
+    pub fn render_mode(&self) -> RenderMode {
+        self.settings.render_mode
+    }
+
+    /// Switch the render mode like the number keys do - delegates to the input module
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        lib_input::set_render_mode(self, mode);
+    }
+
+    /// Call `callback` with the new mode whenever the render mode changes, by key or `set_render_mode`
+    pub fn set_on_render_mode_changed(&mut self, callback: impl Fn(RenderMode) + 'static) {
+        self.settings.on_render_mode_changed = Some(Box::new(callback));
+    }
+
+    /// Whether the GPU device was lost (GPU reset, driver update) and the state must be recreated
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Format of the surface the viewer renders into, for matching when compositing its output
+    pub fn surface_format(&self) -> wgpu::TextureFormat {
+        self.config.format
+    }
+
+    /// Re-read the geometry file that was last loaded, e.g. after it changed on disk
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn reload(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        lib_geometry_manager::reload(self)
+    }
+
+    /// Total vertex, triangle, point and segment counts of the loaded geometry
+    pub fn geometry_counts(&self) -> GeometryCounts {
+        self.geometry_counts
+    }
+
+    /// Degenerate triangles, duplicate vertices and non-manifold edges of the most recently
+    /// loaded mesh, `None` before any mesh was loaded (see `validate_mesh`)
+    pub fn last_mesh_report(&self) -> Option<&MeshReport> {
+        self.last_mesh_report.as_ref()
+    }
+
+    /// Offset subtracted from the loaded positions when far-away geometry was recentered
+    pub fn origin_offset(&self) -> [f64; 3] {
+        self.origin_offset
+    }
+
+    /// Convert a scene position back to the original (file) coordinates
+    pub fn to_world_coordinates(&self, position: [f32; 3]) -> [f64; 3] {
+        std::array::from_fn(|i| position[i] as f64 + self.origin_offset[i])
+    }
+
+    /// Convert a position in the original (file) coordinates to the recentered scene
+    pub fn to_local_coordinates(&self, position: [f64; 3]) -> [f32; 3] {
+        std::array::from_fn(|i| (position[i] - self.origin_offset[i]) as f32)
+    }
+
     /// Main rendering method - delegates to the rendering engine module
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         lib_render::render(self)
     }
+
+    /// Render one frame offscreen and save it as a PNG - delegates to the snapshot module
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_to_image(&mut self, width: u32, height: u32, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        lib_snapshot::render_to_image(self, width, height, path)
+    }
+
+    /// Render one frame at `ss_factor` (1 to 4) times the size, box filter it down and save it as a PNG
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_to_image_ss(&mut self, width: u32, height: u32, ss_factor: u32, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        lib_snapshot::render_to_image_ss(self, width, height, ss_factor, path)
+    }
+
+    /// Save the camera (pose, field of view, near/far, clip plane) and lights to a JSON view file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export_view(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        lib_view::export_view(self, path)
+    }
+
+    /// Restore the camera and lights from a JSON view file written by `export_view`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn import_view(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        lib_view::import_view(self, path)
+    }
+
+    /// Orbit the camera 360 degrees around the scene over `frames` frames, writing
+    /// `frame_0000.png`, ... into `out_dir` at the window size, about the world up axis
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture_turntable(&mut self, frames: u32, out_dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut options = TurntableOptions::new(frames, out_dir);
+        options.width = self.config.width;
+        options.height = self.config.height;
+        lib_snapshot::capture_turntable(self, &options)
+    }
+
+    /// Capture a turntable with a custom rotation axis and frame size
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture_turntable_with_options(&mut self, options: &TurntableOptions) -> Result<(), Box<dyn std::error::Error>> {
+        lib_snapshot::capture_turntable(self, options)
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen(start))]
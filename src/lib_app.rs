@@ -2,9 +2,11 @@ use crate::State;
 
 #[cfg(target_arch = "wasm32")]
 use crate::lib_hot_reload::check_reload_flag;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::lib_hot_reload::poll_geometry_file;
 use winit::{
     event::*,
-    event_loop::EventLoop,
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
     keyboard::{KeyCode, PhysicalKey},
 };
 
@@ -22,17 +24,50 @@ pub async fn run() {
         }
     }
 
+    // Headless "render once and exit" mode, e.g. `--snapshot out.png`
+    #[cfg(not(target_arch = "wasm32"))]
+    let snapshot = match crate::lib_snapshot::SnapshotOptions::from_args(std::env::args().skip(1)) {
+        Ok(snapshot) => snapshot,
+        Err(err) => {
+            log::error!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    #[cfg(not(target_arch = "wasm32"))]
+    let turntable = match crate::lib_snapshot::TurntableOptions::from_args(std::env::args().skip(1)) {
+        Ok(turntable) => turntable,
+        Err(err) => {
+            log::error!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    #[cfg(target_arch = "wasm32")]
+    let (snapshot, turntable): (Option<()>, Option<()>) = (None, None);
+    let headless = snapshot.is_some() || turntable.is_some();
+
+    // `--linear-surface` prefers a linear swapchain format with manual gamma in the shaders
+    #[cfg(not(target_arch = "wasm32"))]
+    let color_space = if std::env::args().any(|arg| arg == "--linear-surface") {
+        crate::SurfaceColorSpace::Linear
+    } else {
+        crate::SurfaceColorSpace::Srgb
+    };
+    #[cfg(target_arch = "wasm32")]
+    let color_space = crate::SurfaceColorSpace::default();
+    let viewer_config = crate::ViewerConfig { color_space, ..Default::default() };
+
     let event_loop = EventLoop::new().unwrap();
     let title = env!("CARGO_PKG_NAME");
     
     let window = winit::window::WindowBuilder::new()
         .with_title(title)
         .with_inner_size(winit::dpi::PhysicalSize::new(1920, 1080))
+        .with_visible(!headless)
         .build(&event_loop)
         .unwrap();
 
     #[cfg(not(target_arch = "wasm32"))]
-    {
+    if !headless {
         window.focus_window();
     }
 
@@ -102,7 +137,7 @@ pub async fn run() {
     }
 
     // Create the initial state
-    let mut state = match State::new(&window).await {
+    let mut state = match State::new_with_config(&window, viewer_config).await {
         Ok(state) => state,
         Err(e) => {
             #[cfg(target_arch = "wasm32")]
@@ -117,18 +152,60 @@ pub async fn run() {
         }
     };
     
-    // Load geometries from the JSON file
-    if let Err(err) = state.load_geometries_from_file("assets/sample_geometry.json").await {
+    // Load the scene given with --scene, otherwise the sample geometry JSON file
+    #[cfg(not(target_arch = "wasm32"))]
+    let scene_path = std::env::args().skip_while(|arg| arg != "--scene").nth(1);
+    #[cfg(target_arch = "wasm32")]
+    let scene_path: Option<String> = None;
+    let loaded = match &scene_path {
+        Some(path) => state.load_scene(path).await,
+        None => state.load_geometries_from_file("assets/sample_geometry.json").await,
+    };
+    if let Err(err) = loaded {
         log::error!("Failed to load geometries from file: {}", err);
     } else {
         log::info!("Successfully loaded geometries from file");
+        log::info!("Geometry counts: {}", state.geometry_counts());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(options) = snapshot {
+        match crate::lib_snapshot::capture_snapshot(&mut state, &options) {
+            Ok(()) => return,
+            Err(err) => {
+                log::error!("Failed to write snapshot {}: {}", options.output_path, err);
+                std::process::exit(1);
+            }
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(options) = turntable {
+        match crate::lib_snapshot::capture_turntable(&mut state, &options) {
+            Ok(()) => return,
+            Err(err) => {
+                log::error!("Failed to write turntable to {}: {}", options.output_dir, err);
+                std::process::exit(1);
+            }
+        }
     }
     
     // Only grid lines and JSON-loaded geometry should be displayed
     // Sample hardcoded geometry creation removed as per user request
     
     let mut last_render_time = instant::Instant::now();
+    let mut device_loss_recovery_attempted = false;
+    // Taken out while the state is recreated after a device loss
+    let mut state_slot = Some(state);
     event_loop.run(move |event, control_flow| {
+        let Some(state) = state_slot.as_mut() else {
+            return;
+        };
+        // Input ends the idle frame rate; draw at once instead of waiting for the next idle frame
+        if let Event::WindowEvent { event: window_event, .. } = &event {
+            if is_user_input(window_event) && register_activity(state) {
+                state.window().request_redraw();
+            }
+        }
         match event {
             // NEW!
             Event::DeviceEvent {
@@ -137,8 +214,15 @@ pub async fn run() {
             } => {
                 // Let the camera controller handle mouse movements directly
                 // It will determine whether to rotate based on if is_rotating is true
-                state.camera_controller.process_mouse(delta.0, delta.1)
+                state.camera_controller.process_mouse(delta.0, delta.1);
+                register_activity(state);
             }
+            // A capped frame is due
+            Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                control_flow.set_control_flow(ControlFlow::Wait);
+                state.window().request_redraw();
+            }
+
             // UPDATED!
             Event::WindowEvent {
                 ref event,
@@ -161,14 +245,48 @@ pub async fn run() {
                     }
                     // UPDATED!
                     WindowEvent::RedrawRequested => {
-                        state.window().request_redraw();
+                        if state.is_device_lost() {
+                            if device_loss_recovery_attempted {
+                                log::error!("GPU device lost again after recovery, giving up");
+                                control_flow.exit();
+                                return;
+                            }
+                            device_loss_recovery_attempted = true;
+                            #[cfg(not(target_arch = "wasm32"))]
+                            {
+                                let lost_state = state_slot.take().expect("the state is only taken here");
+                                match recover_from_device_loss(lost_state) {
+                                    Ok(new_state) => {
+                                        log::info!("Recovered from GPU device loss");
+                                        new_state.window().request_redraw();
+                                        state_slot = Some(new_state);
+                                    }
+                                    Err(err) => {
+                                        log::error!("Failed to recover from GPU device loss: {}", err);
+                                        control_flow.exit();
+                                    }
+                                }
+                                return;
+                            }
+                            #[cfg(target_arch = "wasm32")]
+                            {
+                                web_sys::console::error_1(&"GPU device lost - reload the page to restart the viewer".into());
+                                return;
+                            }
+                        }
+
                         let now = instant::Instant::now();
                         let dt = now - last_render_time;
                         last_render_time = now;
+                        schedule_next_frame(state, control_flow, now);
                         
                         // Check for hot reload flag (WASM only)
                         #[cfg(target_arch = "wasm32")]
-                        check_reload_flag(&mut state);
+                        check_reload_flag(state);
+                        
+                        // Reload the open file when polling is configured and it changed (native only)
+                        #[cfg(not(target_arch = "wasm32"))]
+                        poll_geometry_file(state);
                         
                         state.update(dt);
                         match state.render() {
@@ -188,3 +306,187 @@ pub async fn run() {
         }
     }).unwrap();
 }
+
+/// Whether `event` comes from the user (keyboard, mouse, touch or a window resize)
+fn is_user_input(event: &WindowEvent) -> bool {
+    matches!(
+        event,
+        WindowEvent::KeyboardInput { .. }
+            | WindowEvent::MouseInput { .. }
+            | WindowEvent::MouseWheel { .. }
+            | WindowEvent::CursorMoved { .. }
+            | WindowEvent::Touch(_)
+            | WindowEvent::Resized(_)
+    )
+}
+
+/// Record input for the idle frame-rate cap; true when the viewer was idle until now
+fn register_activity(state: &mut State) -> bool {
+    state.frame_pacer.register_activity(instant::Instant::now())
+}
+
+/// Redraw continuously, or wait until the next frame is due when a frame-rate cap applies
+#[cfg(not(target_arch = "wasm32"))]
+fn schedule_next_frame(state: &State, control_flow: &EventLoopWindowTarget<()>, now: instant::Instant) {
+    match state.frame_pacer.next_frame_time(now) {
+        Some(next_frame) => control_flow.set_control_flow(ControlFlow::WaitUntil(next_frame)),
+        None => state.window().request_redraw(),
+    }
+}
+
+/// The browser paces frames on the web, so the caps do not apply
+#[cfg(target_arch = "wasm32")]
+fn schedule_next_frame(state: &State, _control_flow: &EventLoopWindowTarget<()>, _now: instant::Instant) {
+    state.window().request_redraw();
+}
+
+/// Recreate the GPU state after the device was lost and reload the open geometry
+///
+/// The old surface and device are released before the new ones are created on the same window.
+#[cfg(not(target_arch = "wasm32"))]
+fn recover_from_device_loss(state: State<'_>) -> Result<State<'_>, Box<dyn std::error::Error>> {
+    log::warn!("Reinitializing the GPU context after device loss");
+    let carried = CarriedState::take(state);
+    let mut new_state = pollster::block_on(State::new_with_config(carried.window, carried.viewer_config.clone()))?;
+    carried.restore(&mut new_state)?;
+    Ok(new_state)
+}
+
+/// The part of a State that outlives its GPU device
+///
+/// The display settings, cameras, controller, lights and frame-rate caps move over whole;
+/// the legend, backplate, labels, mesh outlines and the open geometry keep only their CPU
+/// side and are rebuilt on the new device. The top-down view is left.
+#[cfg(not(target_arch = "wasm32"))]
+struct CarriedState<'a> {
+    window: &'a winit::window::Window,
+    viewer_config: crate::ViewerConfig,
+    settings: crate::lib_state::DisplaySettings,
+    camera: crate::camera::Camera,
+    left_top_down: bool,
+    projection: crate::camera::Projection,
+    camera_controller: crate::camera::CameraController,
+    split_view: Option<crate::lib_split_view::SplitView>,
+    frame_pacer: crate::lib_frame_pacing::FramePacer,
+    cursor_readout: crate::lib_depth_pick::CursorReadout,
+    help_overlay_visible: bool,
+    light_uniform: crate::lib_render::LightUniform,
+    globals_uniform: crate::lib_render::GlobalsUniform,
+    mesh_front_face: wgpu::FrontFace,
+    labels: crate::lib_labels::Labels,
+    legend: Option<(f32, f32, Vec<[f32; 4]>)>, // Minimum, maximum and colormap samples
+    backplate: Option<(String, f32, bool)>, // Path, opacity and visibility
+    outlines: Vec<(usize, [f32; 4], f32)>, // Mesh index, color and width
+    geometry_path: Option<String>,
+    scene: Option<crate::lib_scene::Scene>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<'a> CarriedState<'a> {
+    /// Move the surviving parts out of `state` without their GPU resources; the rest, surface
+    /// and device included, is dropped
+    fn take(state: State<'a>) -> Self {
+        let outlines = std::iter::once(&state.obj_model)
+            .chain(&state.additional_mesh_models)
+            .enumerate()
+            .filter_map(|(index, model)| model.outline.as_ref().map(|outline| (index, outline.color, outline.width)))
+            .collect();
+        let State {
+            window,
+            viewer_config,
+            settings,
+            camera,
+            saved_perspective_camera,
+            projection,
+            camera_controller,
+            split_view,
+            frame_pacer,
+            cursor_readout,
+            help_overlay,
+            light_uniform,
+            globals_uniform,
+            mesh_front_face,
+            labels,
+            legend,
+            backplate,
+            geometry_path,
+            scene,
+            ..
+        } = state;
+        Self {
+            window,
+            viewer_config,
+            settings,
+            left_top_down: saved_perspective_camera.is_some(),
+            camera: saved_perspective_camera.unwrap_or(camera),
+            projection,
+            camera_controller,
+            split_view,
+            frame_pacer,
+            cursor_readout,
+            help_overlay_visible: help_overlay.visible,
+            light_uniform,
+            globals_uniform,
+            mesh_front_face,
+            labels: labels.detached(),
+            legend: legend.map(|legend| (legend.min, legend.max, legend.colors)),
+            backplate: backplate.map(|backplate| (backplate.path, backplate.opacity, backplate.visible)),
+            outlines,
+            geometry_path,
+            scene,
+        }
+    }
+
+    /// Move everything into the freshly created `state` and rebuild its GPU resources
+    fn restore(self, state: &mut State<'a>) -> Result<(), Box<dyn std::error::Error>> {
+        state.settings = self.settings;
+        state.set_background_clear(state.settings.background_clear);
+        state.rebuild_grid();
+
+        state.camera = self.camera;
+        state.projection = self.projection;
+        state.camera_controller = self.camera_controller;
+        if self.left_top_down {
+            state.projection.mode = crate::camera::ProjectionMode::Perspective;
+            state.camera_controller.set_orbit_locked(false);
+        }
+        state.split_view = self.split_view;
+        crate::lib_split_view::resize_projections(state);
+        state.frame_pacer = self.frame_pacer;
+        state.cursor_readout = self.cursor_readout;
+        state.cursor_readout.invalidate();
+        state.help_overlay.visible = self.help_overlay_visible;
+
+        state.light_uniform = self.light_uniform;
+        state.write_light_buffer();
+        // The surface format is the same, but the sRGB encoding belongs to the new surface
+        let encode_srgb = state.globals_uniform.encode_srgb;
+        state.globals_uniform = self.globals_uniform;
+        state.globals_uniform.encode_srgb = encode_srgb;
+        if self.mesh_front_face != state.mesh_front_face {
+            state.set_mesh_front_face(self.mesh_front_face);
+        }
+
+        state.labels = self.labels;
+        state.legend = self.legend.map(|(min, max, colors)| crate::lib_legend::Legend::from_colors(&state.device, min, max, colors));
+        if let Some((path, opacity, visible)) = self.backplate {
+            // A backplate that no longer loads is not worth losing the session over
+            match pollster::block_on(state.set_backplate(&path, opacity)) {
+                Ok(()) => state.set_backplate_visible(visible),
+                Err(err) => log::error!("Failed to reload backplate {}: {}", path, err),
+            }
+        }
+
+        if let Some(path) = self.geometry_path {
+            pollster::block_on(state.load_geometries_from_file(&path))?;
+        } else if let Some(scene) = self.scene {
+            // The parsed scene is kept on the CPU, only its GPU models are rebuilt
+            state.scene = Some(scene);
+            crate::lib_scene::apply_scene(state)?;
+        }
+        for (index, color, width) in self.outlines {
+            state.set_outlined(index, color, width)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! # Background Module
+//!
+//! Preset background themes and the vertical gradient background. Plain themes are a
+//! clear color; a gradient is drawn as a full-screen triangle at the start of the pass,
+//! behind the backplate and the geometry, without touching depth.
+
+use crate::lib_state::{BackgroundClear, State};
+use wgpu::util::DeviceExt;
+
+/// Background presets cycled with the K key
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum BackgroundTheme {
+    #[default]
+    LightGrey,
+    Dark,
+    White,
+    Black,
+    BlueGradient,
+}
+
+impl BackgroundTheme {
+    /// All themes, in cycle order
+    pub fn all() -> &'static [BackgroundTheme] {
+        &[
+            BackgroundTheme::LightGrey,
+            BackgroundTheme::Dark,
+            BackgroundTheme::White,
+            BackgroundTheme::Black,
+            BackgroundTheme::BlueGradient,
+        ]
+    }
+
+    /// Human readable name, e.g. for a theme selector
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackgroundTheme::LightGrey => "Light Grey",
+            BackgroundTheme::Dark => "Dark",
+            BackgroundTheme::White => "White",
+            BackgroundTheme::Black => "Black",
+            BackgroundTheme::BlueGradient => "Blue Gradient",
+        }
+    }
+
+    /// Next theme, wrapping around after the last
+    pub fn next(self) -> Self {
+        let themes = Self::all();
+        let index = themes.iter().position(|&theme| theme == self).unwrap_or(0);
+        themes[(index + 1) % themes.len()]
+    }
+
+    /// How the frame is cleared for this theme
+    pub fn background_clear(&self) -> BackgroundClear {
+        match self {
+            BackgroundTheme::LightGrey => BackgroundClear::default(),
+            BackgroundTheme::Dark => BackgroundClear::Color([0.12, 0.12, 0.14, 1.0]),
+            BackgroundTheme::White => BackgroundClear::Color([1.0, 1.0, 1.0, 1.0]),
+            BackgroundTheme::Black => BackgroundClear::Color([0.0, 0.0, 0.0, 1.0]),
+            BackgroundTheme::BlueGradient => BackgroundClear::Gradient {
+                top: [0.30, 0.45, 0.70],
+                bottom: [0.02, 0.04, 0.12],
+            },
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct GradientUniform {
+    top: [f32; 4],    // Color at the top edge of the window, alpha unused
+    bottom: [f32; 4], // Color at the bottom edge of the window
+}
+
+/// Pipeline and colors of the gradient background (group 3 of its pipeline)
+pub struct BackgroundGradient {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl BackgroundGradient {
+    /// Build the full-screen gradient pipeline
+    ///
+    /// `shared_bind_group_layouts` are the camera, light and globals layouts
+    /// used by every other pipeline; the gradient colors are bound at group 3.
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+        shared_bind_group_layouts: [&wgpu::BindGroupLayout; 3],
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("background_gradient_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Background Gradient Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[GradientUniform { top: [0.0; 4], bottom: [0.0; 4] }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("background_gradient_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let [camera_layout, light_layout, globals_layout] = shared_bind_group_layouts;
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Background Gradient Pipeline Layout"),
+            bind_group_layouts: &[camera_layout, light_layout, globals_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Background Gradient Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/background.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Background Gradient Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[], // Full-screen triangle from the vertex index
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            // The pass shares the scene's depth attachment but neither reads nor writes it
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 4,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self { pipeline, uniform_buffer, bind_group }
+    }
+
+    /// Upload the top and bottom colors
+    pub fn set_colors(&self, queue: &wgpu::Queue, top: [f32; 3], bottom: [f32; 3]) {
+        let uniform = GradientUniform {
+            top: [top[0], top[1], top[2], 1.0],
+            bottom: [bottom[0], bottom[1], bottom[2], 1.0],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+}
+
+/// Draw the gradient before anything else in the pass, if the background is a gradient
+pub fn render_background_gradient<'a>(state: &'a State, render_pass: &mut wgpu::RenderPass<'a>) {
+    if !matches!(state.settings.background_clear, BackgroundClear::Gradient { .. }) {
+        return;
+    }
+    let gradient = &state.background_gradient;
+    render_pass.set_pipeline(&gradient.pipeline);
+    render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+    render_pass.set_bind_group(3, &gradient.bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
+
+/// Switch to a preset theme and remember it for cycling
+pub fn set_background_theme(state: &mut State, theme: BackgroundTheme) {
+    state.settings.background_theme = theme;
+    crate::lib_state::set_background_clear(state, theme.background_clear());
+}
@@ -0,0 +1,325 @@
+//! # Backplate Module
+//!
+//! A reference image (e.g. a photo to model against) drawn over the whole window
+//! behind the geometry. It is the first draw of the frame, never writes depth and
+//! fades over the background with an adjustable opacity. The image keeps its
+//! aspect ratio and is letterboxed when the window shape differs.
+
+use crate::lib_state::State;
+use cfg_if::cfg_if;
+use wgpu::util::DeviceExt;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct BackplateUniform {
+    opacity: f32,
+    image_aspect: f32, // Image width / height
+    _padding: [f32; 2],
+}
+
+/// A loaded backplate image and its bind group (group 3 of the backplate pipeline)
+pub struct Backplate {
+    pub path: String,
+    pub opacity: f32,
+    pub visible: bool,
+    image_aspect: f32,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Backplate {
+    fn uniform(&self) -> BackplateUniform {
+        BackplateUniform {
+            opacity: self.opacity,
+            image_aspect: self.image_aspect,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// Backplate pipeline, shared by every image that gets loaded
+pub struct BackplateRenderer {
+    pub pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl BackplateRenderer {
+    /// Build the full-screen backplate pipeline
+    ///
+    /// `shared_bind_group_layouts` are the camera, light and globals layouts
+    /// used by every other pipeline; the image is bound at group 3.
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+        shared_bind_group_layouts: [&wgpu::BindGroupLayout; 3],
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("backplate_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Backplate Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let [camera_layout, light_layout, globals_layout] = shared_bind_group_layouts;
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Backplate Pipeline Layout"),
+            bind_group_layouts: &[camera_layout, light_layout, globals_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Backplate Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/backplate.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Backplate Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[], // Full-screen triangle from the vertex index
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            // The pass shares the scene's depth attachment but neither reads nor writes it
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 4, // Enable 4x MSAA for web compatibility
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self { pipeline, bind_group_layout, sampler }
+    }
+
+    /// Upload a decoded image as a backplate texture; it must be non-empty and within the texture limits
+    fn create_backplate(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: &str,
+        image: &image::RgbaImage,
+        opacity: f32,
+    ) -> Backplate {
+        let (width, height) = image.dimensions();
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Backplate Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb, // Photos are sRGB encoded
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            image,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let backplate_uniform = BackplateUniform {
+            opacity: opacity.clamp(0.0, 1.0),
+            image_aspect: width as f32 / height.max(1) as f32,
+            _padding: [0.0; 2],
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Backplate Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[backplate_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("backplate_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        Backplate {
+            path: path.to_string(),
+            opacity: backplate_uniform.opacity,
+            visible: true,
+            image_aspect: backplate_uniform.image_aspect,
+            uniform_buffer,
+            bind_group,
+        }
+    }
+}
+
+/// Read an image file (native) or fetch it from the assets folder (web)
+async fn load_image_bytes(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            let file_name = path.strip_prefix("assets/").unwrap_or(path);
+            let url = crate::geometry_loader::format_url(file_name);
+            let bytes = reqwest::get(url).await?.bytes().await?.to_vec();
+        } else {
+            let bytes = std::fs::read(path)?;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Check that `image` can become a texture, downscaling it to fit `max_dimension`
+///
+/// An empty image is an error; a larger one keeps its aspect ratio, which is all the
+/// backplate needs from a reference photo.
+fn fit_to_texture_limit(image: image::RgbaImage, max_dimension: u32) -> Result<image::RgbaImage, String> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err(format!("the image is empty ({}x{})", width, height));
+    }
+    if width <= max_dimension && height <= max_dimension {
+        return Ok(image);
+    }
+    let scale = max_dimension as f64 / width.max(height) as f64;
+    let fitted_width = ((width as f64 * scale).round() as u32).clamp(1, max_dimension);
+    let fitted_height = ((height as f64 * scale).round() as u32).clamp(1, max_dimension);
+    log::warn!(
+        "Backplate image {}x{} exceeds the maximum texture size {}, downscaled to {}x{}",
+        width, height, max_dimension, fitted_width, fitted_height
+    );
+    Ok(image::imageops::resize(&image, fitted_width, fitted_height, image::imageops::FilterType::Triangle))
+}
+
+/// Load the image at `path` as the backplate, replacing any previous one
+pub async fn set_backplate(state: &mut State<'_>, path: &str, opacity: f32) -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = load_image_bytes(path).await?;
+    let image = image::load_from_memory(&bytes)?.to_rgba8();
+    let image = fit_to_texture_limit(image, state.device.limits().max_texture_dimension_2d)
+        .map_err(|error| format!("Backplate {}: {}", path, error))?;
+    state.backplate = Some(state.backplate_renderer.create_backplate(&state.device, &state.queue, path, &image, opacity));
+    Ok(())
+}
+
+/// Fade the backplate; 0 is invisible, 1 fully covers the background
+pub fn set_backplate_opacity(state: &mut State, opacity: f32) {
+    if let Some(backplate) = &mut state.backplate {
+        backplate.opacity = opacity.clamp(0.0, 1.0);
+        state.queue.write_buffer(&backplate.uniform_buffer, 0, bytemuck::cast_slice(&[backplate.uniform()]));
+    }
+}
+
+/// Draw the backplate before anything else in the pass
+pub fn render_backplate<'a>(state: &'a State, render_pass: &mut wgpu::RenderPass<'a>) {
+    let Some(backplate) = &state.backplate else {
+        return;
+    };
+    if !backplate.visible || backplate.opacity <= 0.0 {
+        return;
+    }
+    render_pass.set_pipeline(&state.backplate_renderer.pipeline);
+    render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+    render_pass.set_bind_group(3, &backplate.bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_images_are_rejected() {
+        assert!(fit_to_texture_limit(image::RgbaImage::new(0, 16), 8192).is_err());
+        assert!(fit_to_texture_limit(image::RgbaImage::new(16, 0), 8192).is_err());
+    }
+
+    #[test]
+    fn images_within_the_limit_are_kept() {
+        let image = fit_to_texture_limit(image::RgbaImage::new(64, 32), 64).unwrap();
+        assert_eq!(image.dimensions(), (64, 32));
+    }
+
+    #[test]
+    fn oversized_images_are_downscaled_keeping_the_aspect_ratio() {
+        let image = fit_to_texture_limit(image::RgbaImage::new(400, 100), 200).unwrap();
+        assert_eq!(image.dimensions(), (200, 50));
+        let image = fit_to_texture_limit(image::RgbaImage::new(10, 5000), 1000).unwrap();
+        assert_eq!(image.dimensions(), (2, 1000));
+    }
+}
@@ -0,0 +1,72 @@
+//! # Debug Draw Module
+//!
+//! Immediate-mode lines and points for visualizing algorithm state on top of the
+//! geometry without building models. Calls made during a frame are buffered in
+//! `DebugDraw`, uploaded once when the frame is encoded, drawn after the scene
+//! and cleared once the frame has been submitted.
+
+use crate::lib_state::State;
+use crate::model_line::{DrawLines, LineModel, LineVertex};
+use crate::model_point::{DrawQuadPoints, PointVertex, QuadPointModel};
+
+/// Transient per-frame debug lines and points
+#[derive(Debug, Default)]
+pub struct DebugDraw {
+    line_vertices: Vec<LineVertex>,
+    points: Vec<PointVertex>,
+}
+
+impl DebugDraw {
+    /// Queue a line segment from `a` to `b` for the current frame
+    pub fn line(&mut self, a: [f32; 3], b: [f32; 3], color: [f32; 4]) {
+        self.line_vertices.push(LineVertex::new(a, color));
+        self.line_vertices.push(LineVertex::new(b, color));
+    }
+
+    /// Queue a point marker for the current frame
+    pub fn point(&mut self, position: [f32; 3], color: [f32; 4], size: f32) {
+        self.points.push(PointVertex { position, color, size });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.line_vertices.is_empty() && self.points.is_empty()
+    }
+
+    /// Drop everything queued for the previous frame
+    pub fn clear(&mut self) {
+        self.line_vertices.clear();
+        self.points.clear();
+    }
+
+    /// Upload the queued primitives into transient GPU models
+    pub fn upload(&self, device: &wgpu::Device) -> DebugDrawModels {
+        DebugDrawModels {
+            lines: (!self.line_vertices.is_empty())
+                .then(|| LineModel::new(device, "Debug", &self.line_vertices)),
+            points: (!self.points.is_empty())
+                .then(|| QuadPointModel::new(device, "Debug", &self.points)),
+        }
+    }
+}
+
+/// GPU buffers for one frame of debug drawing
+pub struct DebugDrawModels {
+    lines: Option<LineModel>,
+    points: Option<QuadPointModel>,
+}
+
+/// Draw the uploaded debug primitives with the regular line and point pipelines
+pub fn render_debug_draw<'a>(
+    state: &'a State,
+    models: &'a DebugDrawModels,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    if let (Some(pipelines), Some(model)) = (&state.pipelines.line_pipeline, &models.lines) {
+        render_pass.set_pipeline(pipelines.get(model.blend_mode));
+        render_pass.draw_lines(model, &state.camera_bind_group);
+    }
+    if let (Some(pipelines), Some(model)) = (&state.pipelines.point_pipeline, &models.points) {
+        render_pass.set_pipeline(pipelines.get(model.blend_mode));
+        render_pass.draw_quad_points(model, &state.camera_bind_group);
+    }
+}
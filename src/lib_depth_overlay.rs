@@ -0,0 +1,181 @@
+//! # Depth Overlay Module
+//!
+//! A debug view that replaces the frame with the depth buffer as grayscale, black at
+//! the near plane and white at the far plane (and over the background). Depth is
+//! linearized with the projection's near/far distances, so the gray level is
+//! proportional to the distance from the camera. This makes near/far clipping and
+//! z-fighting (surfaces sharing one depth) easy to spot.
+//!
+//! The depth attachment cannot be read in the pass that writes it, so the overlay is
+//! a second pass drawn over the resolved frame after the scene pass.
+
+use crate::State;
+use wgpu::util::DeviceExt;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DepthOverlayUniform {
+    znear: f32,
+    zfar: f32,
+    orthographic: f32, // 1.0 when the projection is orthographic, where depth is already linear
+    _padding: f32,
+}
+
+/// Pipeline and uniforms of the depth overlay pass
+pub struct DepthOverlay {
+    pub visible: bool,
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+}
+
+impl DepthOverlay {
+    /// Build the full-screen overlay pipeline
+    ///
+    /// It reads the 4x multisampled depth buffer and writes the resolved (single
+    /// sample) frame, so it uses neither a depth attachment nor MSAA. `globals_layout`
+    /// provides the sRGB encoding flag of the surface.
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        globals_layout: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("depth_overlay_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: true,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Depth Overlay Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout, globals_layout],
+            push_constant_ranges: &[],
+        });
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Depth Overlay Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/depth_overlay.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Depth Overlay Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[], // Full-screen triangle from the vertex index
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Depth Overlay Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[DepthOverlayUniform {
+                znear: 0.1,
+                zfar: 100.0,
+                orthographic: 0.0,
+                _padding: 0.0,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            visible: false,
+            pipeline,
+            bind_group_layout,
+            uniform_buffer,
+        }
+    }
+}
+
+/// Draw the depth buffer as grayscale over `resolve_view`, if the overlay is visible
+///
+/// `depth_view` is the multisampled depth the scene was just rendered with.
+pub fn render_depth_overlay(
+    state: &State,
+    encoder: &mut wgpu::CommandEncoder,
+    depth_view: &wgpu::TextureView,
+    resolve_view: &wgpu::TextureView,
+) {
+    let overlay = &state.depth_overlay;
+    if !overlay.visible {
+        return;
+    }
+    let uniform = DepthOverlayUniform {
+        znear: state.projection.znear,
+        zfar: state.projection.zfar,
+        orthographic: if state.projection.is_orthographic() { 1.0 } else { 0.0 },
+        _padding: 0.0,
+    };
+    state.queue.write_buffer(&overlay.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    // The depth view is recreated on resize (and differs for snapshots), so the bind group is built per frame
+    let bind_group = state.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("depth_overlay_bind_group"),
+        layout: &overlay.bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(depth_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: overlay.uniform_buffer.as_entire_binding(),
+            },
+        ],
+    });
+
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Depth Overlay Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: resolve_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Load,
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    });
+    render_pass.set_pipeline(&overlay.pipeline);
+    render_pass.set_bind_group(0, &bind_group, &[]);
+    render_pass.set_bind_group(1, &state.globals_bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
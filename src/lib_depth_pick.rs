@@ -0,0 +1,258 @@
+//! # Depth Pick Module
+//!
+//! Reads back the depth of the last rendered frame under a pixel and unprojects it to
+//! the world point drawn there. Scrolling uses it to zoom toward the surface under the
+//! cursor. A one-thread compute pass copies the nearest sample of the multisampled
+//! depth buffer into a 4 byte buffer, so only that value crosses back to the CPU.
+//!
+//! The readback blocks until the GPU is done, which is only possible natively; on the
+//! web no point is picked and zooming falls back to the camera target.
+//!
+//! The cursor readout keeps the world point under the cursor up to date for coordinate
+//! displays. It picks again only after the cursor moved, at most once per `interval`,
+//! so a moving mouse does not stall every frame on the readback.
+
+use crate::State;
+use cgmath::{Matrix4, Point3, SquareMatrix, Vector4};
+use instant::{Duration, Instant};
+use wgpu::util::DeviceExt;
+
+/// Shortest time between two cursor readout picks, unless set with `set_cursor_readout`
+pub const DEFAULT_CURSOR_READOUT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// World position under the cursor, refreshed after the cursor moves
+#[derive(Debug, Clone)]
+pub struct CursorReadout {
+    pub enabled: bool,
+    pub interval: Duration,             // Throttle between readbacks
+    pub position: Option<Point3<f32>>,  // None over the background, outside the window or on the web
+    pending: bool,                      // The cursor or the view changed since the last pick
+    last_pick: Option<Instant>,
+    last_view_proj: [[f32; 4]; 4],      // View-projection of the last update, to notice camera moves
+}
+
+impl Default for CursorReadout {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: DEFAULT_CURSOR_READOUT_INTERVAL,
+            position: None,
+            pending: false,
+            last_pick: None,
+            last_view_proj: [[0.0; 4]; 4],
+        }
+    }
+}
+
+impl CursorReadout {
+    /// Pick again at the next update the throttle allows, e.g. after the cursor moved
+    pub fn invalidate(&mut self) {
+        self.pending = true;
+    }
+
+    /// Pick again when `view_proj` differs from the last update's
+    ///
+    /// Catches every camera move, not only mouse input: keyboard pan and orbit, standard
+    /// views and animated view transitions.
+    pub fn track_view(&mut self, view_proj: [[f32; 4]; 4]) {
+        if view_proj != self.last_view_proj {
+            self.last_view_proj = view_proj;
+            self.pending = true;
+        }
+    }
+
+    /// The cursor left the window: nothing is under it until it moves again
+    pub fn clear(&mut self) {
+        self.position = None;
+        self.pending = false;
+    }
+}
+
+/// Refresh the cursor readout from the last rendered frame, if it is due
+pub fn update_cursor_readout(state: &mut State) {
+    let now = Instant::now();
+    let readout = &state.cursor_readout;
+    if !readout.enabled || !readout.pending {
+        return;
+    }
+    if readout.last_pick.is_some_and(|last_pick| now.duration_since(last_pick) < readout.interval) {
+        return;
+    }
+    let position = state.cursor_position.and_then(|cursor| pick_world_point(state, cursor));
+    let readout = &mut state.cursor_readout;
+    readout.position = position;
+    readout.pending = false;
+    readout.last_pick = Some(now);
+}
+
+/// Compute pipeline and buffers reading the depth under one pixel
+pub struct DepthPicker {
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pixel_buffer: wgpu::Buffer,
+    depth_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+}
+
+impl DepthPicker {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("depth_pick_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: true,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Depth Pick Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Depth Pick Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/depth_pick.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Depth Pick Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader_module,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let pixel_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Depth Pick Pixel Buffer"),
+            contents: bytemuck::cast_slice(&[0u32; 4]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let depth_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Depth Pick Buffer"),
+            size: 4,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Depth Pick Readback Buffer"),
+            size: 4,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            pixel_buffer,
+            depth_buffer,
+            readback_buffer,
+        }
+    }
+}
+
+/// Depth (0 near .. 1 far) of the last rendered frame at a pixel, `None` over the background
+#[cfg(not(target_arch = "wasm32"))]
+fn read_depth(state: &State, pixel: [u32; 2]) -> Option<f32> {
+    let picker = &state.depth_picker;
+    state.queue.write_buffer(&picker.pixel_buffer, 0, bytemuck::cast_slice(&[pixel[0], pixel[1], 0, 0]));
+    // The depth view is recreated on resize, so the bind group is built per pick
+    let bind_group = state.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("depth_pick_bind_group"),
+        layout: &picker.bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&state.multisample_depth_texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: picker.pixel_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: picker.depth_buffer.as_entire_binding(),
+            },
+        ],
+    });
+
+    let mut encoder = state.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Depth Pick Encoder"),
+    });
+    {
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Depth Pick Pass"),
+            timestamp_writes: None,
+        });
+        compute_pass.set_pipeline(&picker.pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+        compute_pass.dispatch_workgroups(1, 1, 1);
+    }
+    encoder.copy_buffer_to_buffer(&picker.depth_buffer, 0, &picker.readback_buffer, 0, 4);
+    state.queue.submit(std::iter::once(encoder.finish()));
+
+    let buffer_slice = picker.readback_buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    state.device.poll(wgpu::Maintain::Wait);
+    receiver.recv().ok()?.ok()?;
+    let depth = bytemuck::cast_slice::<u8, f32>(&buffer_slice.get_mapped_range())[0];
+    picker.readback_buffer.unmap();
+
+    // 1 is the cleared background; 0 only appears in a depth buffer not rendered to yet
+    (depth > 0.0 && depth < 1.0).then_some(depth)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_depth(_state: &State, _pixel: [u32; 2]) -> Option<f32> {
+    None
+}
+
+/// World point drawn at `cursor` (physical pixels) in the last frame, `None` over the background
+///
+/// In split view the point is unprojected with the camera of the viewport under the cursor.
+pub fn pick_world_point(state: &State, cursor: [f32; 2]) -> Option<Point3<f32>> {
+    let inside = |rect: &[u32; 4]| {
+        cursor[0] >= rect[0] as f32 && cursor[1] >= rect[1] as f32
+            && cursor[0] < (rect[0] + rect[2]) as f32 && cursor[1] < (rect[1] + rect[3]) as f32
+    };
+    let viewports = crate::lib_split_view::viewports(state);
+    let viewport = viewports.iter().find(|viewport| inside(&viewport.rect))?;
+    let depth = read_depth(state, [cursor[0] as u32, cursor[1] as u32])?;
+
+    // Unproject from normalized device coordinates of the viewport (y up, depth 0..1)
+    let [x, y, width, height] = viewport.rect.map(|value| value as f32);
+    let view_projection: Matrix4<f32> = viewport.projection.calc_matrix() * viewport.camera.calc_matrix();
+    let inverse = view_projection.invert()?;
+    let ndc = Vector4::new((cursor[0] - x) / width * 2.0 - 1.0, 1.0 - (cursor[1] - y) / height * 2.0, depth, 1.0);
+    let world = inverse * ndc;
+    (world.w.abs() > f32::EPSILON).then(|| Point3::new(world.x / world.w, world.y / world.w, world.z / world.w))
+}
@@ -0,0 +1,63 @@
+//! # Frame Pacing Module
+//!
+//! Optional frame-rate caps for the native event loop, which otherwise redraws as fast
+//! as the surface allows. `fps_cap` limits the rate while the viewer is in use and
+//! `idle_fps_cap` applies once there was no input for `idle_after`, so a window left
+//! open during a long inspection session stops spinning the GPU and the fans.
+//!
+//! Instead of sleeping, the event loop waits until the next frame is due
+//! (`ControlFlow::WaitUntil`), so input is still handled while it waits. On the web
+//! the browser paces frames and the caps are ignored.
+
+use instant::{Duration, Instant};
+
+/// Seconds without input before the idle cap applies, unless set with `set_idle_fps_cap`
+pub const DEFAULT_IDLE_AFTER: Duration = Duration::from_secs(5);
+
+/// Frame-rate caps and the time of the last input
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    pub fps_cap: Option<u32>,      // Frames per second while active, None for uncapped
+    pub idle_fps_cap: Option<u32>, // Frames per second once idle, None keeps `fps_cap`
+    pub idle_after: Duration,      // Time without input after which the viewer counts as idle
+    last_activity: Instant,
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self {
+            fps_cap: None,
+            idle_fps_cap: None,
+            idle_after: DEFAULT_IDLE_AFTER,
+            last_activity: Instant::now(),
+        }
+    }
+}
+
+impl FramePacer {
+    /// Whether there was no input for `idle_after`
+    pub fn is_idle(&self, now: Instant) -> bool {
+        now.duration_since(self.last_activity) >= self.idle_after
+    }
+
+    /// The cap in effect at `now`; the idle cap never raises the rate above `fps_cap`
+    pub fn current_cap(&self, now: Instant) -> Option<u32> {
+        match (self.fps_cap, self.idle_fps_cap.filter(|_| self.is_idle(now))) {
+            (Some(active), Some(idle)) => Some(active.min(idle)),
+            (active, idle) => idle.or(active),
+        }
+    }
+
+    /// Record user input; returns true when the viewer was idle, so a frame should be drawn now
+    pub fn register_activity(&mut self, now: Instant) -> bool {
+        let was_idle = self.idle_fps_cap.is_some() && self.is_idle(now);
+        self.last_activity = now;
+        was_idle
+    }
+
+    /// When the frame after one started at `now` is due, or `None` to draw it right away (uncapped)
+    pub fn next_frame_time(&self, now: Instant) -> Option<Instant> {
+        let fps = self.current_cap(now)?.max(1);
+        Some(now + Duration::from_secs_f64(1.0 / fps as f64))
+    }
+}
@@ -1,15 +1,58 @@
 use crate::{State, geometry_loader};
 use crate::model_polygon::PolygonVertex;
-use crate::model_pipe::PipeVertex;
+use crate::model_pipe::{PipeCapStyle, PipeSegment, PipeVertex};
 use cgmath::prelude::*;
 use wgpu::util::DeviceExt;
 
-/// Load geometry data from a JSON file
+/// Limits of `State::pipe_radius_scale`
+pub const MIN_PIPE_RADIUS_SCALE: f32 = 0.05;
+pub const MAX_PIPE_RADIUS_SCALE: f32 = 20.0;
+
+/// Limits of the automatic on-screen point size (same unit as `model_point::POINT_SIZE`)
+const MIN_AUTO_POINT_SIZE: f32 = 0.001;
+const MAX_AUTO_POINT_SIZE: f32 = 0.03;
+
+/// Load geometry data from a JSON file or `http(s)://` URL
 pub async fn load_geometries_from_file(state: &mut State<'_>, path: &str) -> Result<(), Box<dyn std::error::Error>> {
     println!("Loading geometries from file: {}", path);
     
     // Load geometry data from file
     let geometry_data = geometry_loader::load_geometry_file(path).await?;
+    apply_geometry_data(state, &geometry_data)?;
+    state.geometry_path = Some(path.to_string());
+    state.scene = None;
+    Ok(())
+}
+
+/// Load geometry data from a JSON file, reporting read/parse progress to `on_progress`
+pub async fn load_geometries_from_file_with_progress<F: FnMut(geometry_loader::LoadProgress)>(
+    state: &mut State<'_>,
+    path: &str,
+    on_progress: F,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Loading geometries from file: {}", path);
+
+    let geometry_data = geometry_loader::load_geometry_file_with_progress(path, on_progress).await?;
+    apply_geometry_data(state, &geometry_data)?;
+    state.geometry_path = Some(path.to_string());
+    state.scene = None;
+    Ok(())
+}
+
+/// Reload the geometry file that was last loaded successfully (native only)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn reload(state: &mut State<'_>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = state.geometry_path.clone().ok_or("No geometry file has been loaded yet")?;
+    pollster::block_on(load_geometries_from_file(state, &path))
+}
+
+/// Create GPU models for parsed geometry data and store them in the state
+///
+/// Only the geometry types present in `geometry_data` are replaced.
+pub(crate) fn apply_geometry_data(state: &mut State<'_>, geometry_data: &geometry_loader::GeometryData) -> Result<(), Box<dyn std::error::Error>> {
+    state.geometry_counts = geometry_loader::GeometryCounts::from_geometry_data(geometry_data);
+    state.scene_bounds = geometry_data.bounds();
+    state.origin_offset = geometry_data.origin_offset;
     
     // Process mesh data if available
     if let Some(meshes) = &geometry_data.meshes {
@@ -27,12 +70,15 @@ pub async fn load_geometries_from_file(state: &mut State<'_>, path: &str) -> Res
             // Load all meshes from the JSON file
             for mesh_data in meshes {
                 println!("Loading mesh: {}", mesh_data.name);
+                record_mesh_report(state, mesh_data);
                 
                 // Create the model from each mesh data
                 let model = geometry_loader::create_model_from_mesh_data(
                     &state.device,
                     &state.queue,
                     mesh_data,
+                    state.settings.default_mesh_color,
+                    state.settings.normalize_normals,
                     &texture_bind_group_layout
                 )?;
                 
@@ -42,6 +88,7 @@ pub async fn load_geometries_from_file(state: &mut State<'_>, path: &str) -> Res
             // For backwards compatibility, set the first model as obj_model
             if !mesh_models.is_empty() {
                 state.obj_model = mesh_models.remove(0);
+                state.obj_model_is_default = false;
             }
             
             // Store additional models in a new field
@@ -64,12 +111,24 @@ pub async fn load_geometries_from_file(state: &mut State<'_>, path: &str) -> Res
             
             // Use the model directly
             state.quad_point_model = Some(quad_point_model);
+            update_auto_point_size(state, first_point_set);
         }
     }
     
-    // We don't load lines from JSON files as requested by the user
-    // Lines are created directly in State::new using geometry_generator::create_grid_lines
-    // This preserves the original XYZ grid with grey lines
+    // Process line data if available
+    // The XYZ grid stays in `line_model` (created in State::new); JSON lines are kept
+    // separately and drawn with the wide line pipeline so each set can carry a width
+    if let Some(lines) = &geometry_data.lines {
+        let mut line_models = Vec::new();
+        for line_data in lines.iter().filter(|line_data| !skip_empty("line set", &line_data.name, line_data.is_empty())) {
+            println!("Loading lines: {} (width: {:?})", line_data.name, line_data.width);
+            line_models.push(geometry_loader::create_wide_line_model_from_line_data(
+                &state.device,
+                line_data
+            ));
+        }
+        state.content_line_models = line_models;
+    }
     
     // Process pipe data if available
     if let Some(pipes) = &geometry_data.pipes {
@@ -80,38 +139,246 @@ pub async fn load_geometries_from_file(state: &mut State<'_>, path: &str) -> Res
             
             // Create the pipe model
             // Get raw vertices and indices from the geometry_loader
-            let pipe_model = geometry_loader::create_pipe_model_from_pipe_data(
-                &state.device,
-                first_pipe_set
-            );
-            
             // Use the PipeModel directly since it's already in the correct format with vertex_buffer, index_buffer, and num_indices
-            state.pipe_model = Some(pipe_model);
+            load_pipe_set(state, first_pipe_set);
         }
     }
     
     // Process polygon data if available
     if let Some(polygons) = &geometry_data.polygons {
         if !polygons.is_empty() {
-            // Load the first polygon set
-            let first_polygon_set = &polygons[0];
-            println!("Loading polygons: {}", first_polygon_set.name);
-            
-            // Create the polygon model
-            // Get raw vertices and indices from the geometry_loader
-            let polygon_model = geometry_loader::create_polygon_model_from_polygon_data(
-                &state.device,
-                first_polygon_set
-            );
+            // Load every polygon set; each keeps its own lit/unlit flag
+            let mut polygon_models = Vec::new();
+            for polygon_set in polygons.iter().filter(|polygon_set| !skip_empty("polygon set", &polygon_set.name, polygon_set.is_empty())) {
+                println!("Loading polygons: {} (lit: {})", polygon_set.name, polygon_set.lit.unwrap_or(true));
+                polygon_models.push(geometry_loader::create_polygon_model_from_polygon_data(
+                    &state.device,
+                    polygon_set
+                ));
+            }
             
-            // Use the PolygonModel directly since it's already in the correct format with vertex_buffer, index_buffer, and num_indices
-            state.polygon_model = Some(polygon_model);
+            // As with meshes, the first set is the main polygon model
+            state.polygon_model = (!polygon_models.is_empty()).then(|| polygon_models.remove(0));
+            state.additional_polygon_models = polygon_models;
         }
     }
     
     Ok(())
 }
 
+/// Size newly loaded points from their spacing, unless `State::set_point_size` overrides it
+///
+/// Points are drawn at a constant size on screen. The spacing assumes the points sample a
+/// surface spread over the largest face of their bounds; taken relative to the scene
+/// diagonal, which spans about the viewport once the scene is framed, it gives the size
+/// that roughly closes the gaps between neighbors. The result scales the per-vertex sizes,
+/// so their ratios are kept.
+pub(crate) fn update_auto_point_size(state: &mut State<'_>, point_data: &geometry_loader::PointData) {
+    let positions = point_data.vertices.iter().map(|vertex| vertex.position);
+    let Some((min, max)) = positions.fold(None, |bounds: Option<([f32; 3], [f32; 3])>, p| {
+        let (min, max) = bounds.unwrap_or((p, p));
+        Some((std::array::from_fn(|i| min[i].min(p[i])), std::array::from_fn(|i| max[i].max(p[i]))))
+    }) else {
+        return;
+    };
+    let Some((scene_min, scene_max)) = state.scene_bounds else {
+        return;
+    };
+    let scene_diagonal = (0..3).map(|i| (scene_max[i] - scene_min[i]).powi(2)).sum::<f32>().sqrt();
+    let mean_size = point_data.vertices.iter().map(|vertex| vertex.size).sum::<f32>() / point_data.vertices.len() as f32;
+    if scene_diagonal <= f32::EPSILON || mean_size <= 0.0 {
+        return;
+    }
+
+    let mut extent: [f32; 3] = std::array::from_fn(|i| max[i] - min[i]);
+    extent.sort_by(|a, b| b.total_cmp(a));
+    let count = point_data.vertices.len() as f32;
+    let spacing = if extent[1] > extent[0] * 1e-3 {
+        (extent[0] * extent[1] / count).sqrt()
+    } else {
+        extent[0] / count // Points along a line
+    };
+    let size = (spacing / scene_diagonal).clamp(MIN_AUTO_POINT_SIZE, MAX_AUTO_POINT_SIZE);
+
+    state.auto_point_size = size / mean_size;
+    if state.settings.point_size_override.is_none() {
+        state.globals_uniform.point_size = state.auto_point_size;
+    }
+    log::info!("Automatic point size for '{}': {:.4} (multiplier {:.3})", point_data.name, size, state.auto_point_size);
+}
+
+/// Check a mesh before it is uploaded, log what was found and keep the report for `State::last_mesh_report`
+pub(crate) fn record_mesh_report(state: &mut State<'_>, mesh_data: &geometry_loader::MeshData) {
+    let report = geometry_loader::validate_mesh(mesh_data);
+    if report.is_clean() {
+        log::info!("Validated {}", report);
+    } else {
+        log::warn!("Problems in {}", report);
+    }
+    state.last_mesh_report = Some(report);
+}
+
+/// Drop all loaded meshes, points, lines, pipes and polygons, e.g. before showing another scene
+pub(crate) fn clear_loaded_geometry(state: &mut State<'_>) {
+    // An empty model draws nothing, unlike the startup cube
+    state.obj_model = crate::model::Model::new(Vec::new());
+    state.obj_model_is_default = false;
+    state.additional_mesh_models.clear();
+    state.quad_point_model = None;
+    state.content_line_models.clear();
+    state.pipe_data = None;
+    state.pipe_model = None;
+    state.polygon_model = None;
+    state.additional_polygon_models.clear();
+}
+
+/// Log and report sets without anything to draw, which get no GPU buffers
+pub(crate) fn skip_empty(kind: &str, name: &str, is_empty: bool) -> bool {
+    if is_empty {
+        log::warn!("Skipping empty {} '{}'", kind, name);
+    }
+    is_empty
+}
+
+/// Build a mesh from raw arrays and append it to the loaded meshes
+///
+/// Normals are computed from the triangles when `normals` is `None` and vertices
+/// without `colors` use the same grey as JSON meshes. The first added mesh replaces
+/// the default cube.
+pub fn add_mesh(
+    state: &mut State<'_>,
+    name: &str,
+    positions: &[[f32; 3]],
+    normals: Option<&[[f32; 3]]>,
+    colors: Option<&[[f32; 3]]>,
+    indices: &[u32],
+) -> Result<(), String> {
+    if normals.is_some_and(|normals| normals.len() != positions.len()) {
+        return Err(format!("Mesh '{}': expected {} normals, got {}", name, positions.len(), normals.map_or(0, |n| n.len())));
+    }
+    if colors.is_some_and(|colors| colors.len() != positions.len()) {
+        return Err(format!("Mesh '{}': expected {} colors, got {}", name, positions.len(), colors.map_or(0, |c| c.len())));
+    }
+    if indices.len() % 3 != 0 {
+        return Err(format!("Mesh '{}': index count {} is not a multiple of 3", name, indices.len()));
+    }
+    if let Some(index) = indices.iter().find(|&&index| index as usize >= positions.len()) {
+        return Err(format!("Mesh '{}': index {} is out of range for {} vertices", name, index, positions.len()));
+    }
+
+    // Go through the same conversion as JSON meshes
+    let mesh_data = geometry_loader::MeshData {
+        name: name.to_string(),
+        vertices: positions.iter().enumerate()
+            .map(|(i, position)| geometry_loader::MeshVertexData {
+                position: *position,
+                tex_coords: [0.0, 0.0],
+                normal: normals.map(|normals| normals[i]),
+                tangent: None,
+                bitangent: None,
+                color: colors.map(|colors| colors[i]),
+            })
+            .collect(),
+        indices: indices.to_vec(),
+        material: None,
+        face_colors: None,
+        flip_winding: None,
+    };
+    record_mesh_report(state, &mesh_data);
+    let texture_bind_group_layout = state.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[],
+        label: Some("texture_bind_group_layout"),
+    });
+    let model = geometry_loader::create_model_from_mesh_data(&state.device, &state.queue, &mesh_data, state.settings.default_mesh_color, state.settings.normalize_normals, &texture_bind_group_layout)
+        .map_err(|e| e.to_string())?;
+
+    if state.obj_model_is_default {
+        state.obj_model = model;
+        state.obj_model_is_default = false;
+    } else {
+        state.additional_mesh_models.push(model);
+    }
+
+    state.geometry_counts.mesh_vertices += positions.len();
+    state.geometry_counts.mesh_triangles += indices.len() / 3;
+    for position in positions {
+        let (min, max) = state.scene_bounds.get_or_insert((*position, *position));
+        for i in 0..3 {
+            min[i] = min[i].min(position[i]);
+            max[i] = max[i].max(position[i]);
+        }
+    }
+    Ok(())
+}
+
+/// Remove mesh model `index` (0 is `obj_model`, then `additional_mesh_models`) and drop its GPU buffers
+///
+/// Later meshes move down one index; removing mesh 0 promotes the next mesh into
+/// `obj_model`. The scene bounds are left as they are.
+pub fn remove_mesh(state: &mut State, index: usize) -> Result<(), String> {
+    let mesh_count = 1 + state.additional_mesh_models.len();
+    if index >= mesh_count {
+        return Err(format!("Mesh index {} out of range ({} meshes)", index, mesh_count));
+    }
+    let removed = if index == 0 {
+        let next = if state.additional_mesh_models.is_empty() {
+            crate::model::Model::new(Vec::new())
+        } else {
+            state.additional_mesh_models.remove(0)
+        };
+        std::mem::replace(&mut state.obj_model, next)
+    } else {
+        state.additional_mesh_models.remove(index - 1)
+    };
+
+    // The startup cube is not part of the geometry counts
+    if index != 0 || !state.obj_model_is_default {
+        for mesh in &removed.meshes {
+            // Vertices split for face colors are counted too
+            let vertices = mesh.vertex_buffer.size() as usize / std::mem::size_of::<crate::model::ModelVertex>();
+            let counts = &mut state.geometry_counts;
+            counts.mesh_vertices = counts.mesh_vertices.saturating_sub(vertices);
+            counts.mesh_triangles = counts.mesh_triangles.saturating_sub(mesh.num_elements as usize / 3);
+        }
+    }
+    state.obj_model_is_default = false;
+    Ok(())
+}
+
+/// Index of the mesh model named `name`, as used by `remove_mesh`
+pub fn mesh_index(state: &State, name: &str) -> Option<usize> {
+    std::iter::once(&state.obj_model)
+        .chain(&state.additional_mesh_models)
+        .position(|model| model.meshes.first().is_some_and(|mesh| mesh._name == name))
+}
+
+/// Remove polygon set `index` (0 is `polygon_model`, then `additional_polygon_models`)
+pub fn remove_polygon_set(state: &mut State, index: usize) -> Result<(), String> {
+    if state.polygon_model.is_none() && !state.additional_polygon_models.is_empty() {
+        state.polygon_model = Some(state.additional_polygon_models.remove(0));
+    }
+    let set_count = state.polygon_model.iter().count() + state.additional_polygon_models.len();
+    if index >= set_count {
+        return Err(format!("Polygon set index {} out of range ({} sets)", index, set_count));
+    }
+    let removed = if index == 0 {
+        let next = (!state.additional_polygon_models.is_empty()).then(|| state.additional_polygon_models.remove(0));
+        std::mem::replace(&mut state.polygon_model, next).expect("polygon set 0 exists")
+    } else {
+        state.additional_polygon_models.remove(index - 1)
+    };
+    let counts = &mut state.geometry_counts;
+    counts.polygon_triangles = counts.polygon_triangles.saturating_sub(removed.num_indices as usize / 3);
+    Ok(())
+}
+
+/// Index of the polygon set named `name`, as used by `remove_polygon_set`
+pub fn polygon_set_index(state: &State, name: &str) -> Option<usize> {
+    state.polygon_model.iter()
+        .chain(&state.additional_polygon_models)
+        .position(|model| model.name == name)
+}
+
 /// Create a grid of polygons matching other geometries
 pub fn create_sample_polygon(state: &mut State) {
     const SCALE_FACTOR: f32 = 0.25; // Size factor for polygon
@@ -138,6 +405,7 @@ pub fn create_sample_polygon(state: &mut State) {
             x_normalized, 
             (1.0 - x_normalized) * z_normalized,
             1.0 - z_normalized,
+            1.0,
         ];
         
         // Convert the quaternion rotation to a 4x4 matrix - EXACTLY like in line code
@@ -215,17 +483,69 @@ pub fn create_sample_polygon(state: &mut State) {
     });
     
     // Create the polygon model
+    let bounds = crate::model_polygon::position_bounds(all_vertices.iter().map(|vertex| vertex.position));
     let polygon_model = crate::model_polygon::PolygonModel {
         name: "Sample Polygon Grid".to_string(),
         vertex_buffer,
         index_buffer,
         num_indices: all_indices.len() as u32,
+        lit: true,
+        transparent: all_vertices.iter().any(|vertex| vertex.color[3] < 1.0),
+        center: crate::model_polygon::bounds_center(bounds),
+        bounds,
     };
     
     state.polygon_model = Some(polygon_model);
     println!("Sample polygon grid created successfully!");
 }
 
+/// Show a JSON pipe set at the current pipe radius scale, keeping its data for rebuilds
+pub(crate) fn load_pipe_set(state: &mut State, pipe_set: &geometry_loader::PipeData) {
+    if skip_empty("pipe set", &pipe_set.name, pipe_set.is_empty()) {
+        state.pipe_data = None;
+        state.pipe_model = None;
+        return;
+    }
+    state.pipe_model = Some(geometry_loader::create_pipe_model_from_pipe_data(
+        &state.device,
+        pipe_set,
+        state.settings.pipe_radius_scale,
+        state.settings.pipe_cap_style,
+    ));
+    state.pipe_data = Some(pipe_set.clone());
+}
+
+/// Scale every pipe radius and rebuild the pipe model that is currently shown
+///
+/// The scale is clamped to `[MIN_PIPE_RADIUS_SCALE, MAX_PIPE_RADIUS_SCALE]`.
+pub fn set_pipe_radius_scale(state: &mut State, scale: f32) {
+    state.settings.pipe_radius_scale = scale.clamp(MIN_PIPE_RADIUS_SCALE, MAX_PIPE_RADIUS_SCALE);
+    rebuild_pipe_model(state);
+}
+
+/// Close pipe ends flat or with rounded caps and rebuild the pipe model that is currently shown
+pub fn set_pipe_cap_style(state: &mut State, cap_style: PipeCapStyle) {
+    state.settings.pipe_cap_style = cap_style;
+    rebuild_pipe_model(state);
+}
+
+/// Rebuild the shown pipes with the current radius scale and cap style
+///
+/// JSON pipes are rebuilt from their stored data; pipes converted from the line
+/// model are converted again.
+fn rebuild_pipe_model(state: &mut State) {
+    if let Some(pipe_data) = &state.pipe_data {
+        state.pipe_model = Some(geometry_loader::create_pipe_model_from_pipe_data(
+            &state.device,
+            pipe_data,
+            state.settings.pipe_radius_scale,
+            state.settings.pipe_cap_style,
+        ));
+    } else if state.pipe_model.is_some() {
+        create_pipes_from_lines(state);
+    }
+}
+
 /// Convert regular lines from line_model into 3D pipe lines
 pub fn create_pipes_from_lines(state: &mut State) {
     // Check if we have a line model to convert
@@ -238,9 +558,11 @@ pub fn create_pipes_from_lines(state: &mut State) {
         let mut all_vertices = Vec::new();
         let mut all_indices = Vec::new();
         let mut vertex_count: u32 = 0;
+        let mut segments = Vec::new(); // For the rounded caps
         
-        const PIPE_RADIUS: f32 = 0.02; // Radius of the pipe
+        const PIPE_RADIUS: f32 = 0.02; // Radius of the pipe before `pipe_radius_scale`
         const PIPE_SEGMENTS: u32 = 8;  // Number of segments around the pipe circumference
+        let radius = PIPE_RADIUS * state.settings.pipe_radius_scale;
         
         // Use the same instances stored in state.instances
         println!("Creating pipes with {} instances", state.instances.len());
@@ -272,6 +594,15 @@ pub fn create_pipes_from_lines(state: &mut State) {
             let right = pipe_direction.cross(up).normalize();
             let forward = right.cross(pipe_direction).normalize();
             
+            // Use position-based coloring like other geometries
+            let x_normalized = (pos.x + 15.0) / 30.0;
+            let z_normalized = (pos.z + 15.0) / 30.0;
+            let color = [
+                x_normalized, 
+                (1.0 - x_normalized) * z_normalized,
+                1.0 - z_normalized,
+            ];
+
             // Generate vertices for the pipe
             for segment in 0..PIPE_SEGMENTS {
                 let angle = 2.0 * std::f32::consts::PI * segment as f32 / PIPE_SEGMENTS as f32;
@@ -279,32 +610,23 @@ pub fn create_pipes_from_lines(state: &mut State) {
                 let sin_angle = angle.sin();
                 
                 // Calculate the offset from the pipe center
-                let offset = right * (cos_angle * PIPE_RADIUS) + forward * (sin_angle * PIPE_RADIUS);
+                let offset = right * (cos_angle * radius) + forward * (sin_angle * radius);
                 
                 // Create vertices at both ends of the pipe
                 let start_vertex = start_world + offset;
                 let end_vertex = end_world + offset;
                 
-                // Use position-based coloring like other geometries
-                let x_normalized = (pos.x + 15.0) / 30.0;
-                let z_normalized = (pos.z + 15.0) / 30.0;
-                let color = [
-                    x_normalized, 
-                    (1.0 - x_normalized) * z_normalized,
-                    1.0 - z_normalized,
-                ];
-                
-                all_vertices.push(PipeVertex {
-                    position: [start_vertex.x, start_vertex.y, start_vertex.z],
-                    color,
-                });
-                
-                all_vertices.push(PipeVertex {
-                    position: [end_vertex.x, end_vertex.y, end_vertex.z],
-                    color,
-                });
+                all_vertices.push(PipeVertex::new([start_vertex.x, start_vertex.y, start_vertex.z], color));
+                all_vertices.push(PipeVertex::new([end_vertex.x, end_vertex.y, end_vertex.z], color));
             }
             
+            segments.push(PipeSegment::new(
+                start_world.into(),
+                end_world.into(),
+                color,
+                radius,
+            ));
+
             // Generate indices for the pipe
             let base_idx = vertex_count;
             
@@ -325,6 +647,10 @@ pub fn create_pipes_from_lines(state: &mut State) {
             
             vertex_count += PIPE_SEGMENTS * 2;
         }
+
+        if state.settings.pipe_cap_style == PipeCapStyle::Rounded {
+            crate::model_pipe::append_round_caps(&mut all_vertices, &mut all_indices, &segments);
+        }
         
         println!("Created {} pipe vertices and {} indices", all_vertices.len(), all_indices.len());
         
@@ -348,6 +674,7 @@ pub fn create_pipes_from_lines(state: &mut State) {
             vertex_buffer,
             index_buffer,
             num_indices: all_indices.len() as u32,
+            bounds: crate::model_polygon::position_bounds(all_vertices.iter().map(|vertex| vertex.position)),
         };
         
         state.pipe_model = Some(pipe_model);
@@ -0,0 +1,169 @@
+//! # Help Overlay Module
+//!
+//! On-screen panel listing the key bindings together with the current render mode,
+//! the frame rate and the loaded geometry counts, toggled with F1. It is drawn in
+//! screen space with the label font (see `lib_labels`) on top of everything else.
+
+use crate::camera::{DragModifier, MouseBinding};
+use crate::lib_labels::{self, LabelVertex, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::lib_state::State;
+use wgpu::util::DeviceExt;
+
+/// Key bindings listed in the overlay after the mouse bindings, in the order of the README controls
+const KEY_BINDINGS: &[&str] = &[
+    "F1      Show/hide this help",
+    "F2      Split view, input goes to the view under the cursor",
+    "WASD    Move (or arrows), Space/Shift up/down",
+    "0-5     All, points, pipes, lines, meshes, polygons",
+    "Numpad  4/6 and 8/2 orbit in 15 degree steps",
+    "Numpad  1/3/7 front/right/top (Ctrl: opposite), 5 iso",
+    "V       Points as cloud / surface / spheres",
+    "G       Fly mode",
+    "C / H   Go to / set the home view",
+    "F       Fit the view to the geometry",
+    "R       Reset the camera",
+    "T       Top-down plan view",
+    "O       Orthographic / perspective projection",
+    "B       Face orientation view",
+    "U       Mesh UV view",
+    "J       Normal length view",
+    "Z       Depth buffer view",
+    "N       Flip the mesh winding",
+    "P       Mesh depth pre-pass",
+    "E       Hidden line view",
+    "L       Light indicators",
+    "M       Default cube",
+    "I       Backplate image",
+    "K       Background theme",
+    "Q       Grid and axes: all, no Z axis, axes, grid",
+    "Y       Scene bounds and center",
+    "[ / ]   Exposure down / up",
+    "; / '   Field of view narrower / wider",
+    "X       Clipping plane axis",
+    ", / .   Move the clipping plane",
+    "+ / -   Pipe radius (pipes mode)",
+];
+
+/// Seconds over which frames are counted for the displayed frame rate
+const FPS_INTERVAL: f32 = 0.5;
+/// Pixel gap between the window corner and the panel, and between the panel edge and the text
+const PANEL_MARGIN: f32 = 8.0;
+const PANEL_PADDING: f32 = 8.0;
+
+const TEXT_COLOR: [f32; 4] = [0.95, 0.95, 0.95, 1.0];
+const PANEL_COLOR: [f32; 4] = [0.05, 0.05, 0.05, 0.75];
+
+/// Visibility, frame rate and glyph quads of the help panel
+#[derive(Debug, Default)]
+pub struct HelpOverlay {
+    pub visible: bool,
+    fps: f32,
+    frames: u32,
+    elapsed: f32,
+    text: String, // Text the vertex buffer was built from
+    vertex_buffer: Option<wgpu::Buffer>,
+    num_vertices: u32,
+}
+
+/// Binding as it is written in the panel, e.g. "Alt+Left"
+fn binding_name(binding: MouseBinding) -> String {
+    let modifier = match binding.modifier {
+        Some(DragModifier::Shift) => "Shift+",
+        Some(DragModifier::Alt) => "Alt+",
+        None => "",
+    };
+    format!("{}{:?}", modifier, binding.button)
+}
+
+/// Panel text: status lines, a blank line, then the key and mouse bindings
+fn help_text(state: &State) -> String {
+    let counts = &state.geometry_counts;
+    let mut lines = vec![
+        format!("Render mode: {:?}   {:.0} FPS", state.settings.render_mode, state.help_overlay.fps),
+        format!("Meshes: {} vertices, {} triangles", counts.mesh_vertices, counts.mesh_triangles),
+        format!(
+            "Points: {}   Pipes: {} segments   Polygons: {} triangles",
+            counts.points, counts.pipe_segments, counts.polygon_triangles
+        ),
+    ];
+    // File coordinates, with the offset of recentered geometry added back
+    if let Some(position) = state.cursor_readout.position.filter(|_| state.cursor_readout.enabled) {
+        let offset = state.origin_offset;
+        lines.push(format!(
+            "Cursor: {:.3}, {:.3}, {:.3}",
+            position.x as f64 + offset[0],
+            position.y as f64 + offset[1],
+            position.z as f64 + offset[2]
+        ));
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "Mouse   {} drag orbit, {} drag pan, wheel zoom",
+        binding_name(state.camera_controller.orbit_button()),
+        binding_name(state.camera_controller.pan_button())
+    ));
+    lines.extend(KEY_BINDINGS.iter().map(|line| line.to_string()));
+    lines.join("\n")
+}
+
+/// Count frames for the frame rate and rebuild the panel when its text changed
+pub fn update_help_overlay(state: &mut State, dt: f32) {
+    let overlay = &mut state.help_overlay;
+    overlay.frames += 1;
+    overlay.elapsed += dt;
+    if overlay.elapsed >= FPS_INTERVAL {
+        overlay.fps = overlay.frames as f32 / overlay.elapsed;
+        overlay.frames = 0;
+        overlay.elapsed = 0.0;
+    }
+    if !overlay.visible {
+        return;
+    }
+
+    let text = help_text(state);
+    if text == state.help_overlay.text {
+        return;
+    }
+
+    // Anchored at the top left corner of the window; pixel offsets run up and right
+    let anchor = [-1.0, 1.0, 0.0];
+    let line_count = text.lines().count() as f32;
+    let column_count = text.lines().map(|line| line.chars().count()).max().unwrap_or(0) as f32;
+    let mut vertices: Vec<LabelVertex> = Vec::new();
+    lib_labels::push_rectangle_vertices(
+        &mut vertices,
+        anchor,
+        [PANEL_MARGIN, -PANEL_MARGIN - 2.0 * PANEL_PADDING - line_count * GLYPH_HEIGHT],
+        [PANEL_MARGIN + 2.0 * PANEL_PADDING + column_count * GLYPH_WIDTH, -PANEL_MARGIN],
+        PANEL_COLOR,
+    );
+    lib_labels::push_text_vertices(
+        &mut vertices,
+        anchor,
+        [PANEL_MARGIN + PANEL_PADDING, -PANEL_MARGIN - PANEL_PADDING - GLYPH_HEIGHT],
+        &text,
+        TEXT_COLOR,
+    );
+
+    let overlay = &mut state.help_overlay;
+    overlay.num_vertices = vertices.len() as u32;
+    overlay.vertex_buffer = Some(state.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Help Overlay Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    }));
+    overlay.text = text;
+}
+
+/// Draw the help panel over everything else while it is visible
+pub fn render_help_overlay<'a>(state: &'a State, render_pass: &mut wgpu::RenderPass<'a>) {
+    let overlay = &state.help_overlay;
+    let (true, Some(renderer), Some(buffer)) = (overlay.visible, &state.label_renderer, &overlay.vertex_buffer) else {
+        return;
+    };
+    render_pass.set_pipeline(&renderer.screen_pipeline);
+    render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+    render_pass.set_bind_group(3, &renderer.font_bind_group, &[]);
+    render_pass.set_vertex_buffer(0, buffer.slice(..));
+    render_pass.draw(0..overlay.num_vertices, 0..1);
+}
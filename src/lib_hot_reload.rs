@@ -63,9 +63,13 @@ pub fn check_reload_flag(state: &mut State) {
             *flag = false; // Reset flag
             log::info!("Processing hot reload - fetching fresh geometry data");
             
+            // Fetch the file that is currently open
+            let path = state.geometry_path.clone()
+                .unwrap_or_else(|| "assets/sample_geometry.json".to_string());
+            
             // Spawn async task to fetch geometry data
             wasm_bindgen_futures::spawn_local(async move {
-                match fetch_and_reload_geometry().await {
+                match fetch_and_reload_geometry(&path).await {
                     Ok(_) => {
                         log::info!("📦 Fresh geometry data fetched and ready for processing");
                     }
@@ -88,8 +92,8 @@ pub fn check_and_reload_geometry(state: &mut State, file_change_receiver: &mpsc:
             match event.kind {
                 EventKind::Modify(_) | EventKind::Create(_) => {
                     log::info!("JSON file changed, reloading geometry...");
-                    // Reload geometry using pollster (already available in dependencies)
-                    if let Err(e) = pollster::block_on(state.load_geometries_from_file("assets/sample_geometry.json")) {
+                    // Reload the file that is currently open
+                    if let Err(e) = state.reload() {
                         log::error!("Failed to reload geometry: {}", e);
                     } else {
                         log::info!("Geometry reloaded successfully");
@@ -101,14 +105,67 @@ pub fn check_and_reload_geometry(state: &mut State, file_change_receiver: &mpsc:
     }
 }
 
+/// Modification time polling of the open geometry file, a fallback for network and virtual
+/// filesystems that do not emit change events reliably
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub struct ReloadPoller {
+    interval: Option<instant::Duration>,
+    last_check: Option<instant::Instant>,
+    watched: Option<(String, std::time::SystemTime)>, // Path and modification time at the last check
+}
+
+impl ReloadPoller {
+    /// Poll every `interval`, or never for `None`
+    pub fn new(interval: Option<instant::Duration>) -> Self {
+        Self { interval, last_check: None, watched: None }
+    }
+}
+
+/// Reload the open geometry file when its modification time changed since the last check (native only)
+///
+/// Checks at most once per poll interval, on the next frame after it elapsed. The first
+/// check of a newly opened file only records its modification time.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn poll_geometry_file(state: &mut State) {
+    let poller = &mut state.reload_poller;
+    let (Some(interval), Some(path)) = (poller.interval, state.geometry_path.clone()) else {
+        return;
+    };
+    let now = instant::Instant::now();
+    if poller.last_check.is_some_and(|last_check| now - last_check < interval) {
+        return;
+    }
+    poller.last_check = Some(now);
+
+    // Editors may replace the file on save, so it can be missing for a moment
+    let Ok(modified) = std::fs::metadata(&path).and_then(|metadata| metadata.modified()) else {
+        return;
+    };
+    let changed = match &poller.watched {
+        Some((watched_path, watched_modified)) => *watched_path == path && *watched_modified != modified,
+        None => false,
+    };
+    poller.watched = Some((path.clone(), modified));
+    if changed {
+        log::info!("{} changed on disk, reloading geometry...", path);
+        if let Err(e) = state.reload() {
+            log::error!("Failed to reload geometry: {}", e);
+        } else {
+            log::info!("Geometry reloaded successfully");
+        }
+    }
+}
+
 /// Process geometry reload by parsing JSON and updating State (WASM)
 #[cfg(target_arch = "wasm32")]
 fn process_geometry_reload(state: &mut State, json_string: &str) -> Result<(), String> {
     log::info!("🔍 Parsing {} bytes of geometry JSON", json_string.len());
     
     // Parse JSON into geometry data structures
-    let geometry_data: geometry_loader::GeometryData = serde_json::from_str(json_string)
-        .map_err(|e| format!("JSON parse error: {}", e))?;
+    let geometry_data = geometry_loader::parse_geometry_json("reloaded geometry", json_string)?;
+    state.geometry_counts = geometry_loader::GeometryCounts::from_geometry_data(&geometry_data);
+    state.scene_bounds = geometry_data.bounds();
+    state.origin_offset = geometry_data.origin_offset;
     
     log::info!("🔄 Processing geometry data for hot reload");
     
@@ -130,12 +187,15 @@ fn process_geometry_reload(state: &mut State, json_string: &str) -> Result<(), S
             // Load all meshes from the JSON data
             for mesh_data in meshes {
                 log::info!("Reloading mesh: {}", mesh_data.name);
+                crate::lib_geometry_manager::record_mesh_report(state, mesh_data);
                 
                 // Create the model from each mesh data
                 let model = geometry_loader::create_model_from_mesh_data(
                     &state.device,
                     &state.queue,
                     mesh_data,
+                    state.settings.default_mesh_color,
+                    state.settings.normalize_normals,
                     &texture_bind_group_layout
                 ).map_err(|e| format!("Failed to create mesh model: {}", e))?;
                 
@@ -145,6 +205,7 @@ fn process_geometry_reload(state: &mut State, json_string: &str) -> Result<(), S
             // For backwards compatibility, set the first model as obj_model
             if !mesh_models.is_empty() {
                 state.obj_model = mesh_models.remove(0);
+                state.obj_model_is_default = false;
             }
             
             // Store additional models
@@ -164,47 +225,54 @@ fn process_geometry_reload(state: &mut State, json_string: &str) -> Result<(), S
             );
             
             state.quad_point_model = Some(quad_point_model);
+            crate::lib_geometry_manager::update_auto_point_size(state, first_point_set);
         }
     }
     
+    // Process line data if available
+    if let Some(lines) = &geometry_data.lines {
+        log::info!("📏 Reloading {} line sets", lines.len());
+        state.content_line_models = lines.iter()
+            .filter(|line_data| !crate::lib_geometry_manager::skip_empty("line set", &line_data.name, line_data.is_empty()))
+            .map(|line_data| geometry_loader::create_wide_line_model_from_line_data(&state.device, line_data))
+            .collect();
+    }
+    
     // Process pipe data if available
     if let Some(pipes) = &geometry_data.pipes {
         if !pipes.is_empty() {
             let first_pipe_set = &pipes[0];
             log::info!("🔶 Reloading pipes: {}", first_pipe_set.name);
             
-            let pipe_model = geometry_loader::create_pipe_model_from_pipe_data(
-                &state.device,
-                first_pipe_set
-            );
-            
-            state.pipe_model = Some(pipe_model);
+            crate::lib_geometry_manager::load_pipe_set(state, first_pipe_set);
         }
     }
     
     // Process polygon data if available
     if let Some(polygons) = &geometry_data.polygons {
         if !polygons.is_empty() {
-            let first_polygon_set = &polygons[0];
-            log::info!("🔷 Reloading polygons: {}", first_polygon_set.name);
-            
-            let polygon_model = geometry_loader::create_polygon_model_from_polygon_data(
-                &state.device,
-                first_polygon_set
-            );
+            let mut polygon_models: Vec<_> = polygons.iter()
+                .filter(|polygon_set| !crate::lib_geometry_manager::skip_empty("polygon set", &polygon_set.name, polygon_set.is_empty()))
+                .map(|polygon_set| {
+                    log::info!("🔷 Reloading polygons: {}", polygon_set.name);
+                    geometry_loader::create_polygon_model_from_polygon_data(&state.device, polygon_set)
+                })
+                .collect();
             
-            state.polygon_model = Some(polygon_model);
+            state.polygon_model = (!polygon_models.is_empty()).then(|| polygon_models.remove(0));
+            state.additional_polygon_models = polygon_models;
         }
     }
     
     log::info!("✅ Hot reload complete - all geometry updated in-place!");
+    log::info!("Geometry counts: {}", state.geometry_counts);
     
     Ok(())
 }
 
 /// Fetch geometry JSON from server and reload it (WASM)
 #[cfg(target_arch = "wasm32")]
-async fn fetch_and_reload_geometry() -> Result<(), String> {
+async fn fetch_and_reload_geometry(path: &str) -> Result<(), String> {
     use wasm_bindgen::JsCast;
     use wasm_bindgen_futures::JsFuture;
     use web_sys::{Request, RequestInit, Response};
@@ -217,7 +285,7 @@ async fn fetch_and_reload_geometry() -> Result<(), String> {
     
     // Add timestamp to URL for cache busting
     let timestamp = js_sys::Date::now() as u64;
-    let url = format!("assets/sample_geometry.json?t={}", timestamp);
+    let url = format!("{}?t={}", path, timestamp);
     
     let request = Request::new_with_str_and_init(&url, &opts)
         .map_err(|e| format!("Failed to create request: {:?}", e))?;
@@ -244,9 +312,8 @@ async fn fetch_and_reload_geometry() -> Result<(), String> {
     
     log::info!("📄 Received {} bytes of geometry data", json_string.len());
     
-    // Parse the JSON to validate it
-    let _parsed: serde_json::Value = serde_json::from_str(&json_string)
-        .map_err(|e| format!("JSON parse error: {}", e))?;
+    // Parse the JSON to validate it, reporting the line/column of any typo
+    geometry_loader::parse_geometry_json(&url, &json_string)?;
     
     log::info!("✅ JSON validation successful - geometry data is valid");
     
@@ -1,9 +1,43 @@
-use crate::{State, RenderMode};
+use crate::{BlendMode, MeshShadingMode, PointShape, State, RenderMode};
+use crate::camera::ControllerMode;
 use winit::{
     event::*,
     keyboard::{KeyCode, PhysicalKey},
 };
 
+/// Index of a digit key (Digit0 is 0)
+fn digit_index(key: KeyCode) -> Option<usize> {
+    const DIGITS: [KeyCode; 10] = [
+        KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+        KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    ];
+    DIGITS.iter().position(|digit| *digit == key)
+}
+
+/// Switch the render mode, creating the geometry the mode needs
+///
+/// `on_render_mode_changed` is only called when the mode differs from the current one.
+pub fn set_render_mode(state: &mut State, mode: RenderMode) {
+    let changed = state.settings.render_mode != mode;
+    state.settings.render_mode = mode;
+    match mode {
+        // Create pipe lines when switching to Lines mode, but keep pipes loaded
+        // from JSON so their per-segment radius is not replaced by the grid pipes
+        RenderMode::Lines if state.pipe_model.is_none() => {
+            crate::lib_geometry_manager::create_pipes_from_lines(state);
+        }
+        RenderMode::Polygons => {
+            // Create sample polygon when switching to polygon mode
+            crate::lib_geometry_manager::create_sample_polygon(state);
+        }
+        _ => {}
+    }
+    println!("Render mode: {} ({})", mode.label(), mode as usize);
+    if let (true, Some(callback)) = (changed, &state.settings.on_render_mode_changed) {
+        callback(mode);
+    }
+}
+
 /// Handle input events and update state accordingly
 pub fn handle_input(state: &mut State, event: &WindowEvent) -> bool {
     match event {
@@ -16,40 +50,178 @@ pub fn handle_input(state: &mut State, event: &WindowEvent) -> bool {
                 },
             ..
         } => {
-            // Handle number keys for render mode selection
+            // Number keys select the render mode at that index of `RenderMode::all()`
+            if let Some(mode) = digit_index(*key).and_then(|index| RenderMode::all().get(index)) {
+                set_render_mode(state, *mode);
+                return true;
+            }
+
             match key {
-                KeyCode::Digit0 => {
-                    state.render_mode = RenderMode::All;
-                    println!("Render mode: All (0)");
+                KeyCode::F1 => {
+                    state.help_overlay.visible = !state.help_overlay.visible;
+                    true
+                }
+                KeyCode::F2 => {
+                    let enabled = state.split_view.is_none();
+                    crate::lib_split_view::set_split_view(state, enabled);
+                    if let (true, Some(cursor)) = (enabled, state.cursor_position) {
+                        crate::lib_split_view::update_focus(state, cursor);
+                    }
+                    println!("Split view: {}", if enabled { "on" } else { "off" });
+                    true
+                }
+                KeyCode::KeyT => {
+                    crate::lib_view::toggle_top_down_view(state);
+                    true
+                }
+                KeyCode::KeyH => {
+                    state.camera.set_home_view();
+                    println!("Home view set to the current camera (press C to return)");
+                    true
+                }
+                KeyCode::KeyN => {
+                    let front_face = match state.mesh_front_face {
+                        wgpu::FrontFace::Ccw => wgpu::FrontFace::Cw,
+                        wgpu::FrontFace::Cw => wgpu::FrontFace::Ccw,
+                    };
+                    state.set_mesh_front_face(front_face);
+                    println!("Mesh front face winding: {:?}", front_face);
+                    true
+                }
+                KeyCode::KeyP => {
+                    state.settings.depth_prepass = !state.settings.depth_prepass;
+                    println!("Mesh depth pre-pass: {}", if state.settings.depth_prepass { "on" } else { "off" });
                     true
                 }
-                KeyCode::Digit1 => {
-                    state.render_mode = RenderMode::Points;
-                    println!("Render mode: Points (1)");
+                KeyCode::KeyB => {
+                    state.settings.show_face_orientation = !state.settings.show_face_orientation;
+                    println!("Face orientation view (front green, back red): {}", if state.settings.show_face_orientation { "on" } else { "off" });
                     true
                 }
-                KeyCode::Digit2 => {
-                    state.render_mode = RenderMode::Lines;
-                    println!("Render mode: Lines (2)");
-                    // Force creation of pipe lines when switching to Lines mode
-                    crate::lib_geometry_manager::create_pipes_from_lines(state);
+                KeyCode::KeyU => {
+                    let mode = match state.globals_uniform.mesh_shading_mode() {
+                        MeshShadingMode::Uv => MeshShadingMode::Lit,
+                        _ => MeshShadingMode::Uv,
+                    };
+                    state.set_mesh_shading_mode(mode);
+                    println!("Mesh UV view (u red, v green): {}", if mode == MeshShadingMode::Uv { "on" } else { "off" });
                     true
                 }
-                KeyCode::Digit3 => {
-                    state.render_mode = RenderMode::RegularLines;
-                    println!("Render mode: Regular Lines (3)");
+                KeyCode::KeyJ => {
+                    let mode = match state.globals_uniform.mesh_shading_mode() {
+                        MeshShadingMode::NormalLength => MeshShadingMode::Lit,
+                        _ => MeshShadingMode::NormalLength,
+                    };
+                    state.set_mesh_shading_mode(mode);
+                    println!("Normal length view (unit length mid-grey): {}", if mode == MeshShadingMode::NormalLength { "on" } else { "off" });
                     true
                 }
-                KeyCode::Digit4 => {
-                    state.render_mode = RenderMode::Meshes;
-                    println!("Render mode: Meshes (4)");
+                KeyCode::KeyQ => {
+                    let visibility = state.settings.grid_visibility.next_preset();
+                    state.set_grid_visibility(visibility);
+                    println!("Grid: {:?}", visibility);
                     true
                 }
-                KeyCode::Digit5 => {
-                    state.render_mode = RenderMode::Polygons;
-                    println!("Render mode: Polygons (5)");
-                    // Create sample polygon when switching to polygon mode
-                    crate::lib_geometry_manager::create_sample_polygon(state);
+                KeyCode::KeyF => {
+                    if !state.fit_view_to_scene() {
+                        println!("Nothing to fit the view to");
+                    }
+                    true
+                }
+                KeyCode::KeyO => {
+                    let orthographic = !state.projection.is_orthographic();
+                    state.set_orthographic(orthographic);
+                    println!("Projection: {}", if orthographic { "orthographic" } else { "perspective" });
+                    true
+                }
+                KeyCode::KeyY => {
+                    let show = !state.settings.show_scene_extents;
+                    crate::lib_view::set_show_scene_extents(state, show);
+                    true
+                }
+                KeyCode::KeyK => {
+                    let theme = state.cycle_background_theme();
+                    println!("Background: {}", theme.label());
+                    true
+                }
+                KeyCode::KeyZ => {
+                    state.depth_overlay.visible = !state.depth_overlay.visible;
+                    println!("Depth buffer view (near black, far white): {}", if state.depth_overlay.visible { "on" } else { "off" });
+                    true
+                }
+                KeyCode::KeyE => {
+                    state.settings.hidden_line_mode = state.settings.hidden_line_mode.next();
+                    println!("Hidden line view: {:?}", state.settings.hidden_line_mode);
+                    true
+                }
+                KeyCode::KeyL => {
+                    state.settings.show_light_model = !state.settings.show_light_model;
+                    println!("Light indicator: {}", if state.settings.show_light_model { "shown" } else { "hidden" });
+                    true
+                }
+                KeyCode::KeyM => {
+                    state.show_default_model = !state.show_default_model;
+                    println!("Default cube (until geometry is loaded): {}", if state.show_default_model { "shown" } else { "hidden" });
+                    true
+                }
+                KeyCode::KeyI => {
+                    match state.backplate.as_mut() {
+                        Some(backplate) => {
+                            backplate.visible = !backplate.visible;
+                            println!("Backplate image: {}", if backplate.visible { "shown" } else { "hidden" });
+                        }
+                        None => println!("No backplate image loaded"),
+                    }
+                    true
+                }
+                KeyCode::KeyV => {
+                    toggle_point_surface_mode(state);
+                    true
+                }
+                KeyCode::KeyG => {
+                    let mode = match state.camera_controller.mode() {
+                        ControllerMode::Orbit => ControllerMode::Fly,
+                        ControllerMode::Fly => ControllerMode::Orbit,
+                    };
+                    state.camera_controller.set_mode(mode);
+                    println!("Camera controls: {:?}", mode);
+                    true
+                }
+                // Pipe thickness, only while the pipes are shown
+                KeyCode::Equal | KeyCode::NumpadAdd if state.settings.render_mode == RenderMode::Lines => {
+                    scale_pipe_radius(state, PIPE_RADIUS_STEP);
+                    true
+                }
+                KeyCode::Minus | KeyCode::NumpadSubtract if state.settings.render_mode == RenderMode::Lines => {
+                    scale_pipe_radius(state, 1.0 / PIPE_RADIUS_STEP);
+                    true
+                }
+                KeyCode::BracketRight => {
+                    scale_exposure(state, EXPOSURE_STEP);
+                    true
+                }
+                KeyCode::BracketLeft => {
+                    scale_exposure(state, 1.0 / EXPOSURE_STEP);
+                    true
+                }
+                KeyCode::Quote => {
+                    step_field_of_view(state, FOV_STEP_DEGREES);
+                    true
+                }
+                KeyCode::Semicolon => {
+                    step_field_of_view(state, -FOV_STEP_DEGREES);
+                    true
+                }
+                KeyCode::KeyX => {
+                    cycle_clip_plane_axis(state);
+                    true
+                }
+                KeyCode::Comma => {
+                    move_clip_plane(state, -CLIP_PLANE_STEP);
+                    true
+                }
+                KeyCode::Period => {
+                    move_clip_plane(state, CLIP_PLANE_STEP);
                     true
                 }
                 // Point size is now hardcoded directly in the shader
@@ -66,9 +238,29 @@ pub fn handle_input(state: &mut State, event: &WindowEvent) -> bool {
             ..
         } => state.camera_controller.process_keyboard(*key, *key_state),
         WindowEvent::MouseWheel { delta, .. } => {
-            state.camera_controller.process_scroll(delta);
+            // Zoom toward the surface under the cursor; over the background, toward the target
+            let anchor = state
+                .cursor_position
+                .filter(|_| state.settings.zoom_to_cursor && state.camera_controller.mode() == ControllerMode::Orbit)
+                .and_then(|cursor| crate::lib_depth_pick::pick_world_point(state, cursor));
+            match anchor {
+                Some(point) => state.camera_controller.process_scroll_toward(delta, point),
+                None => state.camera_controller.process_scroll(delta),
+            }
+            state.cursor_readout.invalidate();
             true
         }
+        WindowEvent::CursorMoved { position, .. } => {
+            state.cursor_position = Some([position.x as f32, position.y as f32]);
+            crate::lib_split_view::update_focus(state, [position.x as f32, position.y as f32]);
+            state.cursor_readout.invalidate();
+            false
+        }
+        WindowEvent::CursorLeft { .. } => {
+            state.cursor_position = None;
+            state.cursor_readout.clear();
+            false
+        }
         WindowEvent::MouseInput {
             button,
             state: button_state,
@@ -88,3 +280,78 @@ pub fn handle_input(state: &mut State, event: &WindowEvent) -> bool {
         _ => false,
     }
 }
+
+/// Distance the clipping plane moves along its normal per key press
+const CLIP_PLANE_STEP: f32 = 0.25;
+
+/// Cycle the clipping plane through off -> X -> Y -> Z -> off, keeping its offset
+fn cycle_clip_plane_axis(state: &mut State) {
+    let next = match state.settings.clip_plane {
+        None => Some([1.0, 0.0, 0.0]),
+        Some([x, _, _, _]) if x != 0.0 => Some([0.0, 1.0, 0.0]),
+        Some([_, y, _, _]) if y != 0.0 => Some([0.0, 0.0, 1.0]),
+        Some(_) => None,
+    };
+    let offset = state.settings.clip_plane.map_or(0.0, |plane| plane[3]);
+    state.settings.clip_plane = next.map(|[x, y, z]| [x, y, z, offset]);
+
+    match state.settings.clip_plane {
+        Some(plane) => println!("Clip plane: normal ({}, {}, {}), position {}", plane[0], plane[1], plane[2], -plane[3]),
+        None => println!("Clip plane: off"),
+    }
+}
+
+/// Move the clipping plane along its normal
+fn move_clip_plane(state: &mut State, distance: f32) {
+    if let Some(plane) = state.settings.clip_plane.as_mut() {
+        plane[3] -= distance;
+        println!("Clip plane position: {}", -plane[3]);
+    }
+}
+
+/// Factor the pipe radius scale is multiplied (`+`) or divided (`-`) by per key press
+const PIPE_RADIUS_STEP: f32 = 1.25;
+
+/// Multiply the pipe radius scale by `factor` and rebuild the pipes
+fn scale_pipe_radius(state: &mut State, factor: f32) {
+    crate::lib_geometry_manager::set_pipe_radius_scale(state, state.settings.pipe_radius_scale * factor);
+    println!("Pipe radius scale: {:.2}", state.settings.pipe_radius_scale);
+}
+
+/// Degrees the field of view widens (`'`) or narrows (`;`) per key press
+const FOV_STEP_DEGREES: f32 = 5.0;
+
+/// Widen the vertical field of view by `degrees` (narrow when negative), within the allowed range
+fn step_field_of_view(state: &mut State, degrees: f32) {
+    state.set_field_of_view(state.field_of_view() + degrees);
+    println!("Field of view: {:.0} degrees", state.field_of_view());
+}
+
+/// Factor the exposure is multiplied (`]`) or divided (`[`) by per key press
+const EXPOSURE_STEP: f32 = 1.25;
+
+/// Multiply the exposure by `factor`
+fn scale_exposure(state: &mut State, factor: f32) {
+    state.set_exposure(state.exposure() * factor);
+    println!("Exposure: {:.2}", state.exposure());
+}
+
+/// Cycle the point cloud through the blended "cloud" look, the opaque "surface" look and lit spheres
+///
+/// Surface points write depth without blending, so only the nearest point per pixel shows;
+/// dense clouds read as a clean surface instead of an opaque blob. Spheres are opaque too,
+/// shaded and intersecting each other by depth.
+fn toggle_point_surface_mode(state: &mut State) {
+    let Some(model) = state.quad_point_model.as_ref() else {
+        println!("No point cloud loaded");
+        return;
+    };
+    let (blend_mode, shape, name) = match (model.blend_mode == BlendMode::Opaque, state.point_shape()) {
+        (false, _) => (BlendMode::Opaque, PointShape::Disc, "surface (nearest points only)"),
+        (true, PointShape::Disc) => (BlendMode::Opaque, PointShape::Sphere, "spheres (lit)"),
+        (true, PointShape::Sphere) => (BlendMode::Alpha, PointShape::Disc, "cloud (blended)"),
+    };
+    state.set_point_blend_mode(blend_mode);
+    state.set_point_shape(shape);
+    println!("Points: {}", name);
+}
@@ -0,0 +1,370 @@
+//! # Labels Module
+//!
+//! Text annotations anchored to 3D points, e.g. for measurements. Each character
+//! is a textured quad sampled from the bitmap font atlas bundled in
+//! `assets/font_atlas.png` (printable ASCII, 16 x 6 monospace cells). Quads are
+//! expanded in screen space so labels face the camera at a constant pixel size.
+//! Labels are drawn after everything else, optionally ignoring the depth buffer.
+//! The same glyph quads also draw screen-anchored text such as the color legend and the help overlay.
+
+use crate::lib_state::State;
+use crate::model::Vertex;
+use wgpu::util::DeviceExt;
+
+const FONT_ATLAS_PNG: &[u8] = include_bytes!("../assets/font_atlas.png");
+const ATLAS_COLUMNS: u32 = 16;
+const ATLAS_ROWS: u32 = 6;
+const FIRST_GLYPH: u8 = b' ';
+const FALLBACK_GLYPH: u8 = b'?';
+
+/// On-screen glyph size in pixels (three quarters of the 16 x 24 atlas cells)
+pub(crate) const GLYPH_WIDTH: f32 = 12.0;
+pub(crate) const GLYPH_HEIGHT: f32 = 18.0;
+/// Pixel gap between the anchor point and the lower left corner of the text
+const LABEL_MARGIN: f32 = 4.0;
+
+pub const LABEL_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LabelVertex {
+    pub anchor: [f32; 3],     // World position the label is attached to
+    pub offset: [f32; 2],     // Corner offset from the anchor in pixels
+    pub tex_coords: [f32; 2], // Font atlas coordinates
+    pub color: [f32; 4],      // RGBA text color
+}
+
+impl Vertex for LabelVertex {
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<LabelVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// A text label attached to a world position
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub position: [f32; 3],
+    pub text: String,
+    pub color: [f32; 4],
+}
+
+impl Label {
+    /// Append two triangles per visible character; `\n` starts a new line below
+    fn push_vertices(&self, vertices: &mut Vec<LabelVertex>) {
+        push_text_vertices(vertices, self.position, [LABEL_MARGIN, LABEL_MARGIN], &self.text, self.color);
+    }
+}
+
+/// Append the glyph quads of `text`, its first line starting `origin` pixels from `anchor`
+///
+/// Each further line starts `GLYPH_HEIGHT` pixels lower.
+pub(crate) fn push_text_vertices(vertices: &mut Vec<LabelVertex>, anchor: [f32; 3], origin: [f32; 2], text: &str, color: [f32; 4]) {
+    let cell_u = 1.0 / ATLAS_COLUMNS as f32;
+    let cell_v = 1.0 / ATLAS_ROWS as f32;
+    for (line_index, line) in text.lines().enumerate() {
+        let y0 = origin[1] - line_index as f32 * GLYPH_HEIGHT;
+        let y1 = y0 + GLYPH_HEIGHT;
+        for (column, c) in line.chars().enumerate() {
+            if c == ' ' {
+                continue;
+            }
+            let glyph = if c.is_ascii_graphic() { c as u8 } else { FALLBACK_GLYPH } - FIRST_GLYPH;
+            let u0 = (glyph as u32 % ATLAS_COLUMNS) as f32 * cell_u;
+            let v0 = (glyph as u32 / ATLAS_COLUMNS) as f32 * cell_v;
+            let x0 = origin[0] + column as f32 * GLYPH_WIDTH;
+            let x1 = x0 + GLYPH_WIDTH;
+
+            // Atlas rows run top to bottom, screen y runs bottom to top
+            let corners = [
+                ([x0, y0], [u0, v0 + cell_v]),
+                ([x1, y0], [u0 + cell_u, v0 + cell_v]),
+                ([x1, y1], [u0 + cell_u, v0]),
+                ([x0, y0], [u0, v0 + cell_v]),
+                ([x1, y1], [u0 + cell_u, v0]),
+                ([x0, y1], [u0, v0]),
+            ];
+            vertices.extend(corners.iter().map(|&(offset, tex_coords)| LabelVertex {
+                anchor,
+                offset,
+                tex_coords,
+                color,
+            }));
+        }
+    }
+}
+
+/// Append a solid rectangle from `min` to `max` pixels off `anchor`, e.g. a panel behind text
+///
+/// Negative texture coordinates tell the screen shader to skip the font atlas.
+pub(crate) fn push_rectangle_vertices(vertices: &mut Vec<LabelVertex>, anchor: [f32; 3], min: [f32; 2], max: [f32; 2], color: [f32; 4]) {
+    let corners = [
+        [min[0], min[1]],
+        [max[0], min[1]],
+        [max[0], max[1]],
+        [min[0], min[1]],
+        [max[0], max[1]],
+        [min[0], max[1]],
+    ];
+    vertices.extend(corners.iter().map(|&offset| LabelVertex {
+        anchor,
+        offset,
+        tex_coords: [-1.0, -1.0],
+        color,
+    }));
+}
+
+/// Labels added to the scene and the GPU buffer holding their glyph quads
+#[derive(Debug, Default)]
+pub struct Labels {
+    labels: Vec<Label>,
+    vertex_buffer: Option<wgpu::Buffer>,
+    num_vertices: u32,
+    dirty: bool,
+    pub depth_test: bool, // Hide labels behind geometry instead of always drawing them on top
+}
+
+impl Labels {
+    pub fn add(&mut self, position: [f32; 3], text: &str, color: [f32; 4]) {
+        self.labels.push(Label { position, text: text.to_string(), color });
+        self.dirty = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.labels.clear();
+        self.dirty = true;
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// The labels without their glyph quad buffer, rebuilt on the next upload, e.g. for a new device
+    pub fn detached(self) -> Self {
+        Self { vertex_buffer: None, num_vertices: 0, dirty: true, ..self }
+    }
+
+    /// Rebuild the glyph quads after labels were added or removed
+    pub fn upload(&mut self, device: &wgpu::Device) {
+        if !self.dirty {
+            return;
+        }
+        self.dirty = false;
+
+        let mut vertices = Vec::new();
+        for label in &self.labels {
+            label.push_vertices(&mut vertices);
+        }
+        self.num_vertices = vertices.len() as u32;
+        self.vertex_buffer = (!vertices.is_empty()).then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Label Vertex Buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            })
+        });
+    }
+}
+
+/// Font atlas bind group and the depth tested / overlay label pipelines
+pub struct LabelRenderer {
+    pub font_bind_group: wgpu::BindGroup,
+    pub pipeline: wgpu::RenderPipeline,
+    pub overlay_pipeline: wgpu::RenderPipeline,
+    pub screen_pipeline: wgpu::RenderPipeline, // Anchors are normalized device coordinates, e.g. for the color legend and the help overlay
+}
+
+impl LabelRenderer {
+    /// Upload the bundled font atlas and build the label pipelines
+    ///
+    /// `shared_bind_group_layouts` are the camera, light and globals layouts
+    /// used by every other pipeline; the font atlas is bound at group 3.
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &wgpu::SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+        shared_bind_group_layouts: [&wgpu::BindGroupLayout; 3],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let atlas = image::load_from_memory(FONT_ATLAS_PNG)?.to_rgba8();
+        let (width, height) = atlas.dimensions();
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let font_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Font Atlas Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &font_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &atlas,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+        let font_view = font_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let font_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Font Atlas Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let font_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("font_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let font_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("font_bind_group"),
+            layout: &font_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&font_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&font_sampler),
+                },
+            ],
+        });
+
+        let [camera_layout, light_layout, globals_layout] = shared_bind_group_layouts;
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Label Pipeline Layout"),
+            bind_group_layouts: &[camera_layout, light_layout, globals_layout, &font_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Label Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/label.wgsl").into()),
+        });
+
+        // Labels never write depth so overlapping text blends instead of clipping
+        let label_pipeline = |label: &str, entry_points: (&str, &str), depth_compare: wgpu::CompareFunction| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader_module,
+                    entry_point: Some(entry_points.0),
+                    buffers: &[LabelVertex::desc()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader_module,
+                    entry_point: Some(entry_points.1),
+                    compilation_options: Default::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: config.format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: depth_format,
+                    depth_write_enabled: false,
+                    depth_compare,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 4, // Enable 4x MSAA for web compatibility
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        Ok(Self {
+            font_bind_group,
+            pipeline: label_pipeline("Label Render Pipeline", ("vs_main", "fs_main"), wgpu::CompareFunction::LessEqual),
+            overlay_pipeline: label_pipeline("Label Overlay Render Pipeline", ("vs_main", "fs_main"), wgpu::CompareFunction::Always),
+            screen_pipeline: label_pipeline("Screen Text Render Pipeline", ("vs_screen", "fs_screen"), wgpu::CompareFunction::Always),
+        })
+    }
+}
+
+/// Draw all labels, after the rest of the scene
+pub fn render_labels<'a>(state: &'a State, render_pass: &mut wgpu::RenderPass<'a>) {
+    let (Some(renderer), Some(buffer)) = (&state.label_renderer, &state.labels.vertex_buffer) else {
+        return;
+    };
+    let pipeline = if state.labels.depth_test { &renderer.pipeline } else { &renderer.overlay_pipeline };
+    render_pass.set_pipeline(pipeline);
+    render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+    render_pass.set_bind_group(3, &renderer.font_bind_group, &[]);
+    render_pass.set_vertex_buffer(0, buffer.slice(..));
+    render_pass.draw(0..state.labels.num_vertices, 0..1);
+}
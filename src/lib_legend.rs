@@ -0,0 +1,140 @@
+//! # Legend Module
+//!
+//! Color legend for colormapped data, e.g. points colored by height or time: a
+//! vertical gradient bar with the minimum and maximum value next to its ends, in
+//! the bottom right corner of each viewport. It is built from the screen-space
+//! quads of the label font (see `lib_labels`) and drawn after the scene, limited
+//! to its own rectangle with a scissor rect.
+
+use crate::lib_labels::{self, LabelVertex, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::lib_state::State;
+use wgpu::util::DeviceExt;
+
+/// Colormap samples along the bar; the bar blends linearly between them
+const GRADIENT_STEPS: usize = 32;
+/// Pixel size of the gradient bar
+const BAR_WIDTH: f32 = 20.0;
+const BAR_HEIGHT: f32 = 200.0;
+/// Pixel gap between the viewport corner and the panel, the panel edge and its contents, and the bar and the labels
+const PANEL_MARGIN: f32 = 8.0;
+const PANEL_PADDING: f32 = 8.0;
+const LABEL_GAP: f32 = 6.0;
+
+const TEXT_COLOR: [f32; 4] = [0.95, 0.95, 0.95, 1.0];
+const PANEL_COLOR: [f32; 4] = [0.05, 0.05, 0.05, 0.75];
+
+/// Value range and colormap samples of the legend, with its glyph and gradient quads
+#[derive(Debug)]
+pub struct Legend {
+    pub min: f32,
+    pub max: f32,
+    pub colors: Vec<[f32; 4]>, // Colormap sampled from the minimum (first) to the maximum (last)
+    size: [f32; 2],            // Pixel size of the panel
+    vertex_buffer: wgpu::Buffer,
+    num_vertices: u32,
+}
+
+impl Legend {
+    /// Sample `colormap` over t in [0, 1] and build the legend for values from `min` to `max`
+    pub fn new(device: &wgpu::Device, min: f32, max: f32, colormap: impl Fn(f32) -> [f32; 4]) -> Self {
+        let colors = (0..=GRADIENT_STEPS).map(|i| colormap(i as f32 / GRADIENT_STEPS as f32)).collect();
+        Self::from_colors(device, min, max, colors)
+    }
+
+    /// Build the legend from colormap samples, e.g. those of a legend on a lost device
+    pub fn from_colors(device: &wgpu::Device, min: f32, max: f32, colors: Vec<[f32; 4]>) -> Self {
+        let min_text = format_value(min);
+        let max_text = format_value(max);
+        let label_width = min_text.len().max(max_text.len()) as f32 * GLYPH_WIDTH;
+        let size = [
+            2.0 * PANEL_PADDING + label_width + LABEL_GAP + BAR_WIDTH,
+            2.0 * PANEL_PADDING + BAR_HEIGHT,
+        ];
+
+        // Anchored at the bottom right corner of the viewport; pixel offsets run up and right
+        let anchor = [1.0, -1.0, 0.0];
+        let right = -PANEL_MARGIN - PANEL_PADDING;
+        let bottom = PANEL_MARGIN + PANEL_PADDING;
+        let mut vertices: Vec<LabelVertex> = Vec::new();
+        lib_labels::push_rectangle_vertices(
+            &mut vertices,
+            anchor,
+            [-PANEL_MARGIN - size[0], PANEL_MARGIN],
+            [-PANEL_MARGIN, PANEL_MARGIN + size[1]],
+            PANEL_COLOR,
+        );
+        push_gradient_vertices(&mut vertices, anchor, [right - BAR_WIDTH, bottom], [right, bottom + BAR_HEIGHT], &colors);
+
+        // Labels right-aligned against the bar: the maximum level with its top, the minimum with its bottom
+        let label_right = right - BAR_WIDTH - LABEL_GAP;
+        let label_x = |text: &str| label_right - text.len() as f32 * GLYPH_WIDTH;
+        lib_labels::push_text_vertices(&mut vertices, anchor, [label_x(&max_text), bottom + BAR_HEIGHT - GLYPH_HEIGHT], &max_text, TEXT_COLOR);
+        lib_labels::push_text_vertices(&mut vertices, anchor, [label_x(&min_text), bottom], &min_text, TEXT_COLOR);
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Legend Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Self { min, max, colors, size, vertex_buffer, num_vertices: vertices.len() as u32 }
+    }
+}
+
+/// Value as written next to the bar: at most 4 decimals, without trailing zeros
+fn format_value(value: f32) -> String {
+    let text = format!("{:.4}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+/// Append a bar from `min` to `max` pixels off `anchor`, blending through `colors` from bottom to top
+fn push_gradient_vertices(vertices: &mut Vec<LabelVertex>, anchor: [f32; 3], min: [f32; 2], max: [f32; 2], colors: &[[f32; 4]]) {
+    let steps = colors.len().saturating_sub(1).max(1) as f32;
+    let y = |i: usize| min[1] + (max[1] - min[1]) * i as f32 / steps;
+    for (i, pair) in colors.windows(2).enumerate() {
+        let (y0, y1) = (y(i), y(i + 1));
+        let corners = [
+            ([min[0], y0], pair[0]),
+            ([max[0], y0], pair[0]),
+            ([max[0], y1], pair[1]),
+            ([min[0], y0], pair[0]),
+            ([max[0], y1], pair[1]),
+            ([min[0], y1], pair[1]),
+        ];
+        // Negative texture coordinates draw solid color, as for the panel behind text
+        vertices.extend(corners.iter().map(|&(offset, color)| LabelVertex {
+            anchor,
+            offset,
+            tex_coords: [-1.0, -1.0],
+            color,
+        }));
+    }
+}
+
+/// Draw the legend over the scene in the current viewport, then restore the viewport's scissor rect
+pub fn render_legend<'a>(state: &'a State, render_pass: &mut wgpu::RenderPass<'a>) {
+    let (Some(legend), Some(renderer)) = (&state.legend, &state.label_renderer) else {
+        return;
+    };
+    let [x, y, width, height] = state.camera_uniform.viewport_rect();
+    let viewport = [x as u32, y as u32, width as u32, height as u32];
+    let scale = state.camera_uniform.pixel_scale();
+
+    // Scissor rect of the panel in framebuffer pixels (y down), cut to the viewport
+    let left = (x + width - (PANEL_MARGIN + legend.size[0]) * scale).max(x);
+    let top = (y + height - (PANEL_MARGIN + legend.size[1]) * scale).max(y);
+    let right = x + width - PANEL_MARGIN * scale;
+    let bottom = y + height - PANEL_MARGIN * scale;
+    if right <= left || bottom <= top {
+        return;
+    }
+    render_pass.set_scissor_rect(left as u32, top as u32, (right - left) as u32, (bottom - top) as u32);
+
+    render_pass.set_pipeline(&renderer.screen_pipeline);
+    render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+    render_pass.set_bind_group(3, &renderer.font_bind_group, &[]);
+    render_pass.set_vertex_buffer(0, legend.vertex_buffer.slice(..));
+    render_pass.draw(0..legend.num_vertices, 0..1);
+
+    render_pass.set_scissor_rect(viewport[0], viewport[1], viewport[2], viewport[3]);
+}
@@ -0,0 +1,202 @@
+//! # Outline Module
+//!
+//! Selection outlines around mesh models, drawn with the inverted hull technique: after
+//! the shaded meshes, the back faces of an outlined model are drawn again pushed out
+//! along their normals by a fixed number of pixels, in a solid color. The mesh itself
+//! hides the hull except for a rim around its silhouette.
+//!
+//! The hull follows the vertex normals, so meshes with split normals (hard edges) show
+//! small gaps in the outline at those edges.
+
+use crate::instance::InstanceRaw;
+use crate::lib_state::State;
+use crate::model::{self, Vertex};
+use wgpu::util::DeviceExt;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct OutlineUniform {
+    color: [f32; 4],
+    width: f32, // Pixels
+    _padding: [f32; 3],
+}
+
+/// Outline pipeline, shared by every outlined model
+pub struct OutlineRenderer {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    bind_group_layout: wgpu::BindGroupLayout,
+}
+
+impl OutlineRenderer {
+    /// Build the outline pipeline
+    ///
+    /// `shared_bind_group_layouts` are the camera, light and globals layouts used by
+    /// every other pipeline; the outline color and width are bound at group 3.
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+        shared_bind_group_layouts: [&wgpu::BindGroupLayout; 3],
+        front_face: wgpu::FrontFace,
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("outline_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let [camera_layout, light_layout, globals_layout] = shared_bind_group_layouts;
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Outline Pipeline Layout"),
+            bind_group_layouts: &[camera_layout, light_layout, globals_layout, &bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = create_outline_pipeline(device, config, depth_format, &pipeline_layout, front_face);
+        Self { pipeline, pipeline_layout, bind_group_layout }
+    }
+
+    /// Rebuild the pipeline for a new mesh winding, so the hull keeps culling the front faces
+    pub fn set_front_face(
+        &mut self,
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+        front_face: wgpu::FrontFace,
+    ) {
+        self.pipeline = create_outline_pipeline(device, config, depth_format, &self.pipeline_layout, front_face);
+    }
+}
+
+/// The hull pipeline: front faces culled, depth tested and written like the meshes
+fn create_outline_pipeline(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    depth_format: wgpu::TextureFormat,
+    pipeline_layout: &wgpu::PipelineLayout,
+    front_face: wgpu::FrontFace,
+) -> wgpu::RenderPipeline {
+    let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Outline Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shaders/outline.wgsl").into()),
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Outline Render Pipeline"),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader_module,
+            entry_point: Some("vs_main"),
+            buffers: &[model::ModelVertex::desc(), InstanceRaw::desc()],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_module,
+            entry_point: Some("fs_main"),
+            compilation_options: Default::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: config.format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face,
+            cull_mode: Some(wgpu::Face::Front), // Only the far side of the hull, behind the mesh
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: depth_format,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 4, // Enable 4x MSAA for web compatibility
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// Outline mesh `index` (0 is `obj_model`, then `additional_mesh_models`) `width` pixels
+/// wide in `color`; a width of 0 or less removes the outline
+pub fn set_outlined(state: &mut State, index: usize, color: [f32; 4], width: f32) -> Result<(), String> {
+    let mesh_count = 1 + state.additional_mesh_models.len();
+    let model = match index {
+        0 => &mut state.obj_model,
+        _ => state.additional_mesh_models.get_mut(index - 1)
+            .ok_or_else(|| format!("Mesh index {} out of range ({} meshes)", index, mesh_count))?,
+    };
+    if width <= 0.0 {
+        model.outline = None;
+        return Ok(());
+    }
+
+    let uniform = OutlineUniform { color, width, _padding: [0.0; 3] };
+    match &mut model.outline {
+        Some(outline) => {
+            outline.color = color;
+            outline.width = width;
+            state.queue.write_buffer(&outline.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+        }
+        None => {
+            let uniform_buffer = state.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Outline Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[uniform]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+            let bind_group = state.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("outline_bind_group"),
+                layout: &state.outline_renderer.bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+            });
+            model.outline = Some(model::ModelOutline { color, width, uniform_buffer, bind_group });
+        }
+    }
+    Ok(())
+}
+
+/// Draw the hull of every visible outlined model, after the shaded meshes
+///
+/// `models` are the visible mesh models with their instance buffers and counts.
+pub fn render_outlines<'a>(
+    state: &'a State,
+    models: impl Iterator<Item = (&'a model::Model, &'a wgpu::Buffer, u32)>,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    let mut pipeline_set = false;
+    for (model, instance_buffer, instance_count) in models {
+        let Some(outline) = &model.outline else {
+            continue;
+        };
+        if !pipeline_set {
+            render_pass.set_pipeline(&state.outline_renderer.pipeline);
+            render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+            pipeline_set = true;
+        }
+        render_pass.set_bind_group(3, &outline.bind_group, &[]);
+        render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+        for mesh in &model.meshes {
+            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..mesh.num_elements, 0, 0..instance_count);
+        }
+    }
+}
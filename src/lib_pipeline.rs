@@ -5,6 +5,7 @@ pub fn create_render_pipeline(
     depth_format: Option<wgpu::TextureFormat>,
     vertex_layouts: &[wgpu::VertexBufferLayout],
     shader: wgpu::ShaderModuleDescriptor,
+    front_face: wgpu::FrontFace,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(shader);
 
@@ -33,7 +34,7 @@ pub fn create_render_pipeline(
         primitive: wgpu::PrimitiveState {
             topology: wgpu::PrimitiveTopology::TriangleList,
             strip_index_format: None,
-            front_face: wgpu::FrontFace::Ccw,
+            front_face,
             cull_mode: Some(wgpu::Face::Back), // Re-enabled face culling
             // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
             polygon_mode: wgpu::PolygonMode::Fill,
@@ -59,4 +60,93 @@ pub fn create_render_pipeline(
         multiview: None,
         cache: None,
     })
-}
\ No newline at end of file
+}
+/// How point and line fragments are combined with the color already drawn
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Standard transparency (SrcAlpha, OneMinusSrcAlpha)
+    #[default]
+    Alpha,
+    /// Colors add up (One, One), for glowing points and lines on dark backgrounds
+    Additive,
+    /// Fragments replace the target color
+    Opaque,
+}
+
+impl BlendMode {
+    /// Blend state of the pipeline variant for this mode
+    pub fn blend_state(self) -> wgpu::BlendState {
+        match self {
+            BlendMode::Alpha => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            },
+            BlendMode::Opaque => wgpu::BlendState::REPLACE,
+        }
+    }
+
+    /// Additive geometry does not write depth, so overlapping points and lines all accumulate
+    pub fn depth_write_enabled(self) -> bool {
+        self != BlendMode::Additive
+    }
+}
+
+/// One pipeline per `BlendMode`, otherwise identical
+pub struct BlendPipelines {
+    alpha: wgpu::RenderPipeline,
+    additive: wgpu::RenderPipeline,
+    opaque: wgpu::RenderPipeline,
+}
+
+impl BlendPipelines {
+    /// Create the three variants with `create_pipeline`
+    pub fn new(mut create_pipeline: impl FnMut(BlendMode) -> wgpu::RenderPipeline) -> Self {
+        Self {
+            alpha: create_pipeline(BlendMode::Alpha),
+            additive: create_pipeline(BlendMode::Additive),
+            opaque: create_pipeline(BlendMode::Opaque),
+        }
+    }
+
+    /// Pipeline variant for `mode`
+    pub fn get(&self, mode: BlendMode) -> &wgpu::RenderPipeline {
+        match mode {
+            BlendMode::Alpha => &self.alpha,
+            BlendMode::Additive => &self.additive,
+            BlendMode::Opaque => &self.opaque,
+        }
+    }
+}
+
+/// Set by `State::reload_shaders`: from then on `shader_source` reads the WGSL files from disk
+static SHADERS_FROM_DISK: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Read the shaders from `src/shaders` on disk instead of the copies compiled in (native development builds)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_shaders_from_disk() {
+    SHADERS_FROM_DISK.store(true, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// WGSL source of the shader file `name`: `embedded`, the `include_str!` copy, until shaders
+/// are read from disk, then the file in the source tree the viewer was built from
+///
+/// A file that cannot be read falls back to `embedded` with a warning.
+pub fn shader_source(name: &str, embedded: &'static str) -> std::borrow::Cow<'static, str> {
+    if !SHADERS_FROM_DISK.load(std::sync::atomic::Ordering::Relaxed) {
+        return embedded.into();
+    }
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/shaders").join(name);
+    std::fs::read_to_string(&path).map(Into::into).unwrap_or_else(|e| {
+        log::warn!("Failed to read shader {}: {}; using the built-in copy", path.display(), e);
+        embedded.into()
+    })
+}
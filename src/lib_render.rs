@@ -1,9 +1,11 @@
-use crate::lib_state::State;
+use crate::lib_state::{BackgroundClear, HiddenLineMode, State};
 use crate::RenderMode;
 use crate::model::{DrawModel, DrawLight};
-use crate::model_point::DrawQuadPoints;
+use crate::model_point::{DrawQuadPoints, PointColorMode, PointShape, QuadPointModel};
 use crate::model_pipe::DrawPipes;
-use crate::model_polygon::DrawPolygons;
+use crate::model_polygon::{DrawPolygons, PolygonModel};
+use crate::lib_pipeline::BlendMode;
+use crate::model_line::DrawWideLines;
 use crate::lib_geometry_manager::create_pipes_from_lines;
 use crate::camera;
 use cgmath::prelude::*;
@@ -16,7 +18,9 @@ use std::iter;
 pub struct CameraUniform {
     view_position: [f32; 4],
     view_proj: [[f32; 4]; 4],
-    aspect_ratio: [f32; 4], // Using vec4 for alignment (only first value used)
+    aspect_ratio: [f32; 4], // Aspect ratio (x), render target pixels per output pixel (y); vec4 for alignment
+    viewport_size: [f32; 4], // Viewport width and height in pixels (xy), top-left corner in the window (zw)
+    clip_plane: [f32; 4], // Plane equation (normal xyz, offset w); all zero disables clipping
 }
 
 impl CameraUniform {
@@ -24,7 +28,9 @@ impl CameraUniform {
         Self {
             view_position: [0.0; 4],
             view_proj: cgmath::Matrix4::identity().into(),
-            aspect_ratio: [1.0, 0.0, 0.0, 0.0], // Default to 1.0 aspect ratio
+            aspect_ratio: [1.0, 1.0, 0.0, 0.0], // Default to 1.0 aspect ratio, unscaled pixels
+            viewport_size: [1.0, 1.0, 0.0, 0.0],
+            clip_plane: [0.0; 4],
         }
     }
 
@@ -35,18 +41,279 @@ impl CameraUniform {
     
     pub fn update_aspect_ratio(&mut self, width: f32, height: f32) {
         self.aspect_ratio[0] = width / height;
+        // Wide lines need the pixel size of the viewport for screen-space expansion
+        self.viewport_size[0] = width;
+        self.viewport_size[1] = height;
+    }
+
+    /// Set how many render target pixels make one pixel of the output, e.g. the supersampling factor
+    ///
+    /// Line widths, label offsets and other sizes given in pixels are multiplied by it, so they
+    /// keep their size in the downsampled image.
+    pub fn update_pixel_scale(&mut self, scale: f32) {
+        self.aspect_ratio[1] = scale;
+    }
+
+    /// Render target pixels per output pixel, see `update_pixel_scale`
+    pub fn pixel_scale(&self) -> f32 {
+        self.aspect_ratio[1]
+    }
+
+    /// Set the top-left pixel of the viewport in the window, nonzero for the right half of a split view
+    pub fn update_viewport_origin(&mut self, x: f32, y: f32) {
+        self.viewport_size[2] = x;
+        self.viewport_size[3] = y;
+    }
+
+    /// View-projection matrix of the current view
+    pub fn view_proj(&self) -> [[f32; 4]; 4] {
+        self.view_proj
+    }
+
+    /// Camera position of the current view
+    pub fn eye(&self) -> cgmath::Point3<f32> {
+        cgmath::Point3::new(self.view_position[0], self.view_position[1], self.view_position[2])
+    }
+
+    /// Set the clipping plane; fragments with `dot(plane, (p, 1)) < 0` are discarded
+    pub fn update_clip_plane(&mut self, clip_plane: Option<[f32; 4]>) {
+        self.clip_plane = clip_plane.unwrap_or([0.0; 4]);
+    }
+
+    /// Pixel rectangle of the viewport in the window: x, y of the top-left corner, width, height
+    pub fn viewport_rect(&self) -> [f32; 4] {
+        let [width, height, x, y] = self.viewport_size;
+        [x, y, width, height]
     }
 }
 
+/// Maximum number of simultaneous lights the shaders loop over
+pub const MAX_LIGHTS: usize = 8;
+
+/// A single point light, laid out like `LightSource` in the shaders
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
-pub struct LightUniform {
+pub struct LightSource {
     pub position: [f32; 3],
     pub _padding: u32,
     pub color: [f32; 3],
     pub _padding2: u32,
 }
 
+impl LightSource {
+    pub fn new(position: [f32; 3], color: [f32; 3]) -> Self {
+        Self {
+            position,
+            _padding: 0,
+            color,
+            _padding2: 0,
+        }
+    }
+}
+
+/// Lights at bind group 1; only the first `count` entries of `lights` are lit
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightUniform {
+    pub lights: [LightSource; MAX_LIGHTS],
+    pub count: u32,
+    pub _padding: [u32; 3],
+}
+
+impl LightUniform {
+    /// A single white light, the default scene lighting
+    pub fn new() -> Self {
+        let mut light_uniform = Self {
+            lights: [LightSource::new([0.0; 3], [0.0; 3]); MAX_LIGHTS],
+            count: 0,
+            _padding: [0; 3],
+        };
+        light_uniform.add(LightSource::new([2.0, 2.0, 2.0], [1.0, 1.0, 1.0]));
+        light_uniform
+    }
+
+    /// The active lights
+    pub fn active(&self) -> &[LightSource] {
+        &self.lights[..self.count as usize]
+    }
+
+    /// The active lights, mutable
+    pub fn active_mut(&mut self) -> &mut [LightSource] {
+        &mut self.lights[..self.count as usize]
+    }
+
+    /// Append a light and return its index, or `None` when all `MAX_LIGHTS` slots are used
+    pub fn add(&mut self, light: LightSource) -> Option<usize> {
+        let index = self.count as usize;
+        if index >= MAX_LIGHTS {
+            return None;
+        }
+        self.lights[index] = light;
+        self.count += 1;
+        Some(index)
+    }
+
+    /// Remove the light at `index`; later lights shift down by one
+    pub fn remove(&mut self, index: usize) -> Option<LightSource> {
+        let count = self.count as usize;
+        if index >= count {
+            return None;
+        }
+        let removed = self.lights[index];
+        self.lights.copy_within(index + 1..count, index);
+        self.count -= 1;
+        Some(removed)
+    }
+
+    /// Turn the active lights `dt` seconds further along `orbit`
+    pub fn orbit(&mut self, orbit: &LightOrbit, dt: f32) {
+        let axis = cgmath::Vector3::from(orbit.axis);
+        if axis.magnitude2() == 0.0 {
+            return;
+        }
+        let axis = axis.normalize();
+        let rotation = cgmath::Quaternion::from_axis_angle(axis, cgmath::Deg(orbit.speed * dt));
+        for light in self.active_mut() {
+            let mut position = rotation * cgmath::Vector3::from(light.position);
+            if let Some(radius) = orbit.radius {
+                // Rescale the part around the axis, keeping the height along it
+                let height = axis * position.dot(axis);
+                let around = position - height;
+                if around.magnitude2() > 0.0 {
+                    position = height + around.normalize() * radius;
+                }
+            }
+            light.position = position.into();
+        }
+    }
+}
+
+/// How the lights circle the scene origin each frame
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LightOrbit {
+    /// Distance of the lights from the axis; `None` keeps each light's own distance
+    pub radius: Option<f32>,
+    /// Degrees per second, counter-clockwise around `axis`; negative turns the other way
+    pub speed: f32,
+    /// Axis through the origin the lights turn around
+    pub axis: [f32; 3],
+}
+
+impl Default for LightOrbit {
+    /// The startup orbit: a full turn every six seconds around the up axis
+    fn default() -> Self {
+        Self { radius: None, speed: 60.0, axis: [0.0, 1.0, 0.0] }
+    }
+}
+
+/// How the mesh shader colors meshes
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum MeshShadingMode {
+    /// Lit vertex or face colors
+    #[default]
+    Lit,
+    /// Texture coordinates as colors (u red, v green) for checking UV imports
+    Uv,
+    /// Length of the imported normals as grey, unit length mid-grey, to find unnormalized normals
+    NormalLength,
+}
+
+/// Globals shared by every pipeline at bind group 2
+///
+/// New shader parameters go here instead of adding a bind group per feature.
+/// Every shader declares `Globals`; current readers:
+/// - `point_size`: point.wgsl
+/// - `time`: none yet, available to all shaders for animated effects
+/// - `encode_srgb`: every fragment shader, via `encode_output`
+/// - `point_color_mode`: point.wgsl (other shaders keep the slot as `_padding`)
+/// - `exposure`: every fragment shader, via `encode_output`
+/// - `grid_line_width`: wide_line.wgsl (`vs_grid`)
+/// - `mesh_shading_mode`: shader.wgsl (other shaders keep the slot as `_padding_2`)
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GlobalsUniform {
+    pub point_size: f32, // Multiplier applied to the per-vertex point size
+    pub time: f32,       // Elapsed time in seconds
+    pub encode_srgb: f32, // 1.0 when the surface is linear and shaders must gamma encode
+    pub point_color_mode: f32, // 1.0 colors points by density (`PointColorMode::Density`)
+    pub exposure: f32,   // Brightness multiplier applied to the final color
+    pub grid_line_width: f32, // Screen-space width of the reference grid lines in pixels
+    pub mesh_shading_mode: f32, // 1.0 shows mesh texture coordinates (`MeshShadingMode::Uv`), 2.0 normal lengths
+    pub _padding: f32,
+}
+
+impl GlobalsUniform {
+    pub fn new() -> Self {
+        Self {
+            point_size: 1.0,
+            time: 0.0,
+            encode_srgb: 0.0,
+            point_color_mode: 0.0,
+            exposure: 1.0,
+            grid_line_width: GRID_LINE_WIDTH,
+            mesh_shading_mode: 0.0,
+            _padding: 0.0,
+        }
+    }
+
+    /// Enable manual gamma encoding in the shaders unless the surface format is sRGB
+    pub fn update_surface_format(&mut self, format: wgpu::TextureFormat) {
+        self.encode_srgb = if format.is_srgb() { 0.0 } else { 1.0 };
+    }
+
+    /// Select how the point shader colors points
+    pub fn set_point_color_mode(&mut self, mode: PointColorMode) {
+        self.point_color_mode = match mode {
+            PointColorMode::Vertex => 0.0,
+            PointColorMode::Density => 1.0,
+        };
+    }
+
+    /// Select how the mesh shader colors meshes
+    pub fn set_mesh_shading_mode(&mut self, mode: MeshShadingMode) {
+        self.mesh_shading_mode = match mode {
+            MeshShadingMode::Lit => 0.0,
+            MeshShadingMode::Uv => 1.0,
+            MeshShadingMode::NormalLength => 2.0,
+        };
+    }
+
+    /// Current mesh shading mode
+    pub fn mesh_shading_mode(&self) -> MeshShadingMode {
+        if self.mesh_shading_mode > 1.5 {
+            MeshShadingMode::NormalLength
+        } else if self.mesh_shading_mode > 0.5 {
+            MeshShadingMode::Uv
+        } else {
+            MeshShadingMode::Lit
+        }
+    }
+
+    /// Set the brightness multiplier, clamped to `MIN_EXPOSURE..=MAX_EXPOSURE`
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure.clamp(MIN_EXPOSURE, MAX_EXPOSURE);
+    }
+
+    /// Set the grid line width in pixels, clamped to `MIN_GRID_LINE_WIDTH..=MAX_GRID_LINE_WIDTH`
+    pub fn set_grid_line_width(&mut self, width: f32) {
+        self.grid_line_width = width.clamp(MIN_GRID_LINE_WIDTH, MAX_GRID_LINE_WIDTH);
+    }
+
+    /// Accumulate frame time; wraps every hour to keep f32 precision for animations
+    pub fn advance_time(&mut self, dt: f32) {
+        self.time = (self.time + dt) % 3600.0;
+    }
+}
+
+/// Exposure limits, so the image never turns fully black or fully white
+pub const MIN_EXPOSURE: f32 = 0.05;
+pub const MAX_EXPOSURE: f32 = 20.0;
+
+/// Default and limits of the grid line width in pixels; it stays the same on screen at any zoom
+pub const GRID_LINE_WIDTH: f32 = 1.5;
+pub const MIN_GRID_LINE_WIDTH: f32 = 0.5;
+pub const MAX_GRID_LINE_WIDTH: f32 = 10.0;
+
 /// Main rendering function that handles all GPU drawing operations
 pub fn render(state: &mut State) -> Result<(), wgpu::SurfaceError> {
     let output = state.surface.get_current_texture()?;
@@ -54,14 +321,63 @@ pub fn render(state: &mut State) -> Result<(), wgpu::SurfaceError> {
         .texture
         .create_view(&wgpu::TextureViewDescriptor::default());
 
-    let mut encoder = state
-        .device
-        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Render Encoder"),
-        });
+    let multisample_view = state.multisample_texture_view.clone();
+    let multisample_depth_view = state.multisample_depth_texture_view.clone();
+    if state.split_view.is_some() {
+        crate::lib_split_view::render_split_view(state, &multisample_view, &multisample_depth_view, &view);
+    } else {
+        let mut encoder = state
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        encode_scene(state, &mut encoder, &multisample_view, &multisample_depth_view, &view);
+        state.queue.submit(iter::once(encoder.finish()));
+    }
+    output.present();
+
+    // Debug primitives only live for the frame they were queued in
+    state.debug_draw.clear();
+
+    Ok(())
+}
 
+/// Record the scene into `encoder`, rendering into the multisampled color/depth views
+/// and resolving into `resolve_view`
+///
+/// Shared by the on-screen `render` and offscreen snapshot rendering.
+pub fn encode_scene(
+    state: &mut State,
+    encoder: &mut wgpu::CommandEncoder,
+    multisample_view: &wgpu::TextureView,
+    multisample_depth_view: &wgpu::TextureView,
+    resolve_view: &wgpu::TextureView,
+) {
+    encode_scene_in_viewport(state, encoder, multisample_view, multisample_depth_view, resolve_view, None);
+    // The depth view reads the finished depth buffer, so it needs a pass of its own
+    crate::lib_depth_overlay::render_depth_overlay(state, encoder, multisample_depth_view, resolve_view);
+}
+
+/// Part of the window one scene pass draws into, for split view
+pub struct ViewportPass {
+    pub rect: [u32; 4],   // x, y, width, height in pixels
+    pub clear: bool,      // The first viewport of the frame clears color and depth, later ones load them
+    pub help_panel: bool, // Whether the help panel is drawn in this viewport
+}
+
+/// Record one scene pass, like `encode_scene`, limited to `viewport` when given
+///
+/// The depth overlay is left to the caller, to run once the whole frame is drawn.
+pub fn encode_scene_in_viewport(
+    state: &mut State,
+    encoder: &mut wgpu::CommandEncoder,
+    multisample_view: &wgpu::TextureView,
+    multisample_depth_view: &wgpu::TextureView,
+    resolve_view: &wgpu::TextureView,
+    viewport: Option<&ViewportPass>,
+) {
     // Handle render modes that need to modify state before rendering
-    match state.render_mode {
+    match state.settings.render_mode {
         RenderMode::All | RenderMode::Lines => {
             // Create pipe lines from line data if needed
             if state.pipe_model.is_none() && state.line_model.is_some() {
@@ -77,26 +393,27 @@ pub fn render(state: &mut State) -> Result<(), wgpu::SurfaceError> {
         _ => {}
     }
 
+    // Upload this frame's debug primitives before the pass borrows them
+    let debug_models = (!state.debug_draw.is_empty()).then(|| state.debug_draw.upload(&state.device));
+    state.labels.upload(&state.device);
+
+    // Clearing covers the whole attachment whatever the viewport, so only the first viewport clears
+    let clear = viewport.is_none_or(|viewport| viewport.clear);
     {
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Render Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &state.multisample_texture_view, // Render to multisample texture
-                resolve_target: Some(&view), // Resolve to final texture
+                view: multisample_view, // Render to multisample texture
+                resolve_target: Some(resolve_view), // Resolve to final texture
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: 0.9,
-                        g: 0.9,
-                        b: 0.9,
-                        a: 1.0,
-                    }),
+                    load: if clear { background_load_op(state) } else { wgpu::LoadOp::Load },
                     store: wgpu::StoreOp::Store,
                 },
             })],
             depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                view: &state.multisample_depth_texture_view, // Use multisample depth texture
+                view: multisample_depth_view, // Use multisample depth texture
                 depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(1.0),
+                    load: if clear { wgpu::LoadOp::Clear(1.0) } else { wgpu::LoadOp::Load },
                     store: wgpu::StoreOp::Store,
                 }),
                 stencil_ops: None,
@@ -105,8 +422,29 @@ pub fn render(state: &mut State) -> Result<(), wgpu::SurfaceError> {
             timestamp_writes: None,
         });
 
+        if let Some(ViewportPass { rect: [x, y, width, height], .. }) = viewport {
+            render_pass.set_viewport(*x as f32, *y as f32, *width as f32, *height as f32, 0.0, 1.0);
+            render_pass.set_scissor_rect(*x, *y, *width, *height);
+        }
+
+        // Every pipeline layout is [camera, light, globals], so the shared groups are bound once
+        render_pass.set_bind_group(1, &state.light_bind_group, &[]);
+        render_pass.set_bind_group(2, &state.globals_bind_group, &[]);
+
+        // Drawing only reads the state from here on
+        let state: &State = state;
+
+        // The gradient background and the reference image go behind everything, without touching depth
+        crate::lib_background::render_background_gradient(state, &mut render_pass);
+        crate::lib_backplate::render_backplate(state, &mut render_pass);
+
+        // Opaque meshes fill the depth buffer first when the pre-pass is enabled
+        if use_depth_prepass(state) {
+            render_mesh_depth_prepass(state, &mut render_pass);
+        }
+
         // Render based on the selected render mode
-        match state.render_mode {
+        match state.settings.render_mode {
             RenderMode::All => {
                 render_all_mode(state, &mut render_pass);
             },
@@ -126,144 +464,534 @@ pub fn render(state: &mut State) -> Result<(), wgpu::SurfaceError> {
                 render_meshes_mode(state, &mut render_pass);
             },
         }
+
+        // Debug lines and points go on top of the scene in every render mode
+        if let Some(models) = &debug_models {
+            crate::lib_debug_draw::render_debug_draw(state, models, &mut render_pass);
+        }
+
+        // The orbit pivot crosshair ignores depth so it shows through the geometry
+        crate::lib_target_gizmo::render_target_gizmo(state, &mut render_pass);
+
+        // Labels come last so they blend over everything drawn before
+        crate::lib_labels::render_labels(state, &mut render_pass);
+
+        // Screen space UI goes on top of the scene and its labels: the color legend in every
+        // viewport, then the help panel
+        crate::lib_legend::render_legend(state, &mut render_pass);
+        if viewport.is_none_or(|viewport| viewport.help_panel) {
+            crate::lib_help_overlay::render_help_overlay(state, &mut render_pass);
+        }
     }
-    state.queue.submit(iter::once(encoder.finish()));
-    output.present();
+}
 
-    Ok(())
+/// Color load op for the background setting; a premultiplied surface gets a premultiplied clear color
+fn background_load_op(state: &State) -> wgpu::LoadOp<wgpu::Color> {
+    match state.settings.background_clear {
+        BackgroundClear::Color([r, g, b, a]) => {
+            let premultiply = if state.config.alpha_mode == wgpu::CompositeAlphaMode::PreMultiplied { a } else { 1.0 };
+            wgpu::LoadOp::Clear(wgpu::Color {
+                r: (r * premultiply) as f64,
+                g: (g * premultiply) as f64,
+                b: (b * premultiply) as f64,
+                a: a as f64,
+            })
+        }
+        BackgroundClear::Load => wgpu::LoadOp::Load,
+        // Covered by the gradient drawn first in the pass
+        BackgroundClear::Gradient { bottom: [r, g, b], .. } => wgpu::LoadOp::Clear(wgpu::Color {
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+            a: 1.0,
+        }),
+    }
 }
 
-/// Render all geometry types (meshes, points, lines, polygons)
-fn render_all_mode<'a>(
-    state: &'a mut State,
+/// Mesh pipeline for the current debug settings
+fn mesh_pipeline<'a>(state: &'a State) -> &'a wgpu::RenderPipeline {
+    if state.settings.show_face_orientation {
+        if let Some(pipeline) = &state.pipelines.face_orientation_pipeline {
+            return pipeline;
+        }
+    }
+    match (&state.pipelines.mesh_depth_equal_pipeline, use_depth_prepass(state)) {
+        (Some(pipeline), true) => pipeline,
+        _ => &state.pipelines.render_pipeline,
+    }
+}
+
+/// Whether meshes are drawn with a depth pre-pass this frame
+fn use_depth_prepass(state: &State) -> bool {
+    state.settings.depth_prepass
+        && !state.settings.show_face_orientation
+        && state.pipelines.mesh_depth_prepass_pipeline.is_some()
+        && matches!(state.settings.render_mode, RenderMode::All | RenderMode::Meshes)
+}
+
+/// Write mesh depth before any shading; only the mesh pipelines have a depth-equal color pass
+fn render_mesh_depth_prepass<'a>(
+    state: &'a State,
     render_pass: &mut wgpu::RenderPass<'a>,
 ) {
+    if let Some(pipeline) = &state.pipelines.mesh_depth_prepass_pipeline {
+        render_pass.set_pipeline(pipeline);
+        // Must match the instance ranges of the color pass exactly
+        draw_mesh_models(state, render_pass);
+    }
+}
 
+/// Draw every visible opaque mesh model with edge visualization, each with its own instances
+///
+/// Translucent models are left to the transparent phase (`render_transparent_meshes`).
+fn draw_mesh_models<'a>(
+    state: &'a State,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    for (model, instance_buffer, instance_count) in visible_mesh_models(state) {
+        if draw_transparent(state, model) {
+            continue;
+        }
+        render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+        render_pass.draw_model_with_edges_instanced(
+            model,
+            0..instance_count,
+            &state.camera_bind_group,
+            &state.light_bind_group,
+        );
+    }
+}
 
+/// Whether `obj_model` is drawn: always once a mesh was loaded into it, while it is
+/// still the startup cube only until any geometry is loaded (and if not hidden)
+fn draw_obj_model(state: &State) -> bool {
+    !state.obj_model_is_default || (state.show_default_model && state.geometry_counts.is_empty())
+}
 
+/// Mesh models drawn this frame with their instance buffers and counts, matching the shaded mesh draws
+///
+/// Models with their own instances (`set_model_instances`) use them; the others share
+/// `State::instance_buffer`. Models with no instances are skipped.
+fn visible_mesh_models<'a>(state: &'a State) -> impl Iterator<Item = (&'a crate::model::Model, &'a wgpu::Buffer, u32)> {
+    let obj_model = draw_obj_model(state).then_some((&state.obj_model, state.instances.len() as u32));
+    let additional_instances = additional_mesh_instances(state);
+    let additional = state.additional_mesh_models.iter().map(move |model| (model, additional_instances));
+    obj_model
+        .into_iter()
+        .chain(additional)
+        .map(move |(model, shared_count)| match &model.instances {
+            Some(instances) => (model, &instances.buffer, instances.instances.len() as u32),
+            None => (model, &state.instance_buffer, shared_count),
+        })
+        .filter(|&(_, _, instance_count)| instance_count > 0)
+}
 
+/// Instances a visible mesh model is drawn with: its own, or the first `instance_count` shared ones
+fn model_instances<'a>(state: &'a State, model: &'a crate::model::Model, instance_count: u32) -> &'a [crate::instance::Instance] {
+    match &model.instances {
+        Some(instances) => &instances.instances[..],
+        None => &state.instances[..(instance_count as usize).min(state.instances.len())],
+    }
+}
 
-    // Render the light model
-    render_pass.set_vertex_buffer(1, state.instance_buffer.slice(..));
-    render_pass.set_pipeline(&state.light_render_pipeline);
-    render_pass.draw_light_model(
-        &state.obj_model,
-        &state.camera_bind_group,
-        &state.light_bind_group,
-    );
-    
-    // Render the mesh model
-    render_pass.set_pipeline(&state.render_pipeline);
-    // Draw main mesh model with edge visualization
-    render_pass.draw_model_with_edges_instanced(
-        &state.obj_model,
-        0..state.instances.len() as u32,
-        &state.camera_bind_group,
-        &state.light_bind_group,
-    );
-    
-    // Draw all additional mesh models with edge visualization
-    for model in &state.additional_mesh_models {
-        render_pass.draw_model_with_edges_instanced(
-            model,
-            0..1, // Only draw one instance for additional models
+/// World-space bounds (min, max) of the drawn meshes with their instances, the point cloud,
+/// the pipes and the polygon sets; `None` when none of them has geometry
+pub(crate) fn drawn_geometry_bounds(state: &State) -> Option<([f32; 3], [f32; 3])> {
+    // The corners of each mesh's bounds, placed by every instance it is drawn with
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    for (model, _, instance_count) in visible_mesh_models(state) {
+        let Some((min, max)) = model.bounds() else {
+            continue;
+        };
+        for instance in model_instances(state, model, instance_count) {
+            for corner in 0..8 {
+                let local = cgmath::Point3::new(
+                    if corner & 1 == 0 { min[0] } else { max[0] },
+                    if corner & 2 == 0 { min[1] } else { max[1] },
+                    if corner & 4 == 0 { min[2] } else { max[2] },
+                );
+                positions.push((instance.position + instance.rotation.rotate_point(local).to_vec()).into());
+            }
+        }
+    }
+    let model_bounds = state
+        .quad_point_model
+        .iter()
+        .map(|model| model.bounds)
+        .chain(state.pipe_model.iter().map(|model| model.bounds))
+        .chain(polygon_sets(state).map(|model| model.bounds))
+        .flatten();
+    for (min, max) in model_bounds {
+        positions.extend([min, max]);
+    }
+    crate::model_polygon::position_bounds(positions.into_iter())
+}
+
+/// Whether a mesh model is drawn blended in the transparent phase this frame
+///
+/// The face orientation view shows every mesh opaque.
+fn draw_transparent(state: &State, model: &crate::model::Model) -> bool {
+    model.is_transparent() && !state.settings.show_face_orientation
+}
+
+/// Translucent mesh models to draw this frame, keyed by the world position of their centroid
+///
+/// The centroid is the center of the model's bounds, averaged over the instances it is drawn with.
+fn transparent_mesh_draws<'a>(state: &'a State) -> impl Iterator<Item = ([f32; 3], TransparentDraw<'a>)> {
+    visible_mesh_models(state)
+        .filter(move |(model, _, _)| draw_transparent(state, model))
+        .map(move |(model, instance_buffer, instance_count)| {
+            let instances = model_instances(state, model, instance_count);
+            let center = cgmath::Point3::from(model.center());
+            let world_center = instances
+                .iter()
+                .map(|instance| instance.position + instance.rotation.rotate_point(center).to_vec())
+                .sum::<cgmath::Vector3<f32>>()
+                / instances.len().max(1) as f32;
+            (world_center.into(), TransparentDraw::Mesh(model, instance_buffer, instance_count))
+        })
+}
+
+/// Draw the translucent mesh models only, farthest first (for the Meshes mode)
+fn render_transparent_meshes<'a>(
+    state: &'a State,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    draw_back_to_front(state, transparent_mesh_draws(state).collect(), render_pass);
+}
+
+/// Draw the triangle edges of a model as lines with the currently set wireframe pipeline
+fn draw_mesh_edges<'a>(
+    model: &'a crate::model::Model,
+    instance_buffer: &'a wgpu::Buffer,
+    instance_count: u32,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+    for mesh in &model.meshes {
+        render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(mesh.edge_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..mesh.num_edge_elements, 0, 0..instance_count);
+    }
+}
+
+/// Draw the edges of every mesh model with its wireframe enabled over the shaded surfaces
+///
+/// Uses the same instance buffers and ranges as the meshes.
+fn render_mesh_wireframes<'a>(
+    state: &'a State,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    let Some(pipeline) = &state.pipelines.wireframe_pipeline else {
+        return;
+    };
+
+    let mut pipeline_set = false;
+    for (model, instance_buffer, instance_count) in visible_mesh_models(state) {
+        let Some(wireframe) = model.wireframe.as_ref().filter(|_| model.show_wireframe) else {
+            continue;
+        };
+        if !pipeline_set {
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+            pipeline_set = true;
+        }
+        render_pass.set_bind_group(3, &wireframe.bind_group, &[]);
+        draw_mesh_edges(model, instance_buffer, instance_count, render_pass);
+    }
+}
+
+/// Hidden line view: all mesh edges solid where they pass the depth test against the
+/// shaded meshes, and dashed where they fail it (`Greater`) when occluded edges are shown
+fn render_hidden_lines<'a>(
+    state: &'a State,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    let hidden_pipeline = match state.settings.hidden_line_mode {
+        HiddenLineMode::Off => return,
+        HiddenLineMode::Hidden => None,
+        HiddenLineMode::Dashed => state.pipelines.hidden_line_pipeline.as_ref(),
+    };
+
+    render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+    render_pass.set_bind_group(3, &state.hidden_line_bind_group, &[]);
+    for pipeline in state.pipelines.wireframe_pipeline.iter().chain(hidden_pipeline) {
+        render_pass.set_pipeline(pipeline);
+        for (model, instance_buffer, instance_count) in visible_mesh_models(state) {
+            draw_mesh_edges(model, instance_buffer, instance_count, render_pass);
+        }
+    }
+}
+
+/// Draw a position indicator per light (one instance each) unless hidden
+fn render_light_model<'a>(
+    state: &'a State,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    if state.settings.show_light_model && state.light_uniform.count > 0 {
+        render_pass.set_pipeline(&state.pipelines.light_render_pipeline);
+        render_pass.draw_light_model_instanced(
+            &state.obj_model,
+            0..state.light_uniform.count,
             &state.camera_bind_group,
             &state.light_bind_group,
         );
     }
+}
 
-    // Render points if available - use the quad-based point model for better visuals
-    if let (Some(pipeline), Some(model)) = (&state.point_pipeline, &state.quad_point_model) {
-        render_pass.set_pipeline(pipeline);
-        render_pass.draw_quad_points(model, &state.camera_bind_group);
+/// Instance count used for additional mesh models in the current render mode
+fn additional_mesh_instances(state: &State) -> u32 {
+    match state.settings.render_mode {
+        RenderMode::Meshes => state.instances.len() as u32,
+        _ => 1, // Only draw one instance for additional models
     }
+}
+
+/// Render all geometry types (meshes, points, lines, polygons)
+///
+/// Opaque geometry is drawn first with depth writes on. The translucent point cloud,
+/// polygon sets and meshes follow, back to front without depth writes, so they neither hide geometry
+/// behind them nor get hidden by geometry drawn later.
+fn render_all_mode<'a>(
+    state: &'a State,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    // Render the light model
+    render_pass.set_vertex_buffer(1, state.instance_buffer.slice(..));
+    render_light_model(state, render_pass);
     
+    // Render the main and additional mesh models with edge visualization
+    render_pass.set_pipeline(mesh_pipeline(state));
+    draw_mesh_models(state, render_pass);
+    crate::lib_outline::render_outlines(state, visible_mesh_models(state), render_pass);
+    render_mesh_wireframes(state, render_pass);
+    render_hidden_lines(state, render_pass);
+
     // Render 3D pipe lines instead of regular lines
-    if let (Some(pipeline), Some(model)) = (&state.pipe_pipeline, &state.pipe_model) {
-        render_pass.set_pipeline(pipeline);
-        render_pass.draw_pipes(model, &state.camera_bind_group);
-    }
+    render_pipes(state, render_pass);
     
     // Regular line rendering for grid lines to be visible by default
-    if let (Some(pipeline), Some(model)) = (&state.line_pipeline, &state.line_model) {
+    render_grid(state, render_pass);
+
+    // Render lines loaded from JSON at their requested screen-space width
+    render_wide_line_models(state, render_pass);
+
+    // Opaque points and polygon sets still take part in the depth test of everything after them
+    if let Some(model) = state.quad_point_model.as_ref().filter(|model| points_opaque(state, model)) {
+        if let Some(pipeline) = point_pipeline(state, model) {
+            render_pass.set_pipeline(pipeline);
+            render_pass.draw_quad_points(model, &state.camera_bind_group);
+        }
+    }
+    for model in polygon_sets(state).filter(|model| !model.transparent) {
+        draw_polygon_set(state, model, !depth_write(state), render_pass);
+    }
+
+    render_transparent_geometry(state, render_pass);
+}
+
+/// Geometry drawn in the transparent phase
+enum TransparentDraw<'a> {
+    Points(&'a QuadPointModel),
+    Polygons(&'a PolygonModel),
+    Mesh(&'a crate::model::Model, &'a wgpu::Buffer, u32), // With its instance buffer and count
+}
+
+/// Draw the blended point cloud and the translucent polygon sets and meshes, farthest first
+fn render_transparent_geometry<'a>(
+    state: &'a State,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    let points = state
+        .quad_point_model
+        .iter()
+        .filter(|model| !points_opaque(state, model))
+        .map(|model| (model.center, TransparentDraw::Points(model)));
+    let polygons = polygon_sets(state)
+        .filter(|model| model.transparent)
+        .map(|model| (model.center, TransparentDraw::Polygons(model)));
+    let draws = points.chain(polygons).chain(transparent_mesh_draws(state)).collect();
+    draw_back_to_front(state, draws, render_pass);
+}
+
+/// Sort transparent draws by the distance of their centers to the camera and draw the farthest first
+///
+/// Sorted every frame, so the order follows the camera as it moves.
+fn draw_back_to_front<'a>(
+    state: &'a State,
+    draws: Vec<([f32; 3], TransparentDraw<'a>)>,
+    render_pass: &mut wgpu::RenderPass<'a>,
+) {
+    let eye = state.camera_uniform.eye(); // The camera of the viewport being drawn
+    let mut draws: Vec<(f32, TransparentDraw)> = draws
+        .into_iter()
+        .map(|(center, draw)| (eye.distance2(cgmath::Point3::from(center)), draw))
+        .collect();
+    draws.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (_, draw) in draws {
+        match draw {
+            TransparentDraw::Points(model) => {
+                if let Some(pipelines) = &state.pipelines.transparent_point_pipeline {
+                    render_pass.set_pipeline(pipelines.get(model.blend_mode));
+                    render_pass.draw_quad_points(model, &state.camera_bind_group);
+                }
+            }
+            TransparentDraw::Polygons(model) => draw_polygon_set(state, model, true, render_pass),
+            TransparentDraw::Mesh(model, instance_buffer, instance_count) => {
+                if let Some(opacity) = &model.opacity {
+                    render_pass.set_pipeline(&state.pipelines.transparent_mesh_pipeline);
+                    render_pass.set_bind_group(3, &opacity.bind_group, &[]);
+                    render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+                    render_pass.draw_model_with_edges_instanced(
+                        model,
+                        0..instance_count,
+                        &state.camera_bind_group,
+                        &state.light_bind_group,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Draw the lines loaded from JSON at their requested screen-space width
+fn render_wide_line_models(state: &State, render_pass: &mut wgpu::RenderPass) {
+    let pipelines = if depth_write(state) { &state.pipelines.wide_line_pipeline } else { &state.pipelines.transparent_wide_line_pipeline };
+    if let Some(pipelines) = pipelines {
+        for model in &state.content_line_models {
+            render_pass.set_pipeline(pipelines.get(model.blend_mode));
+            render_pass.draw_wide_lines(model, &state.camera_bind_group);
+        }
+    }
+}
+
+/// Whether points, lines, pipes and polygons write depth in the current render mode
+fn depth_write(state: &State) -> bool {
+    state.settings.depth_write.get(state.settings.render_mode)
+}
+
+/// Point pipelines for the depth write policy of the current render mode
+fn point_pipelines<'a>(state: &'a State) -> Option<&'a crate::lib_pipeline::BlendPipelines> {
+    if depth_write(state) { state.pipelines.point_pipeline.as_ref() } else { state.pipelines.transparent_point_pipeline.as_ref() }
+}
+
+/// Whether the point cloud is drawn with the opaque geometry: surface points and sphere impostors
+fn points_opaque(state: &State, model: &QuadPointModel) -> bool {
+    model.blend_mode == BlendMode::Opaque || state.settings.point_shape == PointShape::Sphere
+}
+
+/// Pipeline for the point cloud's shape; sphere impostors always write depth, as they intersect by it
+fn point_pipeline<'a>(state: &'a State, model: &QuadPointModel) -> Option<&'a wgpu::RenderPipeline> {
+    match state.settings.point_shape {
+        PointShape::Disc => point_pipelines(state).map(|pipelines| pipelines.get(model.blend_mode)),
+        PointShape::Sphere => state.pipelines.sphere_point_pipeline.as_ref(),
+    }
+}
+
+/// Draw the pipes, with depth writes as set for the current render mode
+fn render_pipes(state: &State, render_pass: &mut wgpu::RenderPass) {
+    let pipeline = if depth_write(state) { &state.pipelines.pipe_pipeline } else { &state.pipelines.transparent_pipe_pipeline };
+    if let (Some(pipeline), Some(model)) = (pipeline, &state.pipe_model) {
         render_pass.set_pipeline(pipeline);
-        
-        // Use direct drawing approach to avoid trait issues
-        render_pass.set_vertex_buffer(0, model.vertex_buffer.slice(..));
-        render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
-        render_pass.draw(0..model.num_vertices, 0..1);
+        render_pass.draw_pipes(model, &state.camera_bind_group);
     }
-    
-    // Render polygons loaded from JSON
-    if let (Some(pipeline), Some(model)) = (&state.polygon_pipeline, &state.polygon_model) {
+}
+
+/// Draw the reference grid and axes as faded lines of constant pixel width
+fn render_grid(state: &State, render_pass: &mut wgpu::RenderPass) {
+    // A fully hidden grid has no segments to draw
+    if !state.settings.grid_visibility.any() {
+        return;
+    }
+    if let (Some(pipeline), Some(model)) = (&state.pipelines.grid_line_pipeline, &state.line_model) {
         render_pass.set_pipeline(pipeline);
-        render_pass.draw_polygons(model, &state.camera_bind_group, &state.light_bind_group);
+        render_pass.draw_wide_lines(model, &state.camera_bind_group);
     }
 }
 
 /// Render only points using quad-based rendering
 fn render_points_mode<'a>(
-    state: &'a mut State,
+    state: &'a State,
     render_pass: &mut wgpu::RenderPass<'a>,
 ) {
 
 
     // Render only points using quad-based rendering for better visuals
-    if let (Some(pipeline), Some(model)) = (&state.point_pipeline, &state.quad_point_model) {
-        render_pass.set_pipeline(pipeline);
-        render_pass.draw_quad_points(model, &state.camera_bind_group);
+    if let Some(model) = &state.quad_point_model {
+        if let Some(pipeline) = point_pipeline(state, model) {
+            render_pass.set_pipeline(pipeline);
+            render_pass.draw_quad_points(model, &state.camera_bind_group);
+        }
     }
 }
 
 /// Render lines as 3D pipes
 fn render_lines_mode(
-    state: &mut State,
+    state: &State,
     render_pass: &mut wgpu::RenderPass,
 ) {
 
     
     // Render 3D pipe lines instead of regular lines
-    if let (Some(pipeline), Some(model)) = (&state.pipe_pipeline, &state.pipe_model) {
-        render_pass.set_pipeline(pipeline);
-        render_pass.draw_pipes(model, &state.camera_bind_group);
-    }
+    render_pipes(state, render_pass);
     // Regular line rendering for grid lines to be visible by default
-    if let (Some(pipeline), Some(model)) = (&state.line_pipeline, &state.line_model) {
-        render_pass.set_pipeline(pipeline);
-        
-        // Use direct drawing approach to avoid trait issues
-        render_pass.set_vertex_buffer(0, model.vertex_buffer.slice(..));
-        render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
-        render_pass.draw(0..model.num_vertices, 0..1);
-    }
+    render_grid(state, render_pass);
+    // Render lines loaded from JSON at their requested screen-space width
+    render_wide_line_models(state, render_pass);
 }
 
 /// Render regular lines without 3D pipes
 fn render_regular_lines_mode(
-    state: &mut State,
+    state: &State,
     render_pass: &mut wgpu::RenderPass,
 ) {
     // Render regular lines without 3D pipes
-    if let (Some(pipeline), Some(model)) = (&state.line_pipeline, &state.line_model) {
-        render_pass.set_pipeline(pipeline);
-        
-        // Use the correct type - model_line::LineModel is expected by draw_lines
-        // Draw the model without using draw_lines trait which has type mismatch
-        render_pass.set_vertex_buffer(0, model.vertex_buffer.slice(..));
-        render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
-        render_pass.draw(0..model.num_vertices, 0..1);
-    }
+    render_grid(state, render_pass);
+    // Render lines loaded from JSON at their requested screen-space width
+    render_wide_line_models(state, render_pass);
 }
 
 /// Render only polygons
 fn render_polygons_mode(
-    state: &mut State,
+    state: &State,
     render_pass: &mut wgpu::RenderPass,
 ) {
 
     
-    // Render the polygon model
-    if let (Some(pipeline), Some(model)) = (&state.polygon_pipeline, &state.polygon_model) {
+    // Render the polygon sets
+    render_polygon_sets(state, render_pass);
+}
+
+/// Draw every polygon set with the lit or unlit pipeline it asks for
+fn render_polygon_sets(
+    state: &State,
+    render_pass: &mut wgpu::RenderPass,
+) {
+    for model in polygon_sets(state) {
+        draw_polygon_set(state, model, !depth_write(state), render_pass);
+    }
+}
+
+/// The sample polygon model followed by the polygon sets loaded from JSON
+fn polygon_sets<'a>(state: &'a State) -> impl Iterator<Item = &'a PolygonModel> {
+    state.polygon_model.iter().chain(&state.additional_polygon_models)
+}
+
+/// Draw a polygon set with its lit or unlit pipeline, without depth writes when `transparent`
+fn draw_polygon_set(
+    state: &State,
+    model: &PolygonModel,
+    transparent: bool,
+    render_pass: &mut wgpu::RenderPass,
+) {
+    let pipeline = match (model.lit, transparent) {
+        (true, false) => &state.pipelines.polygon_pipeline,
+        (false, false) => &state.pipelines.polygon_unlit_pipeline,
+        (true, true) => &state.pipelines.transparent_polygon_pipeline,
+        (false, true) => &state.pipelines.transparent_polygon_unlit_pipeline,
+    };
+    if let Some(pipeline) = pipeline {
         render_pass.set_pipeline(pipeline);
         render_pass.draw_polygons(model, &state.camera_bind_group, &state.light_bind_group);
     }
@@ -271,39 +999,23 @@ fn render_polygons_mode(
 
 /// Render only meshes with lighting
 fn render_meshes_mode<'a>(
-    state: &'a mut State,
+    state: &'a State,
     render_pass: &mut wgpu::RenderPass<'a>,
 ) {
 
 
     // Render the light and mesh models
     render_pass.set_vertex_buffer(1, state.instance_buffer.slice(..));
-    render_pass.set_pipeline(&state.light_render_pipeline);
     
     // Draw the main mesh model light
-    render_pass.draw_light_model(
-        &state.obj_model,
-        &state.camera_bind_group,
-        &state.light_bind_group,
-    );
-    
-    // Draw the main mesh model with edge visualization
-    render_pass.set_pipeline(&state.render_pipeline);
-    render_pass.draw_model_with_edges_instanced(
-        &state.obj_model,
-        0..state.instances.len() as u32,
-        &state.camera_bind_group,
-        &state.light_bind_group,
-    );
+    render_light_model(state, render_pass);
     
-    // Draw all additional mesh models with edge visualization
-    for mesh_model in &state.additional_mesh_models {
-        // Draw each mesh model with instancing and edge visualization
-        render_pass.draw_model_with_edges_instanced(
-            mesh_model,
-            0..state.instances.len() as u32,
-            &state.camera_bind_group,
-            &state.light_bind_group,
-        );
-    }
+    // Draw the main and additional mesh models with instancing and edge visualization
+    render_pass.set_pipeline(mesh_pipeline(state));
+    draw_mesh_models(state, render_pass);
+    crate::lib_outline::render_outlines(state, visible_mesh_models(state), render_pass);
+    render_mesh_wireframes(state, render_pass);
+    render_hidden_lines(state, render_pass);
+
+    render_transparent_meshes(state, render_pass);
 }
@@ -0,0 +1,209 @@
+//! # Scene Module
+//!
+//! Scene description files compose several geometry JSON files into one scene:
+//!
+//! ```json
+//! {
+//!   "files": [
+//!     { "path": "building.json" },
+//!     { "path": "site.json", "transform": { "translation": [10, 0, 0], "rotation": [0, 0, 90], "scale": 2 } },
+//!     { "path": "scaffolding.json", "visible": false }
+//!   ]
+//! }
+//! ```
+//!
+//! Paths are relative to the scene file, which may also be an `http(s)://` URL. Every
+//! file is parsed once and kept on the CPU; the visible entries are merged and uploaded
+//! together, so toggling an entry rebuilds the GPU models from memory instead of reading
+//! the files again.
+
+use crate::geometry_loader::{self, GeometryData};
+use crate::lib_geometry_manager;
+use crate::State;
+use cgmath::{Deg, Matrix4, Vector3};
+use serde::{Deserialize, Serialize};
+
+/// Contents of a scene description file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SceneDescription {
+    pub files: Vec<SceneFileEntry>,
+}
+
+/// One geometry file of a scene
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SceneFileEntry {
+    pub path: String, // Relative to the scene file unless absolute
+    pub name: Option<String>, // Defaults to the path
+    #[serde(default)]
+    pub transform: SceneTransform,
+    pub visible: Option<bool>, // Defaults to visible
+}
+
+/// Placement of a file in the scene: scaled, then rotated, then translated
+///
+/// Files recentered for large coordinates are transformed about their recentered origin.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct SceneTransform {
+    pub translation: Option<[f32; 3]>,
+    pub rotation: Option<[f32; 3]>, // Euler angles in degrees about X, then Y, then Z, as `import_rotation`
+    pub scale: Option<f32>,         // Uniform scale
+}
+
+impl SceneTransform {
+    /// The transform as a matrix applied to column vectors
+    pub fn matrix(&self) -> Matrix4<f32> {
+        let [tx, ty, tz] = self.translation.unwrap_or([0.0; 3]);
+        let [rx, ry, rz] = self.rotation.unwrap_or([0.0; 3]);
+        Matrix4::from_translation(Vector3::new(tx, ty, tz))
+            * Matrix4::from_angle_z(Deg(rz))
+            * Matrix4::from_angle_y(Deg(ry))
+            * Matrix4::from_angle_x(Deg(rx))
+            * Matrix4::from_scale(self.scale.unwrap_or(1.0))
+    }
+}
+
+/// A loaded scene file entry with its transformed geometry
+pub struct SceneEntry {
+    pub name: String,
+    pub path: String,
+    pub visible: bool,
+    geometry: GeometryData,
+}
+
+/// The scene loaded with `load_scene`
+pub struct Scene {
+    pub path: String,
+    pub entries: Vec<SceneEntry>,
+    origin_offset: [f64; 3], // Shared by all entries, taken from the first recentered file
+}
+
+/// Resolve a path from a scene file against the directory (or URL) of the scene file
+fn resolve_path(scene_path: &str, path: &str) -> String {
+    if geometry_loader::is_url(scene_path) && !geometry_loader::is_url(path) {
+        if let Ok(url) = reqwest::Url::parse(scene_path).and_then(|base| base.join(path)) {
+            return url.into();
+        }
+    }
+    let path = std::path::Path::new(path);
+    match std::path::Path::new(scene_path).parent() {
+        Some(directory) if path.is_relative() => directory.join(path).to_string_lossy().into_owned(),
+        _ => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// Load a scene description file and every geometry file it lists, replacing the loaded geometry
+pub async fn load_scene(state: &mut State<'_>, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Loading scene from file: {}", path);
+    let text = geometry_loader::read_text_file(path).await?;
+    let description: SceneDescription =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid scene file {}: {}", path, e))?;
+
+    let mut entries = Vec::new();
+    for file in &description.files {
+        let file_path = resolve_path(path, &file.path);
+        let mut geometry = geometry_loader::load_geometry_file(&file_path)
+            .await
+            .map_err(|e| format!("Failed to load scene file {}: {}", file_path, e))?;
+        geometry.transform(file.transform.matrix());
+        entries.push(SceneEntry {
+            name: file.name.clone().unwrap_or_else(|| file.path.clone()),
+            path: file_path,
+            visible: file.visible.unwrap_or(true),
+            geometry,
+        });
+    }
+
+    // Files recentered on load are moved next to the first recentered one; nearby
+    // files differ by little enough to stay precise in f32
+    let origin_offset = entries
+        .iter()
+        .map(|entry| entry.geometry.origin_offset)
+        .find(|offset| *offset != [0.0; 3])
+        .unwrap_or([0.0; 3]);
+    for geometry in entries.iter_mut().map(|entry| &mut entry.geometry) {
+        let shift: [f64; 3] = std::array::from_fn(|i| geometry.origin_offset[i] - origin_offset[i]);
+        if shift != [0.0; 3] {
+            geometry.transform(Matrix4::from_translation(Vector3::new(shift[0] as f32, shift[1] as f32, shift[2] as f32)));
+            geometry.origin_offset = origin_offset;
+        }
+    }
+
+    state.scene = Some(Scene {
+        path: path.to_string(),
+        entries,
+        origin_offset,
+    });
+    state.geometry_path = None;
+    apply_scene(state)
+}
+
+/// Show or hide one entry of the loaded scene
+pub fn set_scene_entry_visible(state: &mut State<'_>, index: usize, visible: bool) -> Result<(), String> {
+    let scene = state.scene.as_mut().ok_or("No scene has been loaded")?;
+    let count = scene.entries.len();
+    let entry = scene
+        .entries
+        .get_mut(index)
+        .ok_or_else(|| format!("Scene entry {} does not exist ({} entries)", index, count))?;
+    if entry.visible != visible {
+        entry.visible = visible;
+        apply_scene(state).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Merge the visible scene entries and upload them in place of the loaded geometry
+pub(crate) fn apply_scene(state: &mut State<'_>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(scene) = &state.scene else {
+        return Ok(());
+    };
+    let mut merged = GeometryData {
+        metadata: geometry_loader::Metadata {
+            version: String::from("1.0"),
+            description: scene.path.clone(),
+            created: String::new(),
+            import_rotation: None,
+            recenter: None,
+            color_range: Some(geometry_loader::ColorRange::Float),
+        },
+        meshes: Some(Vec::new()),
+        points: Some(Vec::new()),
+        lines: Some(Vec::new()),
+        pipes: Some(Vec::new()),
+        polygons: Some(Vec::new()),
+        origin_offset: scene.origin_offset,
+    };
+    for entry in scene.entries.iter().filter(|entry| entry.visible) {
+        merge_geometry(&mut merged, entry.geometry.clone());
+    }
+
+    lib_geometry_manager::clear_loaded_geometry(state);
+    lib_geometry_manager::apply_geometry_data(state, &merged)
+}
+
+/// Append `other` to `merged`
+///
+/// Only one point cloud and one pipe set are drawn, so their sets are joined into the first one.
+fn merge_geometry(merged: &mut GeometryData, other: GeometryData) {
+    fn append<T>(target: &mut Option<Vec<T>>, items: Option<Vec<T>>) {
+        target.get_or_insert_with(Vec::new).extend(items.into_iter().flatten());
+    }
+    append(&mut merged.meshes, other.meshes);
+    append(&mut merged.lines, other.lines);
+    append(&mut merged.polygons, other.polygons);
+
+    let points = merged.points.get_or_insert_with(Vec::new);
+    for point_set in other.points.into_iter().flatten() {
+        match points.first_mut() {
+            Some(first) => first.vertices.extend(point_set.vertices),
+            None => points.push(point_set),
+        }
+    }
+    let pipes = merged.pipes.get_or_insert_with(Vec::new);
+    for pipe_set in other.pipes.into_iter().flatten() {
+        match pipes.first_mut() {
+            Some(first) => first.segments.extend(pipe_set.segments),
+            None => pipes.push(pipe_set),
+        }
+    }
+}
@@ -0,0 +1,515 @@
+//! # Snapshot Module
+//!
+//! Offscreen "render once and exit" support for golden-image tests in CI.
+//! `--snapshot <out.png>` renders a single frame at a fixed, configurable camera
+//! pose into an offscreen texture and writes it as a PNG.
+//!
+//! Supported flags (native only):
+//! - `--snapshot <out.png>`: output file, enables snapshot mode
+//! - `--snapshot-size <W>x<H>`: image size in pixels (default 1920x1080)
+//! - `--snapshot-supersample <N>`: render at N times the size and box filter
+//!   down, N in 1..=4 (default 1)
+//! - `--camera-eye <x>,<y>,<z>`: camera position (default 0,10,10)
+//! - `--camera-target <x>,<y>,<z>`: camera target (default 0,0,0)
+//! - `--view <view.json>`: camera and lights from a view file saved with
+//!   `State::export_view`, replacing `--camera-eye`/`--camera-target`
+//!
+//! `--turntable <out_dir>` instead orbits the camera a full turn around the scene
+//! center and writes numbered PNG frames, e.g. for product-spin GIFs:
+//! - `--turntable-frames <N>`: number of frames (default 36)
+//! - `--turntable-axis <x>,<y>,<z>`: rotation axis (default 0,0,1, the world up)
+//! - `--snapshot-size <W>x<H>`: frame size in pixels (default 1920x1080)
+//! - `--snapshot-supersample <N>`: supersampling factor as for snapshots
+//! - `--view <view.json>`: start from a saved view
+
+use crate::camera;
+use crate::lib_render;
+use crate::lib_view;
+use crate::State;
+use cgmath::{Deg, InnerSpace, Point3, Quaternion, Rotation3, Vector3};
+
+/// Settings for a single offscreen snapshot
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub supersample: u32, // Supersampling factor, 1 renders directly
+    pub camera_eye: [f32; 3],
+    pub camera_target: [f32; 3],
+    pub view_path: Option<String>, // Saved view file, used instead of the eye/target pose
+}
+
+impl SnapshotOptions {
+    /// Create snapshot options with the default size and startup camera pose
+    pub fn new(output_path: &str) -> Self {
+        Self {
+            output_path: output_path.to_string(),
+            width: 1920,
+            height: 1080,
+            supersample: 1,
+            camera_eye: [0.0, 10.0, 10.0],
+            camera_target: [0.0, 0.0, 0.0],
+            view_path: None,
+        }
+    }
+
+    /// Parse snapshot flags from command line arguments
+    ///
+    /// Returns `Ok(None)` when `--snapshot` is not present.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Self>, String> {
+        let args: Vec<String> = args.into_iter().collect();
+        let value_of = |flag: &str| flag_value(&args, flag);
+
+        let mut options = match value_of("--snapshot")? {
+            Some(path) => Self::new(path),
+            None => return Ok(None),
+        };
+
+        if let Some(size) = value_of("--snapshot-size")? {
+            (options.width, options.height) = parse_size(size)?;
+        }
+        if let Some(factor) = value_of("--snapshot-supersample")? {
+            options.supersample = parse_supersample(factor)?;
+        }
+        if let Some(eye) = value_of("--camera-eye")? {
+            options.camera_eye = parse_vec3(eye).ok_or_else(|| format!("Invalid --camera-eye '{}', expected x,y,z", eye))?;
+        }
+        if let Some(target) = value_of("--camera-target")? {
+            options.camera_target = parse_vec3(target).ok_or_else(|| format!("Invalid --camera-target '{}', expected x,y,z", target))?;
+        }
+        options.view_path = value_of("--view")?.cloned();
+
+        Ok(Some(options))
+    }
+}
+
+/// Settings for a 360 degree turntable capture
+#[derive(Debug, Clone)]
+pub struct TurntableOptions {
+    pub output_dir: String,
+    pub frames: u32,
+    pub width: u32,
+    pub height: u32,
+    pub supersample: u32, // Supersampling factor, 1 renders directly
+    pub axis: [f32; 3], // Rotation axis through the scene center, world up (Z) by default
+    pub view_path: Option<String>, // Saved view file to start from
+}
+
+impl TurntableOptions {
+    /// Create turntable options with the default size and the world up axis
+    pub fn new(frames: u32, output_dir: &str) -> Self {
+        Self {
+            output_dir: output_dir.to_string(),
+            frames,
+            width: 1920,
+            height: 1080,
+            supersample: 1,
+            axis: [0.0, 0.0, 1.0],
+            view_path: None,
+        }
+    }
+
+    /// Parse turntable flags from command line arguments
+    ///
+    /// Returns `Ok(None)` when `--turntable` is not present.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Self>, String> {
+        let args: Vec<String> = args.into_iter().collect();
+        let value_of = |flag: &str| flag_value(&args, flag);
+
+        let mut options = match value_of("--turntable")? {
+            Some(dir) => Self::new(DEFAULT_TURNTABLE_FRAMES, dir),
+            None => return Ok(None),
+        };
+
+        if let Some(frames) = value_of("--turntable-frames")? {
+            options.frames = frames
+                .parse()
+                .ok()
+                .filter(|&frames: &u32| frames > 0)
+                .ok_or_else(|| format!("Invalid --turntable-frames '{}', expected a positive integer", frames))?;
+        }
+        if let Some(axis) = value_of("--turntable-axis")? {
+            options.axis = parse_vec3(axis).ok_or_else(|| format!("Invalid --turntable-axis '{}', expected x,y,z", axis))?;
+        }
+        if let Some(size) = value_of("--snapshot-size")? {
+            (options.width, options.height) = parse_size(size)?;
+        }
+        if let Some(factor) = value_of("--snapshot-supersample")? {
+            options.supersample = parse_supersample(factor)?;
+        }
+        options.view_path = value_of("--view")?.cloned();
+
+        Ok(Some(options))
+    }
+}
+
+/// Frames in a turntable capture when `--turntable-frames` is not given (10 degrees per frame)
+const DEFAULT_TURNTABLE_FRAMES: u32 = 36;
+
+/// The value following `flag`, or `None` when the flag is absent
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a String>, String> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(index) => args
+            .get(index + 1)
+            .map(Some)
+            .ok_or_else(|| format!("Missing value for {}", flag)),
+        None => Ok(None),
+    }
+}
+
+/// Parse a `--snapshot-supersample` factor
+fn parse_supersample(factor: &str) -> Result<u32, String> {
+    factor
+        .parse()
+        .ok()
+        .filter(|factor| (1..=MAX_SUPERSAMPLING).contains(factor))
+        .ok_or_else(|| format!("Invalid --snapshot-supersample '{}', expected 1 to {}", factor, MAX_SUPERSAMPLING))
+}
+
+/// Parse a `<W>x<H>` image size
+fn parse_size(size: &str) -> Result<(u32, u32), String> {
+    size.split_once('x')
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+        .filter(|&(w, h): &(u32, u32)| w > 0 && h > 0)
+        .ok_or_else(|| format!("Invalid --snapshot-size '{}', expected <W>x<H>", size))
+}
+
+/// Parse a comma separated `x,y,z` triple
+fn parse_vec3(text: &str) -> Option<[f32; 3]> {
+    let values: Vec<f32> = text.split(',').map(|v| v.trim().parse().ok()).collect::<Option<_>>()?;
+    match values.as_slice() {
+        [x, y, z] => Some([*x, *y, *z]),
+        _ => None,
+    }
+}
+
+/// Place the camera at the snapshot pose (or saved view) and render one frame to the output PNG
+pub fn capture_snapshot(state: &mut State, options: &SnapshotOptions) -> Result<(), Box<dyn std::error::Error>> {
+    match &options.view_path {
+        Some(path) => lib_view::import_view(state, path)?,
+        None => state.camera = camera::Camera::new(options.camera_eye, options.camera_target.into()),
+    }
+
+    render_to_image_ss(state, options.width, options.height, options.supersample, &options.output_path)
+}
+
+/// Orbit the camera a full turn around the scene and write one numbered PNG per frame
+///
+/// The pivot is the center of the loaded geometry bounds (the camera target when
+/// nothing is loaded) and the camera keeps its current distance and elevation.
+/// Frames are written as `frame_0000.png`, `frame_0001.png`, ... and the camera is
+/// restored afterwards.
+pub fn capture_turntable(state: &mut State, options: &TurntableOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if options.frames == 0 {
+        return Err("A turntable capture needs at least one frame".into());
+    }
+    let axis = Vector3::from(options.axis);
+    if axis.magnitude2() <= f32::EPSILON {
+        return Err(format!("Invalid turntable axis {:?}", options.axis).into());
+    }
+    let axis = axis.normalize();
+    if let Some(path) = &options.view_path {
+        lib_view::import_view(state, path)?;
+    }
+
+    let pivot = match state.scene_bounds {
+        Some((min, max)) => Point3::new(
+            (min[0] + max[0]) * 0.5,
+            (min[1] + max[1]) * 0.5,
+            (min[2] + max[2]) * 0.5,
+        ),
+        None => state.camera.target,
+    };
+
+    std::fs::create_dir_all(&options.output_dir)?;
+    let start_camera = state.camera.clone();
+    let result = (0..options.frames).try_for_each(|frame| {
+        let angle = Deg(360.0 * frame as f32 / options.frames as f32);
+        let mut camera = start_camera.clone();
+        camera.target = pivot;
+        camera.orientation = Quaternion::from_axis_angle(axis, angle) * start_camera.orientation;
+        camera.update_position();
+        state.camera = camera;
+
+        let path = std::path::Path::new(&options.output_dir).join(format!("frame_{:04}.png", frame));
+        render_to_image_ss(state, options.width, options.height, options.supersample, &path.to_string_lossy())
+    });
+    state.camera = start_camera;
+    result?;
+
+    log::info!("Turntable written to {} ({} frames)", options.output_dir, options.frames);
+    Ok(())
+}
+
+/// Largest supported supersampling factor of `render_to_image_ss`
+pub const MAX_SUPERSAMPLING: u32 = 4;
+
+/// Render the current scene offscreen at `width` x `height` and save it as a PNG
+///
+/// The light is not advanced and no input is processed, so the same scene and
+/// camera always produce the same image.
+pub fn render_to_image(state: &mut State, width: u32, height: u32, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    render_to_image_ss(state, width, height, 1, path)
+}
+
+/// Render at `ss_factor` times `width` x `height`, box filter down to that size and save it as a PNG
+///
+/// Gives cleaner edges than MSAA alone, e.g. for documentation images. Line widths and
+/// other sizes in pixels refer to the final image. `ss_factor` 1 renders directly.
+pub fn render_to_image_ss(
+    state: &mut State,
+    width: u32,
+    height: u32,
+    ss_factor: u32,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !(1..=MAX_SUPERSAMPLING).contains(&ss_factor) {
+        return Err(format!("Supersampling factor {} is not in 1..={}", ss_factor, MAX_SUPERSAMPLING).into());
+    }
+    let (render_width, render_height) = (width * ss_factor, height * ss_factor);
+    let max_dimension = state.device.limits().max_texture_dimension_2d;
+    if render_width > max_dimension || render_height > max_dimension {
+        return Err(format!(
+            "{}x{} at {}x supersampling exceeds the maximum texture size {}",
+            width, height, ss_factor, max_dimension
+        )
+        .into());
+    }
+
+    let pixels = render_pixels(state, render_width, render_height, ss_factor)?;
+    let pixels = downsample(&pixels, render_width, render_height, ss_factor);
+
+    image::save_buffer(path, &pixels, width, height, image::ColorType::Rgba8)?;
+    log::info!("Snapshot written to {} ({}x{}, {}x supersampling)", path, width, height, ss_factor);
+    Ok(())
+}
+
+/// Average every `factor` x `factor` block of RGBA8 pixels (sRGB encoded) into one pixel
+///
+/// The colors are averaged in linear space so edges blend as they would on screen.
+fn downsample(pixels: &[u8], width: u32, height: u32, factor: u32) -> Vec<u8> {
+    if factor == 1 {
+        return pixels.to_vec();
+    }
+    let to_linear: Vec<f32> = (0..=255u8)
+        .map(|value| {
+            let c = value as f32 / 255.0;
+            if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        })
+        .collect();
+    let to_srgb = |c: f32| {
+        let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+        (c.clamp(0.0, 1.0) * 255.0).round() as u8
+    };
+
+    let (out_width, out_height) = (width / factor, height / factor);
+    let samples = (factor * factor) as f32;
+    let mut output = Vec::with_capacity((out_width * out_height * 4) as usize);
+    for y in 0..out_height {
+        for x in 0..out_width {
+            let mut sum = [0.0f32; 4];
+            for sy in 0..factor {
+                let row = ((y * factor + sy) * width + x * factor) as usize * 4;
+                for pixel in pixels[row..row + factor as usize * 4].chunks_exact(4) {
+                    for channel in 0..3 {
+                        sum[channel] += to_linear[pixel[channel] as usize];
+                    }
+                    sum[3] += pixel[3] as f32;
+                }
+            }
+            output.extend_from_slice(&[
+                to_srgb(sum[0] / samples),
+                to_srgb(sum[1] / samples),
+                to_srgb(sum[2] / samples),
+                (sum[3] / samples).round() as u8,
+            ]);
+        }
+    }
+    output
+}
+
+/// Render one frame offscreen at `width` x `height` and read it back as RGBA8 rows
+///
+/// Sizes given in pixels, e.g. wide lines, are multiplied by `pixel_scale`, the
+/// supersampling factor, so they refer to the downsampled image.
+fn render_pixels(
+    state: &mut State,
+    width: u32,
+    height: u32,
+    pixel_scale: u32,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let format = state.config.format;
+    let swap_red_blue = match format {
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        other => return Err(format!("Snapshots do not support surface format {:?}", other).into()),
+    };
+
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+
+    // Offscreen targets matching the surface format so the existing pipelines can be reused
+    let target_texture = state.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("snapshot_texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let target_view = target_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let multisample_texture = state.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("snapshot_multisample_texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 4, // Must match the 4x MSAA pipelines
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let multisample_view = multisample_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let multisample_depth_texture = state.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("snapshot_multisample_depth_texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 4,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Depth32Float,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING, // Read by the depth overlay
+        view_formats: &[],
+    });
+    let multisample_depth_view = multisample_depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    // Match projection and uniforms to the snapshot size
+    state.projection.resize(width, height);
+    state.camera_uniform.update_aspect_ratio(width as f32, height as f32);
+    state.camera_uniform.update_viewport_origin(0.0, 0.0);
+    state.camera_uniform.update_pixel_scale(pixel_scale as f32);
+    state.camera_uniform.update_view_proj(&state.camera, &state.projection);
+    state.camera_uniform.update_clip_plane(state.settings.clip_plane);
+    state.queue.write_buffer(&state.camera_buffer, 0, bytemuck::cast_slice(&[state.camera_uniform]));
+
+    let mut encoder = state.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Snapshot Encoder"),
+    });
+    lib_render::encode_scene(state, &mut encoder, &multisample_view, &multisample_depth_view, &target_view);
+
+    // Rows in the readback buffer must be padded to COPY_BYTES_PER_ROW_ALIGNMENT
+    let unpadded_bytes_per_row = width * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+
+    let output_buffer = state.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Snapshot Readback Buffer"),
+        size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &target_texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &output_buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        size,
+    );
+    state.queue.submit(std::iter::once(encoder.finish()));
+
+    // Wait for the GPU and read the pixels back
+    let buffer_slice = output_buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    state.device.poll(wgpu::Maintain::Wait);
+    receiver.recv()??;
+
+    let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+    {
+        let data = buffer_slice.get_mapped_range();
+        for row in data.chunks(padded_bytes_per_row as usize) {
+            pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+    }
+    output_buffer.unmap();
+
+    if swap_red_blue {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+
+    // Restore the viewport-sized projection for any further on-screen rendering
+    crate::lib_split_view::resize_projections(state);
+    state.camera_uniform.update_aspect_ratio(state.config.width as f32, state.config.height as f32);
+    state.camera_uniform.update_pixel_scale(1.0);
+
+    Ok(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn snapshot_flags_are_parsed() {
+        assert!(SnapshotOptions::from_args(args("viewer --view saved.json")).unwrap().is_none());
+
+        let options = SnapshotOptions::from_args(args(
+            "viewer --snapshot out.png --snapshot-size 640x480 --snapshot-supersample 2 --camera-eye 1,2,3 --camera-target 0,0,0.5",
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(options.output_path, "out.png");
+        assert_eq!((options.width, options.height, options.supersample), (640, 480, 2));
+        assert_eq!(options.camera_eye, [1.0, 2.0, 3.0]);
+        assert_eq!(options.camera_target, [0.0, 0.0, 0.5]);
+        assert_eq!(options.view_path, None);
+
+        let defaults = SnapshotOptions::from_args(args("viewer --snapshot out.png")).unwrap().unwrap();
+        assert_eq!((defaults.width, defaults.height, defaults.supersample), (1920, 1080, 1));
+    }
+
+    #[test]
+    fn invalid_snapshot_flags_are_rejected() {
+        for text in [
+            "viewer --snapshot",
+            "viewer --snapshot out.png --snapshot-size 640",
+            "viewer --snapshot out.png --snapshot-supersample 5",
+            "viewer --snapshot out.png --camera-eye 1,2",
+        ] {
+            assert!(SnapshotOptions::from_args(args(text)).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn sizes_need_two_positive_integers() {
+        assert_eq!(parse_size("1920x1080"), Ok((1920, 1080)));
+        for size in ["1920", "0x1080", "1920x", "-1x10", "axb", "10x10x10"] {
+            assert!(parse_size(size).is_err(), "{}", size);
+        }
+    }
+}
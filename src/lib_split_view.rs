@@ -0,0 +1,162 @@
+//! # Split View Module
+//!
+//! Two viewports side by side, each with its own camera, to compare two angles of the
+//! same scene. The scene is encoded once per viewport, each time into its half of the
+//! window (`set_viewport`/`set_scissor_rect`) with the camera uniform of that half.
+//!
+//! The focused viewport, the one under the cursor, always drives `State::camera` and
+//! `State::projection`, so camera input, fitting and picking work unchanged; the other
+//! viewport's camera waits in `SplitView`. Moving the cursor into the other half swaps
+//! the two (never during a drag).
+
+use crate::camera::{Camera, Projection};
+use crate::lib_render::{encode_scene_in_viewport, CameraUniform, ViewportPass};
+use crate::State;
+
+/// Left or right half of the window
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViewportSide {
+    Left,
+    Right,
+}
+
+/// The camera of the viewport without focus and which side has it
+pub struct SplitView {
+    pub focused: ViewportSide,
+    pub other_camera: Camera,
+    pub other_projection: Projection,
+}
+
+/// One viewport of the frame: pixel rectangle (x, y, width, height) and its camera
+pub struct Viewport<'a> {
+    pub rect: [u32; 4],
+    pub camera: &'a Camera,
+    pub projection: &'a Projection,
+}
+
+/// Pixel rectangle of one side, or of the whole window
+fn side_rect(state: &State, side: Option<ViewportSide>) -> [u32; 4] {
+    let (width, height) = (state.config.width, state.config.height);
+    let half = width / 2;
+    match side {
+        None => [0, 0, width, height],
+        Some(ViewportSide::Left) => [0, 0, half.max(1), height],
+        Some(ViewportSide::Right) => [half, 0, (width - half).max(1), height],
+    }
+}
+
+/// The viewports to draw this frame, left to right; one covering the window without split view
+pub fn viewports<'a>(state: &'a State) -> Vec<Viewport<'a>> {
+    let Some(split) = &state.split_view else {
+        return vec![Viewport { rect: side_rect(state, None), camera: &state.camera, projection: &state.projection }];
+    };
+    let focused = Viewport { rect: side_rect(state, Some(split.focused)), camera: &state.camera, projection: &state.projection };
+    let other_side = match split.focused {
+        ViewportSide::Left => ViewportSide::Right,
+        ViewportSide::Right => ViewportSide::Left,
+    };
+    let other = Viewport { rect: side_rect(state, Some(other_side)), camera: &split.other_camera, projection: &split.other_projection };
+    match split.focused {
+        ViewportSide::Left => vec![focused, other],
+        ViewportSide::Right => vec![other, focused],
+    }
+}
+
+/// Pixel rectangle of the viewport drawn with `State::camera`
+pub fn focused_rect(state: &State) -> [u32; 4] {
+    side_rect(state, state.split_view.as_ref().map(|split| split.focused))
+}
+
+/// Turn split view on (both halves start from the current camera) or off (the left camera stays)
+pub fn set_split_view(state: &mut State, enabled: bool) {
+    if enabled == state.split_view.is_some() {
+        return;
+    }
+    if enabled {
+        state.split_view = Some(SplitView {
+            focused: ViewportSide::Left,
+            other_camera: state.camera.clone(),
+            other_projection: state.projection.clone(),
+        });
+    } else if let Some(mut split) = state.split_view.take() {
+        if split.focused == ViewportSide::Right {
+            std::mem::swap(&mut state.camera, &mut split.other_camera);
+            std::mem::swap(&mut state.projection, &mut split.other_projection);
+        }
+    }
+    resize_projections(state);
+}
+
+/// Match the projections to the viewport sizes, after a resize or a split view change
+pub fn resize_projections(state: &mut State) {
+    let [_, _, width, height] = focused_rect(state);
+    state.projection.resize(width, height);
+    if let Some(split) = &mut state.split_view {
+        // Both halves differ by at most one pixel
+        split.other_projection.resize(width, height);
+    }
+}
+
+/// Give the focus (and the camera input) to the viewport under the cursor
+///
+/// Not while a mouse button is held, so a drag that crosses the middle keeps its camera.
+pub fn update_focus(state: &mut State, cursor: [f32; 2]) {
+    if state.mouse_pressed {
+        return;
+    }
+    let half = (state.config.width / 2) as f32;
+    let Some(split) = &mut state.split_view else {
+        return;
+    };
+    let side = if cursor[0] < half { ViewportSide::Left } else { ViewportSide::Right };
+    if side != split.focused {
+        std::mem::swap(&mut state.camera, &mut split.other_camera);
+        std::mem::swap(&mut state.projection, &mut split.other_projection);
+        split.focused = side;
+    }
+}
+
+/// Draw the frame as one scene pass per viewport, each with the camera uniform of its viewport
+///
+/// Each pass is submitted before the camera buffer is rewritten for the next viewport.
+pub fn render_split_view(
+    state: &mut State,
+    multisample_view: &wgpu::TextureView,
+    multisample_depth_view: &wgpu::TextureView,
+    resolve_view: &wgpu::TextureView,
+) {
+    let focused = focused_rect(state);
+    let passes: Vec<(ViewportPass, CameraUniform)> = viewports(state)
+        .iter()
+        .enumerate()
+        .map(|(index, viewport)| {
+            let [x, y, width, height] = viewport.rect;
+            let mut uniform = state.camera_uniform;
+            uniform.update_view_proj(viewport.camera, viewport.projection);
+            uniform.update_aspect_ratio(width as f32, height as f32);
+            uniform.update_viewport_origin(x as f32, y as f32);
+            let pass = ViewportPass { rect: viewport.rect, clear: index == 0, help_panel: viewport.rect == focused };
+            (pass, uniform)
+        })
+        .collect();
+
+    let full_window_uniform = state.camera_uniform;
+    for (pass, uniform) in &passes {
+        state.camera_uniform = *uniform;
+        state.queue.write_buffer(&state.camera_buffer, 0, bytemuck::cast_slice(&[*uniform]));
+        let mut encoder = state.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Split View Encoder"),
+        });
+        encode_scene_in_viewport(state, &mut encoder, multisample_view, multisample_depth_view, resolve_view, Some(pass));
+        state.queue.submit(std::iter::once(encoder.finish()));
+    }
+    state.camera_uniform = full_window_uniform;
+    state.queue.write_buffer(&state.camera_buffer, 0, bytemuck::cast_slice(&[full_window_uniform]));
+
+    // The depth view covers the whole window, normalized with the focused camera's clip range
+    let mut encoder = state.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Split View Depth Overlay Encoder"),
+    });
+    crate::lib_depth_overlay::render_depth_overlay(state, &mut encoder, multisample_depth_view, resolve_view);
+    state.queue.submit(std::iter::once(encoder.finish()));
+}
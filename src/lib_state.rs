@@ -6,13 +6,197 @@ use crate::model_pipe;
 use crate::model_point;
 use crate::model_polygon;
 use crate::lib_pipeline;
-use crate::lib_render::{CameraUniform, LightUniform};
+use crate::lib_debug_draw;
+use crate::lib_labels;
+use crate::lib_legend;
+use crate::lib_backplate;
+use crate::lib_background;
+use crate::lib_depth_overlay;
+use crate::lib_outline;
+use crate::lib_frame_pacing;
+use crate::lib_target_gizmo;
+use crate::lib_scene;
+use crate::lib_depth_pick;
+use crate::lib_help_overlay;
+use crate::lib_split_view;
+use crate::lib_hot_reload;
+use crate::geometry_loader;
+use crate::lib_render::{CameraUniform, GlobalsUniform, LightUniform};
 use crate::RenderMode;
 use crate::model::Vertex; // Import Vertex trait for desc() method
 use cgmath::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use wgpu::util::DeviceExt;
 use winit::window::Window;
 
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Preferred color space of the surface (swapchain) format
+///
+/// The preference falls back to whatever format the surface supports. When the
+/// chosen format is linear, the shaders gamma encode their output manually.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum SurfaceColorSpace {
+    #[default]
+    Srgb,
+    Linear,
+}
+
+/// Startup options of the viewer, see `State::new_with_config`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewerConfig {
+    pub color_space: SurfaceColorSpace,
+    /// OBJ file shown until geometry is loaded (default `cube.obj`); `None` starts with an empty model
+    pub default_model: Option<String>,
+    /// Check the open geometry file's modification time this often and reload it when it
+    /// changes, for filesystems that do not report changes reliably; `None` (default) disables
+    /// polling (native only)
+    pub reload_poll_interval: Option<std::time::Duration>,
+}
+
+impl Default for ViewerConfig {
+    fn default() -> Self {
+        Self {
+            color_space: SurfaceColorSpace::default(),
+            default_model: Some(String::from("cube.obj")),
+            reload_poll_interval: None,
+        }
+    }
+}
+
+/// What the color target holds before the scene is drawn each frame
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BackgroundClear {
+    /// Clear to a straight (not premultiplied) RGBA color; alpha below 1 needs a
+    /// compositing surface, e.g. to embed the viewer as a transparent overlay
+    Color([f32; 4]),
+    /// Keep the previous frame's contents and draw over them
+    Load,
+    /// Opaque vertical gradient from the top to the bottom of the window
+    Gradient { top: [f32; 3], bottom: [f32; 3] },
+}
+
+impl Default for BackgroundClear {
+    fn default() -> Self {
+        BackgroundClear::Color([0.9, 0.9, 0.9, 1.0])
+    }
+}
+
+/// How mesh edges are drawn for technical drawings (hidden line removal)
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum HiddenLineMode {
+    #[default]
+    Off,
+    /// Visible edges solid, occluded edges removed
+    Hidden,
+    /// Visible edges solid, occluded edges dashed
+    Dashed,
+}
+
+impl HiddenLineMode {
+    /// Next mode in the Off -> Hidden -> Dashed cycle
+    pub fn next(self) -> Self {
+        match self {
+            HiddenLineMode::Off => HiddenLineMode::Hidden,
+            HiddenLineMode::Hidden => HiddenLineMode::Dashed,
+            HiddenLineMode::Dashed => HiddenLineMode::Off,
+        }
+    }
+}
+
+/// Whether points, lines, pipes and polygons write depth, per render mode
+///
+/// All modes write depth by default. With depth writes off, nearer geometry no longer
+/// hides farther geometry drawn after it, so overlapping points or translucent
+/// surfaces composite in draw order instead of depending on which was drawn first.
+/// Meshes always write depth, and the transparent phase of the All mode never does.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DepthWritePolicy {
+    modes: [bool; 6], // Indexed by the `RenderMode` discriminant
+}
+
+impl Default for DepthWritePolicy {
+    fn default() -> Self {
+        Self { modes: [true; 6] }
+    }
+}
+
+impl DepthWritePolicy {
+    /// Whether geometry writes depth in `mode`
+    pub fn get(&self, mode: RenderMode) -> bool {
+        self.modes[mode as usize]
+    }
+
+    /// Turn depth writes on or off for `mode`
+    pub fn set(&mut self, mode: RenderMode, enabled: bool) {
+        self.modes[mode as usize] = enabled;
+    }
+}
+
+/// View options set by the user, kept when the State is recreated, e.g. after a device loss
+///
+/// Everything here is plain CPU data; the GPU resources that depend on it are rebuilt
+/// from it, e.g. the grid from `grid_height`, `grid_color_fn` and `grid_visibility`.
+pub struct DisplaySettings {
+    pub render_mode: RenderMode,
+    pub on_render_mode_changed: Option<Box<dyn Fn(RenderMode)>>, // Called once per render mode change, e.g. to sync a toolbar
+    pub hidden_line_mode: HiddenLineMode,
+    pub depth_write: DepthWritePolicy, // Depth writes of points, lines, pipes and polygons per render mode
+    pub depth_prepass: bool, // Write opaque mesh depth first; points, lines, pipes and polygons are not pre-passed
+    pub clip_plane: Option<[f32; 4]>,
+    pub auto_near_far: bool, // Fit the near/far planes to the scene every frame
+    pub zoom_to_cursor: bool, // Scroll zooms toward the surface under the cursor
+    pub background_clear: BackgroundClear,
+    pub background_theme: lib_background::BackgroundTheme, // Last preset applied, the start of the K key cycle
+    pub grid_height: f32, // Offset of the grid plane along the world up axis
+    pub grid_color_fn: Box<dyn Fn([f32; 3]) -> [f32; 3]>, // Grid vertex colors, kept to rebuild the grid
+    pub grid_visibility: crate::geometry_generator::GridVisibility, // Grid lines and axes to build
+    pub point_shape: model_point::PointShape, // Discs or sphere impostors for the point cloud
+    pub point_size_override: Option<f32>, // Point size multiplier set with `State::set_point_size`
+    pub pipe_radius_scale: f32, // Multiplier on every pipe radius, JSON and converted lines
+    pub pipe_cap_style: model_pipe::PipeCapStyle, // Flat or rounded pipe ends, JSON and converted lines
+    pub default_mesh_color: [f32; 3], // Color of loaded mesh vertices without one
+    pub normalize_normals: bool, // Rescale loaded mesh normals to unit length
+    pub light_orbit: Option<crate::lib_render::LightOrbit>, // Per-frame light animation, `None` when stopped
+    pub show_light_model: bool,   // Draw the light position indicator
+    pub show_face_orientation: bool,
+    pub show_scene_extents: bool, // Draw the scene bounds box and a crosshair at its center
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            render_mode: RenderMode::default(),
+            on_render_mode_changed: None,
+            hidden_line_mode: HiddenLineMode::default(),
+            depth_write: DepthWritePolicy::default(),
+            depth_prepass: false,
+            clip_plane: None,
+            auto_near_far: true,
+            zoom_to_cursor: true,
+            background_clear: BackgroundClear::default(),
+            background_theme: lib_background::BackgroundTheme::default(),
+            grid_height: 0.0,
+            grid_color_fn: Box::new(crate::geometry_generator::plain_grid_color),
+            grid_visibility: crate::geometry_generator::GridVisibility::default(),
+            point_shape: model_point::PointShape::default(),
+            point_size_override: None,
+            pipe_radius_scale: 1.0,
+            pipe_cap_style: model_pipe::PipeCapStyle::default(),
+            default_mesh_color: geometry_loader::DEFAULT_MESH_COLOR,
+            normalize_normals: false,
+            light_orbit: Some(crate::lib_render::LightOrbit::default()),
+            show_light_model: true,
+            show_face_orientation: false,
+            show_scene_extents: false,
+        }
+    }
+}
+
+/// Edge color of the hidden line view
+const HIDDEN_LINE_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 1.0];
+
 /// State struct for the application
 #[allow(dead_code)]
 pub struct State<'a> {
@@ -21,24 +205,41 @@ pub struct State<'a> {
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
     pub config: wgpu::SurfaceConfiguration,
-    pub render_pipeline: wgpu::RenderPipeline,
-    pub point_pipeline: Option<wgpu::RenderPipeline>,
-    pub line_pipeline: Option<wgpu::RenderPipeline>,
-    pub pipe_pipeline: Option<wgpu::RenderPipeline>,
-    pub polygon_pipeline: Option<wgpu::RenderPipeline>,
+    pub settings: DisplaySettings, // View options that survive a recreated State
+    pub pipelines: Pipelines, // Scene pipelines, replaced together by `reload_shaders`
+    pub mesh_pipeline_layout: wgpu::PipelineLayout,
+    // Shared bind group layouts, kept to rebuild the pipelines in `reload_shaders`
+    pub camera_bind_group_layout: wgpu::BindGroupLayout,
+    pub light_bind_group_layout: wgpu::BindGroupLayout,
+    pub globals_bind_group_layout: wgpu::BindGroupLayout,
+    pub transparent_mesh_pipeline_layout: wgpu::PipelineLayout,
+    pub mesh_front_face: wgpu::FrontFace,
+    pub wireframe_bind_group_layout: wgpu::BindGroupLayout,
+    pub hidden_line_bind_group: wgpu::BindGroup,
     pub multisample_texture: wgpu::Texture,
     pub multisample_texture_view: wgpu::TextureView,
     pub multisample_depth_texture: wgpu::Texture,
     pub multisample_depth_texture_view: wgpu::TextureView,
     pub obj_model: model::Model,
+    pub obj_model_is_default: bool, // Still the startup cube, not replaced by a loaded mesh
     pub additional_mesh_models: Vec<model::Model>,
     pub point_model: Option<model::PointModel>,
     pub quad_point_model: Option<model_point::QuadPointModel>,
-    pub line_model: Option<model::LineModel>,
+    pub line_model: Option<model_line::WideLineModel>, // Reference grid and axes
+    pub last_mesh_report: Option<geometry_loader::MeshReport>, // Validation of the most recently loaded mesh
+    pub content_line_models: Vec<model_line::WideLineModel>,
     pub pipe_model: Option<model_pipe::PipeModel>,
+    pub pipe_data: Option<geometry_loader::PipeData>, // JSON pipe set behind `pipe_model`, kept to rebuild it at a new radius scale
+    pub auto_point_size: f32, // Point size multiplier derived from the spacing of the loaded points
     pub polygon_model: Option<model_polygon::PolygonModel>,
-    pub render_mode: RenderMode,
+    pub additional_polygon_models: Vec<model_polygon::PolygonModel>,
+    pub geometry_counts: geometry_loader::GeometryCounts,
+    pub scene_bounds: Option<([f32; 3], [f32; 3])>, // Bounds of the loaded geometry, used to fit the near/far planes
+    pub origin_offset: [f64; 3], // Subtracted from the loaded positions to keep large coordinates precise in f32
+    pub geometry_path: Option<String>, // Last successfully loaded geometry file, used by reloads
+    pub scene: Option<lib_scene::Scene>, // Loaded scene file, kept to toggle its entries
     pub camera: camera::Camera,
+    pub saved_perspective_camera: Option<camera::Camera>, // Set while the top-down view is active
     pub projection: camera::Projection,
     pub camera_controller: camera::CameraController,
     pub camera_uniform: CameraUniform,
@@ -52,22 +253,68 @@ pub struct State<'a> {
     pub light_uniform: LightUniform,
     pub light_buffer: wgpu::Buffer,
     pub light_bind_group: wgpu::BindGroup,
-    pub light_render_pipeline: wgpu::RenderPipeline,
+    pub globals_uniform: GlobalsUniform,
+    pub globals_buffer: wgpu::Buffer,
+    pub globals_bind_group: wgpu::BindGroup,
+    pub debug_draw: lib_debug_draw::DebugDraw,
+    pub labels: lib_labels::Labels,
+    pub label_renderer: Option<lib_labels::LabelRenderer>, // None when the font atlas failed to load
+    pub legend: Option<lib_legend::Legend>, // Color legend of colormapped data, None when hidden
+    pub backplate_renderer: lib_backplate::BackplateRenderer,
+    pub backplate: Option<lib_backplate::Backplate>, // Reference image behind the scene
+    pub target_gizmo: lib_target_gizmo::TargetGizmo, // Crosshair at the orbit pivot while dragging the camera
+    pub background_gradient: lib_background::BackgroundGradient,
+    pub surface_alpha_modes: Vec<wgpu::CompositeAlphaMode>, // Supported by the surface, for transparent backgrounds
+    pub device_lost: Arc<AtomicBool>, // Set by the device lost callback, checked before each frame
+    pub show_default_model: bool, // Draw the startup cube while no geometry is loaded
     pub mouse_pressed: bool,
+    pub cursor_position: Option<[f32; 2]>, // Physical pixels, None while outside the window
+    pub depth_picker: lib_depth_pick::DepthPicker,
+    pub cursor_readout: lib_depth_pick::CursorReadout, // World position under the cursor, throttled
+    pub depth_overlay: lib_depth_overlay::DepthOverlay, // Grayscale depth buffer view for debugging
+    pub outline_renderer: lib_outline::OutlineRenderer, // Selection outlines of mesh models
+    pub split_view: Option<lib_split_view::SplitView>, // Second viewport and its camera, None for a single view
+    pub frame_pacer: lib_frame_pacing::FramePacer, // Optional frame-rate caps of the native event loop
+    pub help_overlay: lib_help_overlay::HelpOverlay,
+    pub reload_poller: lib_hot_reload::ReloadPoller, // Modification time polling of the open file
+    pub viewer_config: ViewerConfig, // Options the state was created with, reused when it is recreated
 }
 
 impl<'a> State<'a> {
     /// Create a new State instance with full GPU initialization
     pub async fn new(window: &'a Window) -> Result<State<'a>, Box<dyn std::error::Error>> {
+        Self::new_with_color_space(window, SurfaceColorSpace::default()).await
+    }
+
+    /// Create a new State preferring an sRGB or linear surface format
+    pub async fn new_with_color_space(window: &'a Window, color_space: SurfaceColorSpace) -> Result<State<'a>, Box<dyn std::error::Error>> {
+        Self::new_with_config(window, ViewerConfig { color_space, ..ViewerConfig::default() }).await
+    }
+
+    /// Create a new State with the given startup options
+    pub async fn new_with_config(window: &'a Window, viewer_config: ViewerConfig) -> Result<State<'a>, Box<dyn std::error::Error>> {
         let size = window.inner_size();
 
         // Initialize GPU context
-        let (_instance, surface, _adapter, device, queue, config) = 
-            init_gpu_context(window, size).await?;
+        let (_instance, surface, _adapter, device, queue, config, surface_alpha_modes) = 
+            init_gpu_context(window, size, viewer_config.color_space).await?;
 
         // Configure the surface with the device - this was missing and causing the macOS crash
         surface.configure(&device, &config);
 
+        // GPU resets and driver updates lose the device; the app loop recreates the state
+        let device_lost = Arc::new(AtomicBool::new(false));
+        {
+            let device_lost = device_lost.clone();
+            device.set_device_lost_callback(move |reason, message| {
+                // `Destroyed` is the expected notification when a State is dropped
+                if reason == wgpu::DeviceLostReason::Unknown {
+                    log::error!("GPU device lost: {}", message);
+                    device_lost.store(true, Ordering::SeqCst);
+                }
+            });
+        }
+
         // Initialize camera system
         let (camera, projection, camera_controller, camera_uniform, camera_buffer, camera_bind_group, camera_bind_group_layout) = 
             init_camera_system(&device, &config);
@@ -76,12 +323,84 @@ impl<'a> State<'a> {
         let (light_uniform, light_buffer, light_bind_group, light_bind_group_layout) = 
             init_lighting_system(&device);
 
+        // Initialize globals shared by all pipelines
+        let (globals_uniform, globals_buffer, globals_bind_group, globals_bind_group_layout) = 
+            init_globals_system(&device, &config);
+
+        // Per-model wireframe colors, and the fixed edge color of the hidden line view
+        let wireframe_bind_group_layout = create_wireframe_bind_group_layout(&device);
+        let (_, hidden_line_bind_group) =
+            create_wireframe_color_bind_group(&device, &wireframe_bind_group_layout, HIDDEN_LINE_COLOR);
+
         // Create depth texture
         let depth_texture_view = create_depth_texture(&device, &config);
         
+        // Mesh pipelines are rebuilt with this layout when the winding is flipped
+        let mesh_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Translucent meshes bind their opacity at group 3, with the wireframe color layout
+        let transparent_mesh_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Transparent Mesh Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout, &wireframe_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
         // Initialize all rendering pipelines
-        let (render_pipeline, point_pipeline, line_pipeline, pipe_pipeline, polygon_pipeline, light_render_pipeline) = 
-            init_pipelines(&device, &config, &camera_bind_group_layout, &light_bind_group_layout).await;
+        let pipelines = init_pipelines(
+            &device,
+            &config,
+            [&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout],
+            &wireframe_bind_group_layout,
+            &mesh_pipeline_layout,
+            &transparent_mesh_pipeline_layout,
+            wgpu::FrontFace::Ccw,
+        ).await;
+
+        // Text labels sample the bundled font atlas; the viewer still works without them
+        let label_renderer = lib_labels::LabelRenderer::new(
+            &device,
+            &queue,
+            &config,
+            DEPTH_FORMAT,
+            [&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout],
+        )
+        .map_err(|e| log::warn!("Failed to create label renderer: {}", e))
+        .ok();
+
+        let backplate_renderer = lib_backplate::BackplateRenderer::new(
+            &device,
+            &config,
+            DEPTH_FORMAT,
+            [&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout],
+        );
+
+        let background_gradient = lib_background::BackgroundGradient::new(
+            &device,
+            &config,
+            DEPTH_FORMAT,
+            [&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout],
+        );
+
+        let target_gizmo = lib_target_gizmo::TargetGizmo::new(
+            &device,
+            &config,
+            DEPTH_FORMAT,
+            [&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout],
+        );
+
+        let depth_picker = lib_depth_pick::DepthPicker::new(&device);
+        let depth_overlay = lib_depth_overlay::DepthOverlay::new(&device, &config, &globals_bind_group_layout);
+        let outline_renderer = lib_outline::OutlineRenderer::new(
+            &device,
+            &config,
+            DEPTH_FORMAT,
+            [&camera_bind_group_layout, &light_bind_group_layout, &globals_bind_group_layout],
+            wgpu::FrontFace::Ccw,
+        );
 
         // Create multisample textures for MSAA
         let multisample_texture = device.create_texture(&wgpu::TextureDescriptor {
@@ -113,7 +432,7 @@ impl<'a> State<'a> {
             sample_count: 4, // 4x MSAA for web compatibility
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING, // Sampled by the depth picker
             view_formats: &[wgpu::TextureFormat::Depth32Float],
         });
 
@@ -121,10 +440,10 @@ impl<'a> State<'a> {
 
         // Load default models and create instances
         let (obj_model, instances, instance_buffer) = 
-            init_models_and_instances(&device, &queue).await;
+            init_models_and_instances(&device, &queue, viewer_config.default_model.as_deref()).await?;
         
         // Create grid lines for visualization
-        let line_model = Some(crate::geometry_generator::create_grid_lines(&device));
+        let line_model = Some(crate::geometry_generator::create_grid_lines(&device, crate::geometry_generator::plain_grid_color, 0.0, crate::geometry_generator::GridVisibility::default()));
 
         Ok(State {
             window,
@@ -132,24 +451,40 @@ impl<'a> State<'a> {
             device,
             queue,
             config,
-            render_pipeline,
-            point_pipeline,
-            line_pipeline,
-            pipe_pipeline,
-            polygon_pipeline,
+            settings: DisplaySettings::default(),
+            pipelines,
+            mesh_pipeline_layout,
+            camera_bind_group_layout,
+            light_bind_group_layout,
+            globals_bind_group_layout,
+            transparent_mesh_pipeline_layout,
+            mesh_front_face: wgpu::FrontFace::Ccw,
+            wireframe_bind_group_layout,
+            hidden_line_bind_group,
             multisample_texture,
             multisample_texture_view,
             multisample_depth_texture,
             multisample_depth_texture_view,
             obj_model,
+            obj_model_is_default: true,
             additional_mesh_models: Vec::new(),
             point_model: None,
             quad_point_model: None,
             line_model,
+            last_mesh_report: None,
+            content_line_models: Vec::new(),
             pipe_model: None,
+            pipe_data: None,
+            auto_point_size: 1.0,
             polygon_model: None,
-            render_mode: RenderMode::default(),
+            additional_polygon_models: Vec::new(),
+            geometry_counts: geometry_loader::GeometryCounts::default(),
+            scene_bounds: None,
+            origin_offset: [0.0; 3],
+            geometry_path: None,
+            scene: None,
             camera,
+            saved_perspective_camera: None,
             projection,
             camera_controller,
             camera_uniform,
@@ -162,8 +497,31 @@ impl<'a> State<'a> {
             light_uniform,
             light_buffer,
             light_bind_group,
-            light_render_pipeline,
+            globals_uniform,
+            globals_buffer,
+            globals_bind_group,
+            debug_draw: lib_debug_draw::DebugDraw::default(),
+            labels: lib_labels::Labels::default(),
+            label_renderer,
+            legend: None,
+            backplate_renderer,
+            backplate: None,
+            target_gizmo,
+            background_gradient,
+            surface_alpha_modes,
+            device_lost,
+            show_default_model: true,
             mouse_pressed: false,
+            cursor_position: None,
+            depth_picker,
+            cursor_readout: lib_depth_pick::CursorReadout::default(),
+            depth_overlay,
+            outline_renderer,
+            split_view: None,
+            frame_pacer: lib_frame_pacing::FramePacer::default(),
+            help_overlay: lib_help_overlay::HelpOverlay::default(),
+            reload_poller: lib_hot_reload::ReloadPoller::new(viewer_config.reload_poll_interval),
+            viewer_config,
         })
     }
 }
@@ -171,8 +529,9 @@ impl<'a> State<'a> {
 /// Initialize GPU context (instance, surface, adapter, device, queue, config)
 async fn init_gpu_context(
     window: &Window, 
-    size: winit::dpi::PhysicalSize<u32>
-) -> Result<(wgpu::Instance, wgpu::Surface, wgpu::Adapter, wgpu::Device, wgpu::Queue, wgpu::SurfaceConfiguration), Box<dyn std::error::Error>> {
+    size: winit::dpi::PhysicalSize<u32>,
+    color_space: SurfaceColorSpace,
+) -> Result<(wgpu::Instance, wgpu::Surface, wgpu::Adapter, wgpu::Device, wgpu::Queue, wgpu::SurfaceConfiguration, Vec<wgpu::CompositeAlphaMode>), Box<dyn std::error::Error>> {
     // The instance is a handle to our GPU
     let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
         #[cfg(not(target_arch = "wasm32"))]
@@ -222,12 +581,14 @@ async fn init_gpu_context(
         .unwrap();
 
     let surface_caps = surface.get_capabilities(&adapter);
+    let prefer_srgb = color_space == SurfaceColorSpace::Srgb;
     let surface_format = surface_caps
         .formats
         .iter()
         .copied()
-        .find(|f| f.is_srgb())
+        .find(|f| f.is_srgb() == prefer_srgb)
         .unwrap_or(surface_caps.formats[0]);
+    log::info!("Surface format: {:?} (preferred {:?})", surface_format, color_space);
 
     let config = wgpu::SurfaceConfiguration {
         usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -240,7 +601,7 @@ async fn init_gpu_context(
         desired_maximum_frame_latency: 2,
     };
 
-    Ok((instance, surface, adapter, device, queue, config))
+    Ok((instance, surface, adapter, device, queue, config, surface_caps.alpha_modes))
 }
 
 /// Initialize camera system (camera, projection, controller, uniform, buffer, bind group, layout)
@@ -295,12 +656,7 @@ fn init_camera_system(
 
 /// Initialize lighting system (uniform, buffer, bind group, layout)
 fn init_lighting_system(device: &wgpu::Device) -> (LightUniform, wgpu::Buffer, wgpu::BindGroup, wgpu::BindGroupLayout) {
-    let light_uniform = LightUniform {
-        position: [2.0, 2.0, 2.0],
-        _padding: 0,
-        color: [1.0, 1.0, 1.0],
-        _padding2: 0,
-    };
+    let light_uniform = LightUniform::new();
 
     let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
         label: Some("Light VB"),
@@ -334,6 +690,82 @@ fn init_lighting_system(device: &wgpu::Device) -> (LightUniform, wgpu::Buffer, w
     (light_uniform, light_buffer, light_bind_group, light_bind_group_layout)
 }
 
+/// Initialize globals shared by every pipeline at bind group 2 (uniform, buffer, bind group, layout)
+fn init_globals_system(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> (GlobalsUniform, wgpu::Buffer, wgpu::BindGroup, wgpu::BindGroupLayout) {
+    let mut globals_uniform = GlobalsUniform::new();
+    globals_uniform.update_surface_format(config.format);
+
+    let globals_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Globals Buffer"),
+        contents: bytemuck::cast_slice(&[globals_uniform]),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let globals_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+        label: Some("globals_bind_group_layout"),
+    });
+
+    let globals_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &globals_bind_group_layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: globals_buffer.as_entire_binding(),
+        }],
+        label: Some("globals_bind_group"),
+    });
+
+    (globals_uniform, globals_buffer, globals_bind_group, globals_bind_group_layout)
+}
+
+/// Create the bind group layout of a model's wireframe color uniform (group 3)
+fn create_wireframe_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+        label: Some("wireframe_bind_group_layout"),
+    })
+}
+
+/// Create a wireframe color uniform and its bind group (group 3 of the wireframe pipelines)
+fn create_wireframe_color_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    color: [f32; 4],
+) -> (wgpu::Buffer, wgpu::BindGroup) {
+    let color_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Wireframe Color Buffer"),
+        contents: bytemuck::cast_slice(&color),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: color_buffer.as_entire_binding(),
+        }],
+        label: Some("wireframe_bind_group"),
+    });
+    (color_buffer, bind_group)
+}
+
 /// Create depth texture
 fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::TextureView {
     let depth_size = wgpu::Extent3d {
@@ -356,90 +788,463 @@ fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfigurati
     depth_texture.create_view(&wgpu::TextureViewDescriptor::default())
 }
 
-/// Initialize all rendering pipelines
-async fn init_pipelines(
+/// Create the mesh pipelines (shaded, face orientation, depth pre-pass and depth Equal)
+///
+/// `front_face` selects which winding counts as front facing; the pipelines are
+/// rebuilt with it when the mesh winding is flipped at runtime.
+fn create_mesh_pipelines(
     device: &wgpu::Device,
     config: &wgpu::SurfaceConfiguration,
-    camera_bind_group_layout: &wgpu::BindGroupLayout,
-    light_bind_group_layout: &wgpu::BindGroupLayout,
+    render_pipeline_layout: &wgpu::PipelineLayout,
+    front_face: wgpu::FrontFace,
 ) -> (
     wgpu::RenderPipeline,
     Option<wgpu::RenderPipeline>,
     Option<wgpu::RenderPipeline>,
     Option<wgpu::RenderPipeline>,
-    Option<wgpu::RenderPipeline>,
-    wgpu::RenderPipeline,
 ) {
-    const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
-
-    // Create empty texture bind group layout
-    let _texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-        entries: &[],
-        label: Some("texture_bind_group_layout"),
-    });
-
     // Main render pipeline
-    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: Some("Render Pipeline Layout"),
-        bind_group_layouts: &[camera_bind_group_layout, light_bind_group_layout],
-        push_constant_ranges: &[],
-    });
-
     let render_pipeline = {
         let shader = wgpu::ShaderModuleDescriptor {
             label: Some("Normal Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/shader.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("shader.wgsl", include_str!("shaders/shader.wgsl"))),
         };
         lib_pipeline::create_render_pipeline(
             device,
-            &render_pipeline_layout,
+            render_pipeline_layout,
             config.format,
             Some(DEPTH_FORMAT),
             &[model::ModelVertex::desc(), InstanceRaw::desc()],
             shader,
+            front_face,
         )
     };
 
+    // Face orientation debug pipeline - same mesh shader without culling so back faces are visible
+    let face_orientation_pipeline = Some({
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Face Orientation Shader"),
+            source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("shader.wgsl", include_str!("shaders/shader.wgsl"))),
+        });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Face Orientation Pipeline"),
+            layout: Some(render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[model::ModelVertex::desc(), InstanceRaw::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_face_orientation"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face,
+                cull_mode: None, // Back faces must be drawn to be colored
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 4,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    });
+
+    // Depth pre-pass pipelines for meshes: the pre-pass writes depth only, then the
+    // color pass shades each pixel once with depth_compare Equal and depth writes off
+    let mesh_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Mesh Depth Pre-pass Shader"),
+        source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("shader.wgsl", include_str!("shaders/shader.wgsl"))),
+    });
+    let mesh_depth_pipeline = |label: &str, fragment_entry: &str, write_mask: wgpu::ColorWrites, depth_write_enabled: bool, depth_compare: wgpu::CompareFunction| {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &mesh_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[model::ModelVertex::desc(), InstanceRaw::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &mesh_shader,
+                entry_point: Some(fragment_entry),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled,
+                depth_compare,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 4,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    };
+    let mesh_depth_prepass_pipeline = Some(mesh_depth_pipeline(
+        "Mesh Depth Pre-pass Pipeline",
+        "fs_depth_only",
+        wgpu::ColorWrites::empty(),
+        true,
+        wgpu::CompareFunction::Less,
+    ));
+    let mesh_depth_equal_pipeline = Some(mesh_depth_pipeline(
+        "Mesh Depth Equal Pipeline",
+        "fs_main",
+        wgpu::ColorWrites::ALL,
+        false,
+        wgpu::CompareFunction::Equal,
+    ));
+
+    (render_pipeline, face_orientation_pipeline, mesh_depth_prepass_pipeline, mesh_depth_equal_pipeline)
+}
+
+/// Rebuild the mesh pipelines so that `front_face` winding is front facing
+///
+/// A global override for imported meshes whose triangles are wound clockwise.
+pub fn set_mesh_front_face(state: &mut State, front_face: wgpu::FrontFace) {
+    let (render_pipeline, face_orientation_pipeline, mesh_depth_prepass_pipeline, mesh_depth_equal_pipeline) =
+        create_mesh_pipelines(&state.device, &state.config, &state.mesh_pipeline_layout, front_face);
+    state.pipelines.render_pipeline = render_pipeline;
+    state.pipelines.face_orientation_pipeline = face_orientation_pipeline;
+    state.pipelines.mesh_depth_prepass_pipeline = mesh_depth_prepass_pipeline;
+    state.pipelines.mesh_depth_equal_pipeline = mesh_depth_equal_pipeline;
+    state.pipelines.transparent_mesh_pipeline =
+        create_transparent_mesh_pipeline(&state.device, &state.config, &state.transparent_mesh_pipeline_layout, front_face);
+    state.outline_renderer.set_front_face(&state.device, &state.config, DEPTH_FORMAT, front_face);
+    state.mesh_front_face = front_face;
+}
+
+/// Rebuild the scene pipelines from the shader files on disk, to iterate on WGSL without recompiling
+///
+/// Covers the mesh, point, line, pipe, polygon, wireframe and light pipelines; the overlays
+/// (labels, background, backplate, outlines, depth views and target gizmo) keep their
+/// built-in shaders. Compilation and validation errors are logged and the previous
+/// pipelines stay in use, so a typo in a shader does not end the session.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn reload_shaders(state: &mut State) {
+    lib_pipeline::read_shaders_from_disk();
+    state.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let pipelines = pollster::block_on(init_pipelines(
+        &state.device,
+        &state.config,
+        [&state.camera_bind_group_layout, &state.light_bind_group_layout, &state.globals_bind_group_layout],
+        &state.wireframe_bind_group_layout,
+        &state.mesh_pipeline_layout,
+        &state.transparent_mesh_pipeline_layout,
+        state.mesh_front_face,
+    ));
+    if let Some(error) = pollster::block_on(state.device.pop_error_scope()) {
+        log::error!("Shader reload failed, keeping the previous pipelines: {}", error);
+        return;
+    }
+
+    state.pipelines = pipelines;
+    log::info!("Reloaded shaders from {}/src/shaders", env!("CARGO_MANIFEST_DIR"));
+}
+
+/// Create the pipeline of translucent mesh models
+///
+/// Alpha blended and without depth writes, so meshes drawn back to front show each
+/// other through; back faces stay culled like the opaque mesh pipeline.
+fn create_transparent_mesh_pipeline(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    pipeline_layout: &wgpu::PipelineLayout,
+    front_face: wgpu::FrontFace,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Transparent Mesh Shader"),
+        source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("shader.wgsl", include_str!("shaders/shader.wgsl"))),
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Transparent Mesh Pipeline"),
+        layout: Some(pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            buffers: &[model::ModelVertex::desc(), InstanceRaw::desc()],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_transparent"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: config.format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face,
+            cull_mode: Some(wgpu::Face::Back),
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 4,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// Show or hide the wireframe overlay of one mesh model in `color`
+///
+/// Index 0 is `obj_model`, the following indices are `additional_mesh_models`.
+pub fn set_mesh_wireframe(state: &mut State, index: usize, enabled: bool, color: [f32; 4]) -> Result<(), String> {
+    let mesh_count = 1 + state.additional_mesh_models.len();
+    let model = match index {
+        0 => &mut state.obj_model,
+        _ => state.additional_mesh_models.get_mut(index - 1)
+            .ok_or_else(|| format!("Mesh index {} out of range ({} meshes)", index, mesh_count))?,
+    };
+
+    model.show_wireframe = enabled;
+    match &mut model.wireframe {
+        Some(wireframe) => {
+            wireframe.color = color;
+            state.queue.write_buffer(&wireframe.color_buffer, 0, bytemuck::cast_slice(&color));
+        }
+        None if enabled => {
+            let (color_buffer, bind_group) =
+                create_wireframe_color_bind_group(&state.device, &state.wireframe_bind_group_layout, color);
+            model.wireframe = Some(model::ModelWireframe { color, color_buffer, bind_group });
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Set the opacity of one mesh model, from 0 (invisible) to 1 (opaque)
+///
+/// Index 0 is `obj_model`, the following indices are `additional_mesh_models`.
+/// Translucent models are drawn after the opaque geometry, farthest first.
+pub fn set_mesh_opacity(state: &mut State, index: usize, opacity: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&opacity) {
+        return Err(format!("Mesh opacity must be between 0 and 1, got {}", opacity));
+    }
+    let mesh_count = 1 + state.additional_mesh_models.len();
+    let model = match index {
+        0 => &mut state.obj_model,
+        _ => state.additional_mesh_models.get_mut(index - 1)
+            .ok_or_else(|| format!("Mesh index {} out of range ({} meshes)", index, mesh_count))?,
+    };
+
+    let color = [1.0, 1.0, 1.0, opacity];
+    match &mut model.opacity {
+        Some(model_opacity) => {
+            model_opacity.opacity = opacity;
+            state.queue.write_buffer(&model_opacity.color_buffer, 0, bytemuck::cast_slice(&color));
+        }
+        None if opacity < 1.0 => {
+            let (color_buffer, bind_group) =
+                create_wireframe_color_bind_group(&state.device, &state.wireframe_bind_group_layout, color);
+            model.opacity = Some(model::ModelOpacity { opacity, color_buffer, bind_group });
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Draw mesh `index` (0 is the first mesh) once per instance from its own instance buffer
+///
+/// An empty list hides the mesh; `None` goes back to the shared instance buffer.
+pub fn set_model_instances(state: &mut State, index: usize, instances: Option<Vec<Instance>>) -> Result<(), String> {
+    let instances = instances.map(|instances| model::ModelInstances::new(&state.device, instances));
+    let mesh_count = 1 + state.additional_mesh_models.len();
+    let mesh_model = match index {
+        0 => &mut state.obj_model,
+        _ => state.additional_mesh_models.get_mut(index - 1)
+            .ok_or_else(|| format!("Mesh index {} out of range ({} meshes)", index, mesh_count))?,
+    };
+    mesh_model.instances = instances;
+    Ok(())
+}
+
+/// Tint the shared instances, one color per instance in `State::instances` order
+///
+/// White keeps a mesh's own colors. Models with their own instances (`set_model_instances`)
+/// take the colors of those instances instead.
+pub fn set_instance_colors(state: &mut State, colors: &[[f32; 3]]) -> Result<(), String> {
+    if colors.len() != state.instances.len() {
+        return Err(format!("Expected {} instance colors, got {}", state.instances.len(), colors.len()));
+    }
+    for (instance, color) in state.instances.iter_mut().zip(colors) {
+        instance.color = *color;
+    }
+    let instance_data = state.instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
+    state.queue.write_buffer(&state.instance_buffer, 0, bytemuck::cast_slice(&instance_data));
+    Ok(())
+}
+
+/// Set how the color target starts each frame, switching the surface to a
+/// compositing alpha mode when the clear color is translucent
+pub fn set_background_clear(state: &mut State, background_clear: BackgroundClear) {
+    let translucent = matches!(background_clear, BackgroundClear::Color(color) if color[3] < 1.0);
+    let alpha_mode = if translucent {
+        [wgpu::CompositeAlphaMode::PreMultiplied, wgpu::CompositeAlphaMode::PostMultiplied]
+            .iter()
+            .copied()
+            .find(|mode| state.surface_alpha_modes.contains(mode))
+            .unwrap_or_else(|| {
+                log::warn!("The surface does not support transparency (alpha modes {:?}); the background stays opaque", state.surface_alpha_modes);
+                state.surface_alpha_modes[0]
+            })
+    } else {
+        state.surface_alpha_modes[0]
+    };
+    if alpha_mode != state.config.alpha_mode {
+        state.config.alpha_mode = alpha_mode;
+        state.surface.configure(&state.device, &state.config);
+    }
+    if let BackgroundClear::Gradient { top, bottom } = background_clear {
+        state.background_gradient.set_colors(&state.queue, top, bottom);
+    }
+    state.settings.background_clear = background_clear;
+}
+
+/// The scene pipelines, built together by `init_pipelines`
+///
+/// The overlays (labels, background, backplate, outlines, depth views and target gizmo)
+/// own their pipelines and are not part of it.
+pub struct Pipelines {
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub face_orientation_pipeline: Option<wgpu::RenderPipeline>,
+    pub mesh_depth_prepass_pipeline: Option<wgpu::RenderPipeline>,
+    pub mesh_depth_equal_pipeline: Option<wgpu::RenderPipeline>,
+    pub transparent_mesh_pipeline: wgpu::RenderPipeline, // Translucent mesh models, blended without depth writes
+    pub point_pipeline: Option<lib_pipeline::BlendPipelines>,
+    pub line_pipeline: Option<lib_pipeline::BlendPipelines>,
+    pub grid_line_pipeline: Option<wgpu::RenderPipeline>,
+    pub wide_line_pipeline: Option<lib_pipeline::BlendPipelines>,
+    pub pipe_pipeline: Option<wgpu::RenderPipeline>,
+    pub polygon_pipeline: Option<wgpu::RenderPipeline>,
+    pub polygon_unlit_pipeline: Option<wgpu::RenderPipeline>,
+    pub sphere_point_pipeline: Option<wgpu::RenderPipeline>, // Points as lit spheres, see `PointShape`
+    // Variants without depth writes, for the transparent phase and render modes with depth writes off
+    pub transparent_point_pipeline: Option<lib_pipeline::BlendPipelines>,
+    pub transparent_wide_line_pipeline: Option<lib_pipeline::BlendPipelines>,
+    pub transparent_pipe_pipeline: Option<wgpu::RenderPipeline>,
+    pub transparent_polygon_pipeline: Option<wgpu::RenderPipeline>,
+    pub transparent_polygon_unlit_pipeline: Option<wgpu::RenderPipeline>,
+    pub wireframe_pipeline: Option<wgpu::RenderPipeline>,
+    pub hidden_line_pipeline: Option<wgpu::RenderPipeline>, // Edges behind the depth buffer, dashed
+    pub light_render_pipeline: wgpu::RenderPipeline,
+}
+
+/// Initialize all rendering pipelines
+async fn init_pipelines(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    shared_bind_group_layouts: [&wgpu::BindGroupLayout; 3],
+    wireframe_bind_group_layout: &wgpu::BindGroupLayout,
+    render_pipeline_layout: &wgpu::PipelineLayout,
+    transparent_mesh_pipeline_layout: &wgpu::PipelineLayout,
+    mesh_front_face: wgpu::FrontFace,
+) -> Pipelines {
+    // All pipelines share the same bind group layout: camera (0), light (1), globals (2).
+    // Keeping the indices identical lets the shared groups stay bound across pipelines.
+    let [camera_bind_group_layout, light_bind_group_layout, globals_bind_group_layout] = shared_bind_group_layouts;
+
+    // Create empty texture bind group layout
+    let _texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[],
+        label: Some("texture_bind_group_layout"),
+    });
+
+    // Mesh pipelines; counter-clockwise front faces until the winding is flipped
+    let (render_pipeline, face_orientation_pipeline, mesh_depth_prepass_pipeline, mesh_depth_equal_pipeline) =
+        create_mesh_pipelines(device, config, render_pipeline_layout, mesh_front_face);
+
+
     // Point pipeline
     let point_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Point Pipeline Layout"),
-        bind_group_layouts: &[camera_bind_group_layout],
+        bind_group_layouts: &shared_bind_group_layouts,
         push_constant_ranges: &[],
     });
 
-    let point_pipeline = Some({
+    // One variant per blend mode, picked by each point model's `blend_mode`
+    let point_shader_module = {
         let shader = wgpu::ShaderModuleDescriptor {
             label: Some("Point Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/point.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("point.wgsl", include_str!("shaders/point.wgsl"))),
         };
-        let shader_module = device.create_shader_module(shader);
+        device.create_shader_module(shader)
+    };
+    let point_render_pipeline = |label: &str, fragment_entry: &str, blend_mode: lib_pipeline::BlendMode, depth_write_enabled: bool| {
         device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Point Render Pipeline"),
+            label: Some(label),
             layout: Some(&point_pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &shader_module,
+                module: &point_shader_module,
                 entry_point: Some("vs_main"),
                 buffers: &[model_point::QuadPointVertex::desc()],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader_module,
-                entry_point: Some("fs_main"),
+                module: &point_shader_module,
+                entry_point: Some(fragment_entry),
                 compilation_options: Default::default(),
                 targets: &[Some(wgpu::ColorTargetState {
                     format: config.format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
-                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                        alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                    }),
+                    blend: Some(blend_mode.blend_state()),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
             }),
@@ -454,7 +1259,7 @@ async fn init_pipelines(
             },
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: DEPTH_FORMAT,
-                depth_write_enabled: true,
+                depth_write_enabled,
                 depth_compare: wgpu::CompareFunction::Less,
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
@@ -467,37 +1272,55 @@ async fn init_pipelines(
             multiview: None,
             cache: None,
         })
-    });
+    };
+    let point_pipeline = Some(lib_pipeline::BlendPipelines::new(|blend_mode| {
+        point_render_pipeline(&format!("Point Render Pipeline ({:?})", blend_mode), "fs_main", blend_mode, blend_mode.depth_write_enabled())
+    }));
+    // Blended points in the transparent phase of the All mode, drawn after all opaque geometry,
+    // and points in render modes with depth writes off
+    let transparent_point_pipeline = Some(lib_pipeline::BlendPipelines::new(|blend_mode| {
+        point_render_pipeline(&format!("Transparent Point Render Pipeline ({:?})", blend_mode), "fs_main", blend_mode, false)
+    }));
+    // Opaque sphere impostors, which write their own per-fragment depth
+    let sphere_point_pipeline = Some(point_render_pipeline(
+        "Sphere Point Render Pipeline",
+        "fs_sphere",
+        lib_pipeline::BlendMode::Opaque,
+        true,
+    ));
 
     // Line pipeline
     let line_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Line Pipeline Layout"),
-        bind_group_layouts: &[camera_bind_group_layout],
+        bind_group_layouts: &shared_bind_group_layouts,
         push_constant_ranges: &[],
     });
 
-    let line_pipeline = Some({
+    // Line-list pipelines for content lines, one per blend mode
+    let line_shader_module = {
         let shader = wgpu::ShaderModuleDescriptor {
             label: Some("Line Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/line.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("line.wgsl", include_str!("shaders/line.wgsl"))),
         };
-        let shader_module = device.create_shader_module(shader);
+        device.create_shader_module(shader)
+    };
+    let line_list_pipeline = |label: &str, blend_mode: lib_pipeline::BlendMode| {
         device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Line Render Pipeline"),
+            label: Some(label),
             layout: Some(&line_pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &shader_module,
+                module: &line_shader_module,
                 entry_point: Some("vs_main"),
                 buffers: &[model_line::LineVertex::desc()],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader_module,
+                module: &line_shader_module,
                 entry_point: Some("fs_main"),
                 compilation_options: Default::default(),
                 targets: &[Some(wgpu::ColorTargetState {
                     format: config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    blend: Some(blend_mode.blend_state()),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
             }),
@@ -512,7 +1335,7 @@ async fn init_pipelines(
             },
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: DEPTH_FORMAT,
-                depth_write_enabled: true,
+                depth_write_enabled: blend_mode.depth_write_enabled(),
                 depth_compare: wgpu::CompareFunction::Less,
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
@@ -525,32 +1348,97 @@ async fn init_pipelines(
             multiview: None,
             cache: None,
         })
-    });
+    };
+    let line_pipeline = Some(lib_pipeline::BlendPipelines::new(|blend_mode| {
+        line_list_pipeline(&format!("Line Render Pipeline ({:?})", blend_mode), blend_mode)
+    }));
+
+    // Wide line pipelines (screen-space quads, share the line pipeline layout). The grid is
+    // faded, keeps a constant pixel width and does not write depth, so it never hides
+    // content drawn after it
+    let wide_line_shader_module = {
+        let shader = wgpu::ShaderModuleDescriptor {
+            label: Some("Wide Line Shader"),
+            source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("wide_line.wgsl", include_str!("shaders/wide_line.wgsl"))),
+        };
+        device.create_shader_module(shader)
+    };
+    let wide_line_quad_pipeline = |label: &str, entry_points: (&str, &str), blend_mode: lib_pipeline::BlendMode, depth_write_enabled: bool| {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&line_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &wide_line_shader_module,
+                entry_point: Some(entry_points.0),
+                buffers: &[model_line::WideLineVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &wide_line_shader_module,
+                entry_point: Some(entry_points.1),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(blend_mode.blend_state()),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None, // Quads may face either way after screen-space expansion
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: depth_write_enabled && blend_mode.depth_write_enabled(),
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 4, // Enable 4x MSAA for web compatibility
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    };
+    let wide_line_pipeline = Some(lib_pipeline::BlendPipelines::new(|blend_mode| {
+        wide_line_quad_pipeline(&format!("Wide Line Render Pipeline ({:?})", blend_mode), ("vs_main", "fs_main"), blend_mode, true)
+    }));
+    let transparent_wide_line_pipeline = Some(lib_pipeline::BlendPipelines::new(|blend_mode| {
+        wide_line_quad_pipeline(&format!("Transparent Wide Line Render Pipeline ({:?})", blend_mode), ("vs_main", "fs_main"), blend_mode, false)
+    }));
+    let grid_line_pipeline = Some(wide_line_quad_pipeline("Grid Line Render Pipeline", ("vs_grid", "fs_grid"), lib_pipeline::BlendMode::Alpha, false));
 
     // Pipe pipeline
     let pipe_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Pipe Pipeline Layout"),
-        bind_group_layouts: &[camera_bind_group_layout],
+        bind_group_layouts: &shared_bind_group_layouts,
         push_constant_ranges: &[],
     });
 
-    let pipe_pipeline = Some({
-        let shader = wgpu::ShaderModuleDescriptor {
-            label: Some("Pipe Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/pipe.wgsl").into()),
-        };
-        let shader_module = device.create_shader_module(shader);
+    let pipe_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Pipe Shader"),
+        source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("pipe.wgsl", include_str!("shaders/pipe.wgsl"))),
+    });
+    let pipe_render_pipeline = |label: &str, depth_write_enabled: bool| {
         device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Pipe Render Pipeline"),
+            label: Some(label),
             layout: Some(&pipe_pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &shader_module,
+                module: &pipe_shader_module,
                 entry_point: Some("vs_main"),
                 buffers: &[model_pipe::PipeVertex::desc()],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader_module,
+                module: &pipe_shader_module,
                 entry_point: Some("fs_main"),
                 compilation_options: Default::default(),
                 targets: &[Some(wgpu::ColorTargetState {
@@ -570,7 +1458,7 @@ async fn init_pipelines(
             },
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: DEPTH_FORMAT,
-                depth_write_enabled: true,
+                depth_write_enabled,
                 depth_compare: wgpu::CompareFunction::Less,
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
@@ -583,33 +1471,92 @@ async fn init_pipelines(
             multiview: None,
             cache: None,
         })
-    });
+    };
+    let pipe_pipeline = Some(pipe_render_pipeline("Pipe Render Pipeline", true));
+    let transparent_pipe_pipeline = Some(pipe_render_pipeline("Transparent Pipe Render Pipeline", false));
 
     // Polygon pipeline
     let polygon_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Polygon Pipeline Layout"),
-        bind_group_layouts: &[camera_bind_group_layout, light_bind_group_layout],
+        bind_group_layouts: &shared_bind_group_layouts,
+        push_constant_ranges: &[],
+    });
+
+    // Lit (fs_main) and unlit (fs_unlit) variants share the vertex stage; the transparent
+    // variants do not write depth so overlapping translucent sets all stay visible
+    let polygon_shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Polygon Shader"),
+        source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("polygon.wgsl", include_str!("shaders/polygon.wgsl"))),
+    });
+    let polygon_render_pipeline = |label: &str, fragment_entry: &str, depth_write_enabled: bool| {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&polygon_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &polygon_shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[model_polygon::PolygonVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &polygon_shader_module,
+                entry_point: Some(fragment_entry),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: model_polygon::PRIMITIVE_STATE,
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 4, // Enable 4x MSAA for web compatibility
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    };
+    let polygon_pipeline = Some(polygon_render_pipeline("Polygon Render Pipeline", "fs_main", true));
+    let polygon_unlit_pipeline = Some(polygon_render_pipeline("Polygon Unlit Render Pipeline", "fs_unlit", true));
+    let transparent_polygon_pipeline = Some(polygon_render_pipeline("Transparent Polygon Render Pipeline", "fs_main", false));
+    let transparent_polygon_unlit_pipeline =
+        Some(polygon_render_pipeline("Transparent Polygon Unlit Render Pipeline", "fs_unlit", false));
+
+    // Wireframe overlay pipeline - mesh edges as lines, colored per model through group 3
+    let wireframe_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Wireframe Pipeline Layout"),
+        bind_group_layouts: &[camera_bind_group_layout, light_bind_group_layout, globals_bind_group_layout, wireframe_bind_group_layout],
         push_constant_ranges: &[],
     });
 
-    let polygon_pipeline = Some({
+    // Edges pass the depth test with a small shader bias (visible) or only behind it (occluded)
+    let wireframe_line_pipeline = |label: &str, fragment_entry: &str, depth_compare: wgpu::CompareFunction| {
         let shader = wgpu::ShaderModuleDescriptor {
-            label: Some("Polygon Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/polygon.wgsl").into()),
+            label: Some("Wireframe Shader"),
+            source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("wireframe.wgsl", include_str!("shaders/wireframe.wgsl"))),
         };
         let shader_module = device.create_shader_module(shader);
         device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Polygon Render Pipeline"),
-            layout: Some(&polygon_pipeline_layout),
+            label: Some(label),
+            layout: Some(&wireframe_pipeline_layout),
             vertex: wgpu::VertexState {
                 module: &shader_module,
                 entry_point: Some("vs_main"),
-                buffers: &[model_polygon::PolygonVertex::desc()],
+                buffers: &[model::ModelVertex::desc(), InstanceRaw::desc()],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
                 module: &shader_module,
-                entry_point: Some("fs_main"),
+                entry_point: Some(fragment_entry),
                 compilation_options: Default::default(),
                 targets: &[Some(wgpu::ColorTargetState {
                     format: config.format,
@@ -618,20 +1565,20 @@ async fn init_pipelines(
                 })],
             }),
             primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
+                topology: wgpu::PrimitiveTopology::LineList,
                 strip_index_format: None,
                 front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
+                cull_mode: None,
                 polygon_mode: wgpu::PolygonMode::Fill,
                 unclipped_depth: false,
                 conservative: false,
             },
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: DEPTH_FORMAT,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
+                depth_write_enabled: false, // The overlay must not hide geometry drawn after it
+                depth_compare,
                 stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
+                bias: wgpu::DepthBiasState::default(), // Not allowed for lines, biased in the shader
             }),
             multisample: wgpu::MultisampleState {
                 count: 4, // Enable 4x MSAA for web compatibility
@@ -641,55 +1588,118 @@ async fn init_pipelines(
             multiview: None,
             cache: None,
         })
-    });
+    };
+
+    let wireframe_pipeline = Some(wireframe_line_pipeline("Wireframe Render Pipeline", "fs_main", wgpu::CompareFunction::LessEqual));
+    let hidden_line_pipeline = Some(wireframe_line_pipeline("Hidden Line Render Pipeline", "fs_dashed", wgpu::CompareFunction::Greater));
 
     // Light render pipeline
     let light_render_pipeline = {
         let shader = wgpu::ShaderModuleDescriptor {
             label: Some("Light Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/light.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(lib_pipeline::shader_source("light.wgsl", include_str!("shaders/light.wgsl"))),
         };
         lib_pipeline::create_render_pipeline(
             device,
-            &render_pipeline_layout,
+            render_pipeline_layout,
             config.format,
             Some(DEPTH_FORMAT),
-            &[model::ModelVertex::desc(), InstanceRaw::desc()],
+            &light_vertex_layouts(),
             shader,
+            wgpu::FrontFace::Ccw,
         )
     };
 
-    (render_pipeline, point_pipeline, line_pipeline, pipe_pipeline, polygon_pipeline, light_render_pipeline)
+    let transparent_mesh_pipeline =
+        create_transparent_mesh_pipeline(device, config, transparent_mesh_pipeline_layout, mesh_front_face);
+
+    Pipelines {
+        render_pipeline,
+        face_orientation_pipeline,
+        mesh_depth_prepass_pipeline,
+        mesh_depth_equal_pipeline,
+        transparent_mesh_pipeline,
+        point_pipeline,
+        line_pipeline,
+        grid_line_pipeline,
+        wide_line_pipeline,
+        pipe_pipeline,
+        polygon_pipeline,
+        polygon_unlit_pipeline,
+        sphere_point_pipeline,
+        transparent_point_pipeline,
+        transparent_wide_line_pipeline,
+        transparent_pipe_pipeline,
+        transparent_polygon_pipeline,
+        transparent_polygon_unlit_pipeline,
+        wireframe_pipeline,
+        hidden_line_pipeline,
+        light_render_pipeline,
+    }
+}
+
+/// Vertex buffers of the light indicator pipeline: the model only
+///
+/// light.wgsl looks each light up by `instance_index`, so it must not declare the
+/// one-element instance buffer; one instance per light would overrun it.
+fn light_vertex_layouts() -> [wgpu::VertexBufferLayout<'static>; 1] {
+    [model::ModelVertex::desc()]
 }
 
 /// Initialize models and instances
+///
+/// `default_model` is the OBJ file shown until geometry is loaded; without one the model is empty.
 async fn init_models_and_instances(
     device: &wgpu::Device,
     queue: &wgpu::Queue,
-) -> (model::Model, Vec<Instance>, wgpu::Buffer) {
+    default_model: Option<&str>,
+) -> Result<(model::Model, Vec<Instance>, wgpu::Buffer), Box<dyn std::error::Error>> {
     // Create empty texture bind group layout for model loading
     let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
         entries: &[],
         label: Some("texture_bind_group_layout"),
     });
     
-    // Load default cube model
-    let obj_model = crate::resources::load_model("cube.obj", device, queue, &texture_bind_group_layout)
-        .await
-        .expect("Failed to load cube model");
+    // Load the default model, e.g. the cube
+    let obj_model = match default_model {
+        Some(path) => crate::resources::load_model(path, crate::geometry_loader::DEFAULT_MESH_COLOR, device, queue, &texture_bind_group_layout)
+            .await
+            .map_err(|e| format!("Failed to load default model {}: {}", path, e))?,
+        None => model::Model::new(Vec::new()),
+    };
 
     // Create single instance at origin
-    let instances = vec![Instance {
-        position: cgmath::Vector3::new(0.0, 0.0, 0.0),
-        rotation: cgmath::Quaternion::from_axis_angle(cgmath::Vector3::unit_z(), cgmath::Deg(0.0)),
-    }];
+    let instances = vec![Instance::new(
+        cgmath::Vector3::new(0.0, 0.0, 0.0),
+        cgmath::Quaternion::from_axis_angle(cgmath::Vector3::unit_z(), cgmath::Deg(0.0)),
+    )];
 
     let instance_data = instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
     let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
         label: Some("Instance Buffer"),
         contents: bytemuck::cast_slice(&instance_data),
-        usage: wgpu::BufferUsages::VERTEX,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST, // Colors can be rewritten in place
     });
 
-    (obj_model, instances, instance_buffer)
+    Ok((obj_model, instances, instance_buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib_render::{LightSource, LightUniform};
+
+    #[test]
+    fn light_pipeline_draws_several_lights_without_an_instance_buffer() {
+        // `render_light_model` draws one instance per active light, more than the single
+        // `InstanceRaw` in the shared instance buffer, so no layout may step per instance
+        let mut light_uniform = LightUniform::new();
+        light_uniform.add(LightSource::new([-2.0, 2.0, 2.0], [1.0, 0.5, 0.5]));
+        light_uniform.add(LightSource::new([0.0, -2.0, 2.0], [0.5, 0.5, 1.0]));
+        assert_eq!(light_uniform.count, 3);
+
+        assert!(light_vertex_layouts()
+            .iter()
+            .all(|layout| layout.step_mode == wgpu::VertexStepMode::Vertex));
+    }
 }
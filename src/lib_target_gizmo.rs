@@ -0,0 +1,158 @@
+//! # Target Gizmo Module
+//!
+//! A small axis crosshair at the camera target, so the orbit pivot is visible while
+//! orbiting or panning. It is drawn on top of the scene (no depth test), appears at
+//! full opacity while the mouse drags the camera and fades out once the drag ends.
+//! Its size follows the camera distance so it keeps the same size on screen.
+
+use crate::camera::ControllerMode;
+use crate::lib_state::State;
+use crate::model_line::LineVertex;
+use wgpu::util::DeviceExt;
+
+/// Axis colors of the crosshair (X red, Y green, Z blue)
+const AXIS_COLORS: [[f32; 3]; 3] = [[0.9, 0.2, 0.2], [0.2, 0.75, 0.25], [0.2, 0.4, 0.9]];
+
+/// Crosshair at the camera target with its own overlay line pipeline
+pub struct TargetGizmo {
+    pub enabled: bool,
+    pub size: f32,          // Half length of each axis as a fraction of the camera distance
+    pub fade_duration: f32, // Seconds to fade out after orbiting/panning stops
+    opacity: f32,
+    pipeline: wgpu::RenderPipeline,
+    vertex_buffer: wgpu::Buffer,
+}
+
+impl TargetGizmo {
+    /// Build the overlay pipeline and the crosshair vertex buffer
+    ///
+    /// `shared_bind_group_layouts` are the camera, light and globals layouts
+    /// used by every other pipeline.
+    pub fn new(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+        shared_bind_group_layouts: [&wgpu::BindGroupLayout; 3],
+    ) -> Self {
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Target Gizmo Pipeline Layout"),
+            bind_group_layouts: &shared_bind_group_layouts,
+            push_constant_ranges: &[],
+        });
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Target Gizmo Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/line.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Target Gizmo Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vs_main"),
+                buffers: &[LineVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            // Always visible, even inside or behind geometry
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 4, // Enable 4x MSAA for web compatibility
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Target Gizmo Vertex Buffer"),
+            contents: bytemuck::cast_slice(&crosshair_vertices([0.0; 3], 1.0, 0.0)),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            enabled: true,
+            size: 0.05,
+            fade_duration: 0.6,
+            opacity: 0.0,
+            pipeline,
+            vertex_buffer,
+        }
+    }
+}
+
+/// One line per axis through `center`, `half_length` to each side
+fn crosshair_vertices(center: [f32; 3], half_length: f32, opacity: f32) -> [LineVertex; 6] {
+    let mut vertices = [LineVertex::new(center, [0.0; 4]); 6];
+    for (axis, color) in AXIS_COLORS.iter().enumerate() {
+        let color = [color[0], color[1], color[2], opacity];
+        let mut start = center;
+        let mut end = center;
+        start[axis] -= half_length;
+        end[axis] += half_length;
+        vertices[axis * 2] = LineVertex::new(start, color);
+        vertices[axis * 2 + 1] = LineVertex::new(end, color);
+    }
+    vertices
+}
+
+/// Show the gizmo while the mouse orbits or pans the camera and fade it out afterwards
+pub fn update_target_gizmo(state: &mut State, dt: f32) {
+    let controller = &state.camera_controller;
+    let gizmo = &mut state.target_gizmo;
+    if !gizmo.enabled {
+        gizmo.opacity = 0.0;
+        return;
+    }
+
+    // In fly mode the eye, not the target, is the pivot
+    let dragging = controller.mode() == ControllerMode::Orbit && (controller.is_orbiting() || controller.is_panning());
+    gizmo.opacity = if dragging {
+        1.0
+    } else {
+        (gizmo.opacity - dt / gizmo.fade_duration.max(f32::EPSILON)).max(0.0)
+    };
+    if gizmo.opacity <= 0.0 {
+        return;
+    }
+
+    let half_length = state.camera.distance * gizmo.size;
+    let vertices = crosshair_vertices(state.camera.target.into(), half_length, gizmo.opacity);
+    state.queue.write_buffer(&gizmo.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+}
+
+/// Draw the crosshair over the scene while it is visible
+pub fn render_target_gizmo<'a>(state: &'a State, render_pass: &mut wgpu::RenderPass<'a>) {
+    let gizmo = &state.target_gizmo;
+    if !gizmo.enabled || gizmo.opacity <= 0.0 {
+        return;
+    }
+    render_pass.set_pipeline(&gizmo.pipeline);
+    render_pass.set_bind_group(0, &state.camera_bind_group, &[]);
+    render_pass.set_vertex_buffer(0, gizmo.vertex_buffer.slice(..));
+    render_pass.draw(0..6, 0..1);
+}
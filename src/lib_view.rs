@@ -0,0 +1,296 @@
+//! # View Module
+//!
+//! Alternate camera views. The top-down plan view looks straight down the up
+//! axis with an orthographic projection and orbit disabled; the previous 3D
+//! camera is stored so toggling back restores it exactly. Any view can also
+//! switch between perspective and orthographic projection. The near and far
+//! planes follow the depth extent of the scene bounds along the view direction.
+//! The camera and lights can be saved to and restored from a JSON view file.
+//! The scene extents (center and size of the bounds) can be shown as a box with
+//! a crosshair at the center.
+
+use crate::camera::{ProjectionMode, StandardView};
+use crate::lib_render::{LightSource, MAX_LIGHTS};
+use crate::State;
+use cgmath::{Deg, InnerSpace, Point3, Quaternion, Transform};
+use serde::{Deserialize, Serialize};
+
+/// Lower limit of the fitted near plane, when the camera is inside the scene bounds
+const NEAR_PLANE_EPSILON: f32 = 0.01;
+
+/// How many fitted distances away the camera can zoom out after fitting the view
+const FIT_ZOOM_OUT_FACTOR: f32 = 4.0;
+
+/// Toggle between the 3D perspective view and the locked 2D top-down view
+pub fn toggle_top_down_view(state: &mut State) {
+    match state.saved_perspective_camera.take() {
+        Some(camera) => {
+            // Restore the exact 3D camera and controls
+            state.camera = camera;
+            state.projection.mode = ProjectionMode::Perspective;
+            state.camera_controller.set_orbit_locked(false);
+            println!("View: 3D perspective");
+        }
+        None => {
+            // Save the camera where it is, without a transition to resume on return
+            state.camera.stop_animation();
+            state.saved_perspective_camera = Some(state.camera.clone());
+            state.camera.set_top_down();
+            state.projection.mode = orthographic_matching(state);
+            state.camera_controller.set_orbit_locked(true);
+            println!("View: 2D top-down (orbit disabled)");
+        }
+    }
+}
+
+/// Look from a standard direction, unless the top-down view has locked the orbit
+pub fn set_standard_view(state: &mut State, view: StandardView) {
+    if state.saved_perspective_camera.is_none() {
+        state.camera.set_view(view);
+    }
+}
+
+/// Orthographic projection showing the height a perspective view shows at the target
+fn orthographic_matching(state: &State) -> ProjectionMode {
+    ProjectionMode::Orthographic { scale: state.projection.view_height_at(state.camera.distance) }
+}
+
+/// Switch between perspective and orthographic projection, keeping the framing at the target
+///
+/// Going back to perspective moves the eye to the distance that shows the orthographic height.
+pub fn set_orthographic(state: &mut State, orthographic: bool) {
+    match (orthographic, state.projection.mode) {
+        (true, ProjectionMode::Perspective) => state.projection.mode = orthographic_matching(state),
+        (false, ProjectionMode::Orthographic { scale }) => {
+            state.projection.mode = ProjectionMode::Perspective;
+            state.camera.stop_animation();
+            let unit_height = state.projection.view_height_at(1.0);
+            state.camera.distance = state.camera_controller.clamp_zoom(scale / unit_height);
+            state.camera.update_position();
+        }
+        _ => {}
+    }
+}
+
+/// Aim the camera at the center of the drawn geometry, at the distance where it fills the view
+///
+/// Fits the sphere around the bounds of the meshes (with their instances), the point cloud,
+/// the pipes and the polygon sets into the narrower of the two fields of view, or into the
+/// orthographic height, keeping the view direction. The farthest zoom distance grows to a few
+/// times the fitted distance for models too large for it, and the distance stays within the
+/// zoom limits. Returns false, leaving the camera untouched, when nothing is loaded.
+pub fn fit_view_to_scene(state: &mut State) -> bool {
+    let Some((min, max)) = crate::lib_render::drawn_geometry_bounds(state) else {
+        return false;
+    };
+    let center = Point3::new((min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5, (min[2] + max[2]) * 0.5);
+    let radius = (Point3::from(max) - center).magnitude().max(NEAR_PLANE_EPSILON);
+
+    let projection = &state.projection;
+    let half_fovy = projection.fovy.0 * 0.5;
+    let half_fovx = (half_fovy.tan() * projection.aspect).atan();
+    let distance = radius / half_fovy.min(half_fovx).sin();
+    if projection.is_orthographic() {
+        state.projection.mode = ProjectionMode::Orthographic { scale: 2.0 * radius * (1.0 / projection.aspect).max(1.0) };
+    }
+
+    let controller = &mut state.camera_controller;
+    if distance * FIT_ZOOM_OUT_FACTOR > controller.max_zoom() {
+        controller.set_max_zoom(distance * FIT_ZOOM_OUT_FACTOR);
+    }
+
+    state.camera.stop_animation();
+    state.camera.target = center;
+    state.camera.distance = state.camera_controller.clamp_zoom(distance);
+    state.camera.update_position();
+    true
+}
+
+/// Fit the projection's near and far planes to the scene bounds as seen from the current camera
+///
+/// Uses the view-space depth of the 8 corners of the loaded geometry bounds (joined with
+/// the reference grid): `znear = max(min_depth * 0.5, epsilon)`, `zfar = max_depth * 2`.
+pub fn fit_near_far(state: &mut State) {
+    let (grid_min, grid_max) = crate::geometry_generator::grid_bounds(state.settings.grid_height);
+    let (min, max) = match state.scene_bounds {
+        Some((min, max)) => (
+            [min[0].min(grid_min[0]), min[1].min(grid_min[1]), min[2].min(grid_min[2])],
+            [max[0].max(grid_max[0]), max[1].max(grid_max[1]), max[2].max(grid_max[2])],
+        ),
+        None => (grid_min, grid_max),
+    };
+
+    let view = state.camera.calc_matrix();
+    let mut min_depth = f32::MAX;
+    let mut max_depth = f32::MIN;
+    for i in 0..8 {
+        let corner = Point3::new(
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        );
+        // The camera looks down -Z in view space
+        let depth = -view.transform_point(corner).z;
+        min_depth = min_depth.min(depth);
+        max_depth = max_depth.max(depth);
+    }
+
+    // Everything is behind the camera, keep the current planes
+    if max_depth <= 0.0 {
+        return;
+    }
+    state.projection.set_near_far((min_depth * 0.5).max(NEAR_PLANE_EPSILON), max_depth * 2.0);
+}
+
+/// Color of the scene extents box and center marker
+const SCENE_EXTENTS_COLOR: [f32; 4] = [1.0, 0.55, 0.0, 1.0];
+
+/// Center and size of the loaded geometry bounds, `None` before any geometry is loaded
+pub fn scene_extents(state: &State) -> Option<(Point3<f32>, [f32; 3])> {
+    let (min, max) = state.scene_bounds?;
+    let center = Point3::new((min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5, (min[2] + max[2]) * 0.5);
+    Some((center, [max[0] - min[0], max[1] - min[1], max[2] - min[2]]))
+}
+
+/// Show or hide the scene extents; showing them also prints the center and size
+pub fn set_show_scene_extents(state: &mut State, show: bool) {
+    state.settings.show_scene_extents = show;
+    if !show {
+        return;
+    }
+    match scene_extents(state) {
+        Some((center, size)) => println!(
+            "Scene center ({:.3}, {:.3}, {:.3}), size {:.3} x {:.3} x {:.3}",
+            center.x, center.y, center.z, size[0], size[1], size[2]
+        ),
+        None => println!("No geometry loaded"),
+    }
+}
+
+/// Queue the bounds box and a crosshair at its center for this frame, while shown
+pub fn draw_scene_extents(state: &mut State) {
+    let (true, Some((min, max))) = (state.settings.show_scene_extents, state.scene_bounds) else {
+        return;
+    };
+    let corner = |i: usize| [
+        if i & 1 == 0 { min[0] } else { max[0] },
+        if i & 2 == 0 { min[1] } else { max[1] },
+        if i & 4 == 0 { min[2] } else { max[2] },
+    ];
+    // Corners differing in one bit share an edge
+    for i in 0..8 {
+        for bit in [1, 2, 4] {
+            if i & bit == 0 {
+                state.debug_draw.line(corner(i), corner(i | bit), SCENE_EXTENTS_COLOR);
+            }
+        }
+    }
+
+    let center: [f32; 3] = std::array::from_fn(|axis| (min[axis] + max[axis]) * 0.5);
+    let diagonal = (0..3).map(|axis| (max[axis] - min[axis]).powi(2)).sum::<f32>().sqrt();
+    let half_length = diagonal * 0.05;
+    for axis in 0..3 {
+        let (mut a, mut b) = (center, center);
+        a[axis] -= half_length;
+        b[axis] += half_length;
+        state.debug_draw.line(a, b, SCENE_EXTENTS_COLOR);
+    }
+}
+
+/// Camera and light setup saved to a view file, for exactly reproducible renders
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ViewSettings {
+    pub camera: CameraSettings,
+    pub lights: Vec<LightSettings>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CameraSettings {
+    pub position: [f32; 3],
+    pub target: [f32; 3],
+    pub orientation: [f32; 4], // Quaternion as [x, y, z, w]
+    pub distance: f32,
+    pub fovy_degrees: f32,
+    pub znear: f32,
+    pub zfar: f32,
+    pub auto_near_far: bool, // When set, znear/zfar are refitted to the scene every frame
+    pub clip_plane: Option<[f32; 4]>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LightSettings {
+    pub position: [f32; 3],
+    pub color: [f32; 3],
+}
+
+/// Current camera and lights; the 3D camera is saved while the top-down view is active
+pub fn view_settings(state: &State) -> ViewSettings {
+    let camera = state.saved_perspective_camera.as_ref().unwrap_or(&state.camera);
+    let orientation = camera.orientation;
+    ViewSettings {
+        camera: CameraSettings {
+            position: camera.position.into(),
+            target: camera.target.into(),
+            orientation: [orientation.v.x, orientation.v.y, orientation.v.z, orientation.s],
+            distance: camera.distance,
+            fovy_degrees: Deg::from(state.projection.fovy).0,
+            znear: state.projection.znear,
+            zfar: state.projection.zfar,
+            auto_near_far: state.settings.auto_near_far,
+            clip_plane: state.settings.clip_plane,
+        },
+        lights: state
+            .light_uniform
+            .active()
+            .iter()
+            .map(|light| LightSettings { position: light.position, color: light.color })
+            .collect(),
+    }
+}
+
+/// Restore a saved camera and lights, leaving the top-down view if it is active
+pub fn apply_view_settings(state: &mut State, settings: &ViewSettings) -> Result<(), String> {
+    if settings.lights.len() > MAX_LIGHTS {
+        return Err(format!("View has {} lights, at most {} are supported", settings.lights.len(), MAX_LIGHTS));
+    }
+
+    if state.saved_perspective_camera.take().is_some() {
+        state.projection.mode = ProjectionMode::Perspective;
+        state.camera_controller.set_orbit_locked(false);
+    }
+    let camera = &settings.camera;
+    let [x, y, z, w] = camera.orientation;
+    state.camera.reset_to(
+        camera.position.into(),
+        camera.target.into(),
+        Quaternion::new(w, x, y, z),
+        camera.distance,
+    );
+    state.projection.fovy = Deg(camera.fovy_degrees).into();
+    state.projection.set_near_far(camera.znear, camera.zfar);
+    state.settings.auto_near_far = camera.auto_near_far;
+    state.settings.clip_plane = camera.clip_plane;
+
+    state.light_uniform.count = 0;
+    for light in &settings.lights {
+        state.light_uniform.add(LightSource::new(light.position, light.color));
+    }
+    state.queue.write_buffer(&state.light_buffer, 0, bytemuck::cast_slice(&[state.light_uniform]));
+    Ok(())
+}
+
+/// Write the current view to a JSON file (native only)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export_view(state: &State, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string_pretty(&view_settings(state))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Restore the view saved in a JSON file (native only)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn import_view(state: &mut State, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let settings: ViewSettings = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    apply_view_settings(state, &settings)?;
+    Ok(())
+}
@@ -19,19 +19,13 @@
 
 #[path = "model_mesh.rs"]
 pub mod model_mesh;
-#[path = "model_point.rs"]
-pub mod model_point;
-#[path = "model_line.rs"]
-pub mod model_line;
-#[path = "model_pipe.rs"]
-pub mod model_pipe;
-#[path = "model_polygon.rs"]
-pub mod model_polygon;
+// The other model types are crate modules of their own, re-exported here
+pub(crate) use crate::{model_line, model_pipe, model_point, model_polygon};
 
 // Re-export all model types and traits
-pub use model_mesh::{ModelVertex, Mesh, Model, DrawModel, DrawLight, Vertex};
+pub use model_mesh::{ModelVertex, Mesh, Model, ModelInstances, ModelOpacity, ModelOutline, ModelWireframe, DrawModel, DrawLight, Vertex, create_edge_index_buffer, vertex_bounds};
 pub use model_point::{PointModel};
-pub use model_line::{LineVertex, LineModel};
+pub use model_line::LineModel;
 pub use model_pipe::{PipeModel};
 pub use model_polygon::{PolygonModel};
 
@@ -56,6 +50,37 @@ pub enum OpenModelGeometry {
     Pline(OpenModelPline),
 }
 
+/// Cast an f64 position to f32 after subtracting `origin_offset` in f64
+///
+/// Used by every OpenModel conversion, so large (e.g. geo-referenced) coordinates keep
+/// their precision instead of being truncated; `[0.0; 3]` casts them unchanged.
+pub fn local_position(position: [f64; 3], origin_offset: [f64; 3]) -> [f32; 3] {
+    std::array::from_fn(|i| (position[i] - origin_offset[i]) as f32)
+}
+
+/// Origin offset for OpenModel geometries, like `GeometryData::origin_offset` for JSON files
+///
+/// The center of the f64 bounds of all positions when any coordinate is beyond
+/// `geometry_loader::RECENTER_THRESHOLD`, otherwise zero.
+pub fn openmodel_origin_offset(geometries: &[OpenModelGeometry]) -> [f64; 3] {
+    let mut min = [f64::MAX; 3];
+    let mut max = [f64::MIN; 3];
+    for geometry in geometries {
+        geometry.for_each_position(|position| {
+            for i in 0..3 {
+                min[i] = min[i].min(position[i]);
+                max[i] = max[i].max(position[i]);
+            }
+        });
+    }
+    let threshold = crate::geometry_loader::RECENTER_THRESHOLD as f64;
+    let far = min.iter().chain(&max).any(|coordinate| coordinate.abs() > threshold);
+    if min[0] > max[0] || !far {
+        return [0.0; 3];
+    }
+    std::array::from_fn(|i| (min[i] + max[i]) * 0.5)
+}
+
 /// Unified model creation from OpenModel geometries
 /// This provides a high-level interface for creating GPU models from OpenModel data
 #[allow(dead_code)]
@@ -64,12 +89,16 @@ pub struct UnifiedModelFactory;
 impl UnifiedModelFactory {
     /// Create appropriate GPU models from a collection of mixed OpenModel geometries
     /// Returns separate model collections for each geometry type
+    ///
+    /// Far-away geometries are recentered in f64 before the f32 cast; the collection's
+    /// `origin_offset` adds the original coordinates back.
     #[allow(dead_code)]
     pub fn create_models_from_openmodel_geometries(
         device: &wgpu::Device,
         name_prefix: &str,
         geometries: &[OpenModelGeometry],
     ) -> UnifiedModelCollection {
+        let origin_offset = openmodel_origin_offset(geometries);
         let mut point_models = Vec::new();
         let mut line_models = Vec::new();
         let mut mesh_models = Vec::new();
@@ -81,23 +110,23 @@ impl UnifiedModelFactory {
             
             match geometry {
                 OpenModelGeometry::Point(point) => {
-                    let model = PointModel::from_openmodel_points(device, &model_name, &[point.clone()]);
+                    let model = PointModel::from_openmodel_points(device, &model_name, &[point.clone()], origin_offset);
                     point_models.push(model);
                 },
                 OpenModelGeometry::PointCloud(pointcloud) => {
-                    let model = PointModel::from_openmodel_pointcloud(device, &model_name, pointcloud);
+                    let model = PointModel::from_openmodel_pointcloud(device, &model_name, pointcloud, origin_offset);
                     point_models.push(model);
                 },
                 OpenModelGeometry::Line(line) => {
-                    let model = LineModel::from_openmodel_line(device, &model_name, line);
+                    let model = LineModel::from_openmodel_line(device, &model_name, line, origin_offset);
                     line_models.push(model);
                 },
                 OpenModelGeometry::Mesh(mesh) => {
-                    let model = Model::from_openmodel_mesh(device, &model_name, mesh);
+                    let model = Model::from_openmodel_mesh(device, &model_name, mesh, origin_offset);
                     mesh_models.push(model);
                 },
                 OpenModelGeometry::Pline(pline) => {
-                    let model = PolygonModel::from_openmodel_pline(device, &model_name, pline);
+                    let model = PolygonModel::from_openmodel_pline(device, &model_name, pline, origin_offset);
                     polygon_models.push(model);
                 },
             }
@@ -109,17 +138,19 @@ impl UnifiedModelFactory {
             mesh_models,
             pipe_models,
             polygon_models,
+            origin_offset,
         }
     }
     
-    /// Create pipe models from OpenModel lines with automatic mesh generation
+    /// Create pipe models from OpenModel lines with automatic mesh generation, relative to `origin_offset`
     #[allow(dead_code)]
     pub fn create_pipe_models_from_openmodel_lines(
         device: &wgpu::Device,
         name: &str,
         lines: &[OpenModelLine],
+        origin_offset: [f64; 3],
     ) -> Vec<PipeModel> {
-        vec![PipeModel::from_openmodel_lines(device, name, lines)]
+        vec![PipeModel::from_openmodel_lines(device, name, lines, origin_offset)]
     }
 }
 
@@ -131,6 +162,7 @@ pub struct UnifiedModelCollection {
     pub mesh_models: Vec<Model>,
     pub pipe_models: Vec<PipeModel>,
     pub polygon_models: Vec<PolygonModel>,
+    pub origin_offset: [f64; 3], // Subtracted from every position before the f32 cast; add it back for original coordinates
 }
 
 impl OpenModelGeometry {
@@ -146,6 +178,28 @@ impl OpenModelGeometry {
         }
     }
     
+    /// Call `visit` with every position in full f64 precision
+    pub fn for_each_position(&self, mut visit: impl FnMut([f64; 3])) {
+        match self {
+            OpenModelGeometry::Point(point) => visit([point.x, point.y, point.z]),
+            OpenModelGeometry::PointCloud(pointcloud) => {
+                pointcloud.points.iter().for_each(|point| visit([point.x, point.y, point.z]))
+            }
+            OpenModelGeometry::Line(line) => {
+                visit([line.x0, line.y0, line.z0]);
+                visit([line.x1, line.y1, line.z1]);
+            }
+            OpenModelGeometry::Mesh(mesh) => {
+                for (_face_key, face_vertices) in mesh.get_face_data() {
+                    for position in face_vertices.iter().filter_map(|&vertex_key| mesh.vertex_position(vertex_key)) {
+                        visit([position.x, position.y, position.z]);
+                    }
+                }
+            }
+            OpenModelGeometry::Pline(pline) => pline.points.iter().for_each(|point| visit([point.x, point.y, point.z])),
+        }
+    }
+
     /// Check if the geometry has color information
     #[allow(dead_code)]
     pub fn has_color(&self) -> bool {
@@ -174,3 +228,28 @@ impl OpenModelGeometry {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn far_openmodel_geometry_is_recentered_before_the_f32_cast() {
+        let geometries = [
+            OpenModelGeometry::Point(OpenModelPoint::new(2_600_000.25, 1_200_000.5, 400.0)),
+            OpenModelGeometry::Point(OpenModelPoint::new(2_600_010.25, 1_200_020.5, 410.0)),
+        ];
+        let origin_offset = openmodel_origin_offset(&geometries);
+        assert_eq!(origin_offset, [2_600_005.25, 1_200_010.5, 405.0]);
+
+        // The quarter and half units would be lost casting the raw coordinates to f32
+        assert_eq!(local_position([2_600_000.25, 1_200_000.5, 400.0], origin_offset), [-5.0, -10.0, -5.0]);
+    }
+
+    #[test]
+    fn near_openmodel_geometry_keeps_its_coordinates() {
+        let geometries = [OpenModelGeometry::Point(OpenModelPoint::new(1.0, 2.0, 3.0))];
+        assert_eq!(openmodel_origin_offset(&geometries), [0.0; 3]);
+        assert_eq!(openmodel_origin_offset(&[]), [0.0; 3]);
+    }
+}
@@ -6,20 +6,27 @@
 //!
 //! Key components:
 //! - `LineVertex`: GPU vertex structure for lines with position and color
-//! - `LineModel`: A collection of lines with rendering properties
+//! - `LineModel`: A collection of lines with rendering properties, optionally indexed so
+//!   connected segments share their vertices
 //! - `DrawLines` trait: Rendering abstraction for line collections
+//! - `WideLineVertex` / `WideLineModel`: Screen-space wide lines expanded to quads in the shader
 //! - OpenModel integration: Bridge between OpenModel Line and GPU structures
 
 use wgpu::util::DeviceExt;
+use crate::lib_pipeline::BlendMode;
+use crate::model::local_position;
 use openmodel::geometry::Line as OpenModelLine;
 use openmodel::primitives::Color as OpenModelColor;
 
+// Configuration constants
+pub const LINE_WIDTH: f32 = 1.5;  // Default screen-space line width in pixels
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 #[allow(dead_code)]
 pub struct LineVertex {
     pub position: [f32; 3],
-    pub color: [f32; 3],
+    pub color: [f32; 4], // RGBA, alpha 1.0 is opaque
 }
 
 impl LineVertex {
@@ -38,7 +45,7 @@ impl LineVertex {
                 wgpu::VertexAttribute {
                     offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
                     shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3,
+                    format: wgpu::VertexFormat::Float32x4,
                 },
             ],
         }
@@ -46,7 +53,7 @@ impl LineVertex {
 
     /// Create a LineVertex from position and color
     #[allow(dead_code)]
-    pub fn new(position: [f32; 3], color: [f32; 3]) -> Self {
+    pub fn new(position: [f32; 3], color: [f32; 4]) -> Self {
         LineVertex { position, color }
     }
 }
@@ -55,6 +62,9 @@ pub struct LineModel {
     pub _name: String, // Using underscore to indicate unused field
     pub vertex_buffer: wgpu::Buffer,
     pub num_vertices: u32,
+    pub index_buffer: Option<wgpu::Buffer>, // Pairs of vertex indices per segment; `None` draws the vertices in order
+    pub num_indices: u32,
+    pub blend_mode: BlendMode, // Selects the line pipeline variant
 }
 
 impl LineModel {
@@ -70,55 +80,76 @@ impl LineModel {
             _name: String::from(name),
             vertex_buffer,
             num_vertices: vertices.len() as u32,
+            index_buffer: None,
+            num_indices: 0,
+            blend_mode: BlendMode::default(),
+        }
+    }
+
+    /// Create a LineModel whose segments are given as pairs of indices into `vertices`
+    ///
+    /// Polylines and meshes of edges then store every shared vertex once.
+    pub fn new_indexed(device: &wgpu::Device, name: &str, vertices: &[LineVertex], indices: &[u32]) -> Self {
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} Line Index Buffer", name)),
+            contents: bytemuck::cast_slice(indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        Self {
+            index_buffer: Some(index_buffer),
+            num_indices: indices.len() as u32,
+            ..Self::new(device, name, vertices)
         }
     }
 
-    /// Create a LineModel from an OpenModel Line with default color
+    /// Create a LineModel from an OpenModel Line with default color, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_line(device: &wgpu::Device, name: &str, line: &OpenModelLine) -> Self {
+    pub fn from_openmodel_line(device: &wgpu::Device, name: &str, line: &OpenModelLine, origin_offset: [f64; 3]) -> Self {
         let color = if line.data.has_color() {
             let color_data = line.data.get_color();
-            [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0]
+            [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0, 1.0]
         } else {
-            [1.0, 1.0, 1.0] // Default white color
+            [1.0, 1.0, 1.0, 1.0] // Default opaque white color
         };
 
         let vertices = vec![
-            LineVertex::new([line.x0 as f32, line.y0 as f32, line.z0 as f32], color),
-            LineVertex::new([line.x1 as f32, line.y1 as f32, line.z1 as f32], color),
+            LineVertex::new(local_position([line.x0, line.y0, line.z0], origin_offset), color),
+            LineVertex::new(local_position([line.x1, line.y1, line.z1], origin_offset), color),
         ];
 
         Self::new(device, name, &vertices)
     }
 
-    /// Create a LineModel from a collection of OpenModel Lines
+    /// Create a LineModel from a collection of OpenModel Lines, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_lines(device: &wgpu::Device, name: &str, lines: &[OpenModelLine]) -> Self {
+    pub fn from_openmodel_lines(device: &wgpu::Device, name: &str, lines: &[OpenModelLine], origin_offset: [f64; 3]) -> Self {
         let mut vertices = Vec::new();
 
         for line in lines {
             let color = if line.data.has_color() {
                 let color_data = line.data.get_color();
-                [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0]
+                [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0, 1.0]
             } else {
-                [1.0, 1.0, 1.0] // Default white color
+                [1.0, 1.0, 1.0, 1.0] // Default opaque white color
             };
 
-            vertices.push(LineVertex::new([line.x0 as f32, line.y0 as f32, line.z0 as f32], color));
-            vertices.push(LineVertex::new([line.x1 as f32, line.y1 as f32, line.z1 as f32], color));
+            vertices.push(LineVertex::new(local_position([line.x0, line.y0, line.z0], origin_offset), color));
+            vertices.push(LineVertex::new(local_position([line.x1, line.y1, line.z1], origin_offset), color));
         }
 
         Self::new(device, name, &vertices)
     }
 
-    /// Create a LineModel from an OpenModel Line with specified color
+    /// Create a LineModel from an OpenModel Line with specified color, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_line_with_color(device: &wgpu::Device, name: &str, line: &OpenModelLine, color: &OpenModelColor) -> Self {
-        let color_array = [color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0];
+    pub fn from_openmodel_line_with_color(device: &wgpu::Device, name: &str, line: &OpenModelLine, color: &OpenModelColor, origin_offset: [f64; 3]) -> Self {
+        let (r, g, b, a) = color.to_float();
+        let color_array = [r, g, b, a];
         
         let vertices = vec![
-            LineVertex::new([line.x0 as f32, line.y0 as f32, line.z0 as f32], color_array),
-            LineVertex::new([line.x1 as f32, line.y1 as f32, line.z1 as f32], color_array),
+            LineVertex::new(local_position([line.x0, line.y0, line.z0], origin_offset), color_array),
+            LineVertex::new(local_position([line.x1, line.y1, line.z1], origin_offset), color_array),
         ];
 
         Self::new(device, name, &vertices)
@@ -134,10 +165,7 @@ pub trait DrawLines<'a> {
     );
 }
 
-impl<'a, 'b> DrawLines<'b> for wgpu::RenderPass<'a>
-where
-    'b: 'a,
-{
+impl<'a, 'b> DrawLines<'b> for wgpu::RenderPass<'a> {
     fn draw_lines(
         &mut self,
         line_model: &'b LineModel,
@@ -145,6 +173,178 @@ where
     ) {
         self.set_vertex_buffer(0, line_model.vertex_buffer.slice(..));
         self.set_bind_group(0, camera_bind_group, &[]);
-        self.draw(0..line_model.num_vertices, 0..1);
+        match &line_model.index_buffer {
+            Some(index_buffer) => {
+                self.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                self.draw_indexed(0..line_model.num_indices, 0, 0..1);
+            }
+            None => self.draw(0..line_model.num_vertices, 0..1),
+        }
+    }
+}
+
+/// Vertex for screen-space wide lines.
+///
+/// Every segment becomes a quad of four vertices that all carry both segment
+/// endpoints; `corner.x` selects the endpoint (0 = start, 1 = end) and `corner.y`
+/// the side of the line (-1 or 1). The vertex shader offsets each corner
+/// perpendicular to the projected segment by half of `width` pixels.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct WideLineVertex {
+    pub start: [f32; 3],
+    pub end: [f32; 3],
+    pub color: [f32; 4],
+    pub corner: [f32; 2],
+    pub width: f32,
+}
+
+impl WideLineVertex {
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        use std::mem;
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<WideLineVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                // start
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // end
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // color
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                // corner
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 10]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                // width
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 12]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32,
+                },
+            ],
+        }
+    }
+}
+
+/// Line segments rendered as screen-space quads with a fixed pixel width
+pub struct WideLineModel {
+    pub _name: String,
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub num_indices: u32,
+    pub blend_mode: BlendMode, // Selects the wide line pipeline variant
+}
+
+impl WideLineModel {
+    /// Create a WideLineModel from line-list vertices (two vertices per segment)
+    pub fn new(device: &wgpu::Device, name: &str, vertices: &[LineVertex], width: f32) -> Self {
+        let segments = vertices.chunks_exact(2).map(|segment| (&segment[0], &segment[1]));
+        Self::from_segments(device, name, segments, width)
+    }
+
+    /// Create a WideLineModel from segments given as pairs of indices into `vertices`
+    pub fn new_indexed(device: &wgpu::Device, name: &str, vertices: &[LineVertex], indices: &[u32], width: f32) -> Self {
+        let segments = indices
+            .chunks_exact(2)
+            .map(|pair| (&vertices[pair[0] as usize], &vertices[pair[1] as usize]));
+        Self::from_segments(device, name, segments, width)
+    }
+
+    /// Upload the quads of `segments`, see `wide_line_quads`
+    fn from_segments<'v>(
+        device: &wgpu::Device,
+        name: &str,
+        segments: impl ExactSizeIterator<Item = (&'v LineVertex, &'v LineVertex)>,
+        width: f32,
+    ) -> Self {
+        let (quad_vertices, indices) = wide_line_quads(segments, width);
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} Wide Line Vertex Buffer", name)),
+            contents: bytemuck::cast_slice(&quad_vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} Wide Line Index Buffer", name)),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        Self {
+            _name: String::from(name),
+            vertex_buffer,
+            index_buffer,
+            num_indices: indices.len() as u32,
+            blend_mode: BlendMode::default(),
+        }
+    }
+}
+
+/// Expand every segment into a quad of four vertices and two triangles, all `width` pixels wide
+pub fn wide_line_quads<'v>(
+    segments: impl ExactSizeIterator<Item = (&'v LineVertex, &'v LineVertex)>,
+    width: f32,
+) -> (Vec<WideLineVertex>, Vec<u32>) {
+    let segment_count = segments.len();
+    let mut quad_vertices = Vec::with_capacity(segment_count * 4);
+    let mut indices: Vec<u32> = Vec::with_capacity(segment_count * 6);
+
+    for (i, (start, end)) in segments.enumerate() {
+        let corners = [
+            ([0.0, -1.0], start.color),
+            ([0.0, 1.0], start.color),
+            ([1.0, -1.0], end.color),
+            ([1.0, 1.0], end.color),
+        ];
+        for (corner, color) in corners {
+            quad_vertices.push(WideLineVertex {
+                start: start.position,
+                end: end.position,
+                color,
+                corner,
+                width,
+            });
+        }
+
+        let base = (i * 4) as u32;
+        indices.extend_from_slice(&[base, base + 2, base + 1, base + 1, base + 2, base + 3]);
+    }
+    (quad_vertices, indices)
+}
+
+pub trait DrawWideLines<'a> {
+    fn draw_wide_lines(
+        &mut self,
+        wide_line_model: &'a WideLineModel,
+        camera_bind_group: &'a wgpu::BindGroup,
+    );
+}
+
+impl<'a, 'b: 'a> DrawWideLines<'a> for wgpu::RenderPass<'b> {
+    fn draw_wide_lines(
+        &mut self,
+        wide_line_model: &'a WideLineModel,
+        camera_bind_group: &'a wgpu::BindGroup,
+    ) {
+        self.set_vertex_buffer(0, wide_line_model.vertex_buffer.slice(..));
+        self.set_index_buffer(wide_line_model.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        self.set_bind_group(0, camera_bind_group, &[]);
+        self.draw_indexed(0..wide_line_model.num_indices, 0, 0..1);
     }
 }
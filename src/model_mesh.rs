@@ -7,13 +7,16 @@
 //! Key components:
 //! - `ModelVertex`: GPU vertex structure for 3D meshes with positions, normals, etc.
 //! - `Material`: Represents surface properties with texture maps
-//! - `Mesh`: A single mesh with vertices and indices
-//! - `Model`: A collection of meshes with materials and edge visualization
+//! - `Mesh`: A single mesh with vertices, indices and wireframe edge indices
+//! - `Model`: A collection of meshes with materials, edge visualization and an optional wireframe overlay
+//! - `ModelInstances`: Per-model instance list and buffer for drawing many copies of a model
 //! - `DrawModel` & `DrawLight` traits: Rendering abstractions for meshes
 //! - OpenModel integration: Bridge between OpenModel Mesh and GPU structures
 
 use wgpu::util::DeviceExt;
 use openmodel::geometry::Mesh as OpenModelMesh;
+use super::local_position;
+use crate::instance::Instance;
 
 // Texture module no longer used
 
@@ -86,15 +89,100 @@ pub struct Mesh {
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub num_elements: u32,
+    pub edge_index_buffer: wgpu::Buffer, // Unique triangle edges as a line list, for the wireframe overlay
+    pub num_edge_elements: u32,
+    pub bounds: Option<([f32; 3], [f32; 3])>, // Axis-aligned bounds (min, max) of the vertices, None when empty
     // material field removed - not needed for texture-free pipeline
 }
 
 pub struct Model {
     pub meshes: Vec<Mesh>,
     pub edge_meshes: Vec<Mesh>, // Edge visualization as pipes
+    pub show_wireframe: bool,   // Draw the triangle edges on top of the shaded surface
+    pub wireframe: Option<ModelWireframe>, // Created the first time the wireframe is enabled
+    pub instances: Option<ModelInstances>, // Own placements; None draws with the shared instance buffer
+    pub opacity: Option<ModelOpacity>, // Set below 1 to draw the model blended in the transparent phase
+    pub outline: Option<ModelOutline>, // Selection outline around the silhouette
     // materials field removed - not needed for texture-free pipeline
 }
 
+/// Placements of a model drawn with instancing, bound at vertex buffer slot 1
+pub struct ModelInstances {
+    pub instances: Vec<Instance>,
+    pub buffer: wgpu::Buffer,
+}
+
+impl ModelInstances {
+    pub fn new(device: &wgpu::Device, instances: Vec<Instance>) -> Self {
+        let instance_data = instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Model Instance Buffer"),
+            contents: bytemuck::cast_slice(&instance_data),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Self { instances, buffer }
+    }
+}
+
+/// Color uniform of a model's wireframe overlay, bound at group 3 of the wireframe pipeline
+pub struct ModelWireframe {
+    pub color: [f32; 4],
+    pub color_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+/// Color and pixel width of a model's selection outline, bound at group 3 of the outline pipeline
+pub struct ModelOutline {
+    pub color: [f32; 4],
+    pub width: f32,
+    pub uniform_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+/// Opacity uniform of a translucent model, bound at group 3 of the transparent mesh pipeline
+///
+/// Shares the layout of the wireframe color; only the alpha channel is used.
+pub struct ModelOpacity {
+    pub opacity: f32,
+    pub color_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+/// Axis-aligned bounds (min, max) of the vertex positions, `None` when there are none
+pub fn vertex_bounds(vertices: &[ModelVertex]) -> Option<([f32; 3], [f32; 3])> {
+    vertices.iter().fold(None, |bounds, vertex| {
+        let (mut min, mut max) = bounds.unwrap_or((vertex.position, vertex.position));
+        for i in 0..3 {
+            min[i] = min[i].min(vertex.position[i]);
+            max[i] = max[i].max(vertex.position[i]);
+        }
+        Some((min, max))
+    })
+}
+
+/// Unique undirected edges of a triangle list, as line list indices
+pub fn triangle_edge_indices(indices: &[u32]) -> Vec<u32> {
+    let mut edges: Vec<(u32, u32)> = indices
+        .chunks_exact(3)
+        .flat_map(|t| [(t[0], t[1]), (t[1], t[2]), (t[2], t[0])])
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    edges.into_iter().flat_map(|(a, b)| [a, b]).collect()
+}
+
+/// Create the wireframe index buffer for a triangle list; returns the buffer and its index count
+pub fn create_edge_index_buffer(device: &wgpu::Device, name: &str, indices: &[u32]) -> (wgpu::Buffer, u32) {
+    let edge_indices = triangle_edge_indices(indices);
+    let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some(&format!("{} Edge Index Buffer", name)),
+        contents: bytemuck::cast_slice(&edge_indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+    (buffer, edge_indices.len() as u32)
+}
+
 impl Mesh {
     /// Create a new Mesh from vertices and indices
     pub fn new(device: &wgpu::Device, name: &str, vertices: &[ModelVertex], indices: &[u32]) -> Self {
@@ -109,22 +197,26 @@ impl Mesh {
             contents: bytemuck::cast_slice(indices),
             usage: wgpu::BufferUsages::INDEX,
         });
+        let (edge_index_buffer, num_edge_elements) = create_edge_index_buffer(device, name, indices);
 
         Self {
             _name: name.to_string(),
             vertex_buffer,
             index_buffer,
             num_elements: indices.len() as u32,
+            edge_index_buffer,
+            num_edge_elements,
+            bounds: vertex_bounds(vertices),
         }
     }
 
-    /// Create a Mesh from an OpenModel Mesh
-    pub fn from_openmodel_mesh(device: &wgpu::Device, name: &str, openmodel_mesh: &OpenModelMesh) -> Self {
-        Self::from_openmodel_mesh_with_color(device, name, openmodel_mesh, [1.0, 1.0, 1.0]) // Default white
+    /// Create a Mesh from an OpenModel Mesh, with positions relative to `origin_offset`
+    pub fn from_openmodel_mesh(device: &wgpu::Device, name: &str, openmodel_mesh: &OpenModelMesh, origin_offset: [f64; 3]) -> Self {
+        Self::from_openmodel_mesh_with_color(device, name, openmodel_mesh, [1.0, 1.0, 1.0], origin_offset) // Default white
     }
 
-    /// Create a Mesh from an OpenModel Mesh with specified color
-    pub fn from_openmodel_mesh_with_color(device: &wgpu::Device, name: &str, openmodel_mesh: &OpenModelMesh, color: [f32; 3]) -> Self {
+    /// Create a Mesh from an OpenModel Mesh with specified color, with positions relative to `origin_offset`
+    pub fn from_openmodel_mesh_with_color(device: &wgpu::Device, name: &str, openmodel_mesh: &OpenModelMesh, color: [f32; 3], origin_offset: [f64; 3]) -> Self {
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
         let mut vertex_map = std::collections::HashMap::new();
@@ -153,7 +245,7 @@ impl Mesh {
                                     .unwrap_or([0.0, 0.0, 1.0]);
 
                                 let model_vertex = ModelVertex {
-                                    position: [position.x as f32, position.y as f32, position.z as f32],
+                                    position: local_position([position.x, position.y, position.z], origin_offset),
                                     tex_coords: [0.0, 0.0], // Default texture coordinates
                                     normal,
                                     tangent: [1.0, 0.0, 0.0], // Default tangent
@@ -179,43 +271,69 @@ impl Mesh {
 impl Model {
     /// Create a new Model from a collection of meshes
     pub fn new(meshes: Vec<Mesh>) -> Self {
-        Self { 
-            meshes,
-            edge_meshes: Vec::new(),
-        }
+        Self::with_edges(meshes, Vec::new())
     }
 
-    /// Create a Model from an OpenModel Mesh (single mesh)
-    pub fn from_openmodel_mesh(device: &wgpu::Device, name: &str, openmodel_mesh: &OpenModelMesh) -> Self {
-        let mesh = Mesh::from_openmodel_mesh(device, name, openmodel_mesh);
-        let edge_meshes = Self::create_edge_meshes(device, openmodel_mesh);
-        Self { 
-            meshes: vec![mesh],
+    /// Create a Model from surface meshes and pipe edge meshes
+    pub fn with_edges(meshes: Vec<Mesh>, edge_meshes: Vec<Mesh>) -> Self {
+        Self {
+            meshes,
             edge_meshes,
+            show_wireframe: false,
+            wireframe: None,
+            instances: None,
+            opacity: None,
+            outline: None,
         }
     }
 
-    /// Create a Model from multiple OpenModel Meshes
-    pub fn from_openmodel_meshes(device: &wgpu::Device, openmodel_meshes: &[(String, OpenModelMesh)]) -> Self {
+    /// Whether the model is drawn blended, back to front with the other translucent geometry
+    pub fn is_transparent(&self) -> bool {
+        self.opacity.as_ref().is_some_and(|opacity| opacity.opacity < 1.0)
+    }
+
+    /// Axis-aligned bounds of the surface meshes in model space, `None` when there are none
+    pub fn bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        self.meshes.iter().filter_map(|mesh| mesh.bounds).reduce(|(min_a, max_a), (min_b, max_b)| {
+            (
+                std::array::from_fn(|i| min_a[i].min(min_b[i])),
+                std::array::from_fn(|i| max_a[i].max(max_b[i])),
+            )
+        })
+    }
+
+    /// Center of the surface bounds in model space, used to sort translucent models
+    pub fn center(&self) -> [f32; 3] {
+        self.bounds()
+            .map(|(min, max)| std::array::from_fn(|i| (min[i] + max[i]) * 0.5))
+            .unwrap_or([0.0; 3])
+    }
+
+    /// Create a Model from an OpenModel Mesh (single mesh), with positions relative to `origin_offset`
+    pub fn from_openmodel_mesh(device: &wgpu::Device, name: &str, openmodel_mesh: &OpenModelMesh, origin_offset: [f64; 3]) -> Self {
+        let mesh = Mesh::from_openmodel_mesh(device, name, openmodel_mesh, origin_offset);
+        let edge_meshes = Self::create_edge_meshes(device, openmodel_mesh, origin_offset);
+        Self::with_edges(vec![mesh], edge_meshes)
+    }
+
+    /// Create a Model from multiple OpenModel Meshes, with positions relative to `origin_offset`
+    pub fn from_openmodel_meshes(device: &wgpu::Device, openmodel_meshes: &[(String, OpenModelMesh)], origin_offset: [f64; 3]) -> Self {
         let meshes: Vec<Mesh> = openmodel_meshes.iter()
-            .map(|(name, mesh)| Mesh::from_openmodel_mesh(device, name, mesh))
+            .map(|(name, mesh)| Mesh::from_openmodel_mesh(device, name, mesh, origin_offset))
             .collect();
         
         // Create edge meshes from all OpenModel meshes
         let mut edge_meshes = Vec::new();
         for (_name, mesh) in openmodel_meshes {
-            let edges = Self::create_edge_meshes(device, mesh);
+            let edges = Self::create_edge_meshes(device, mesh, origin_offset);
             edge_meshes.extend(edges);
         }
         
-        Self { 
-            meshes,
-            edge_meshes,
-        }
+        Self::with_edges(meshes, edge_meshes)
     }
 
     /// Create edge visualization meshes from an OpenModel mesh
-    fn create_edge_meshes(device: &wgpu::Device, openmodel_mesh: &OpenModelMesh) -> Vec<Mesh> {
+    fn create_edge_meshes(device: &wgpu::Device, openmodel_mesh: &OpenModelMesh, origin_offset: [f64; 3]) -> Vec<Mesh> {
         // Extract edges as pipes using OpenModel's extract_edges_as_pipes method
         let edge_radius = 0.005; // Much thinner radius for edge visualization
         let edge_pipes = openmodel_mesh.extract_edges_as_pipes(edge_radius, None);
@@ -227,7 +345,8 @@ impl Model {
                 device, 
                 &format!("edge_{}", i), 
                 edge_pipe,
-                [0.25, 0.25, 0.25] // Black color for edges
+                [0.25, 0.25, 0.25], // Black color for edges
+                origin_offset,
             );
             edge_meshes.push(edge_mesh);
         }
@@ -452,14 +571,15 @@ where
         camera_bind_group: &'a wgpu::BindGroup,
         light_bind_group: &'a wgpu::BindGroup,
     ) {
-        self.set_vertex_buffer(0, model.meshes[0].vertex_buffer.slice(..));
-        self.set_index_buffer(
-            model.meshes[0].index_buffer.slice(..),
-            wgpu::IndexFormat::Uint32,
-        );
+        // Empty models (no triangles) have no mesh to draw
+        let Some(mesh) = model.meshes.first() else {
+            return;
+        };
+        self.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        self.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         self.set_bind_group(0, camera_bind_group, &[]);
         self.set_bind_group(1, light_bind_group, &[]);
-        self.draw_indexed(0..model.meshes[0].num_elements, 0, 0..1);
+        self.draw_indexed(0..mesh.num_elements, 0, 0..1);
     }
 
     fn draw_light_model_instanced(
@@ -469,14 +589,15 @@ where
         camera_bind_group: &'a wgpu::BindGroup,
         light_bind_group: &'a wgpu::BindGroup,
     ) {
-        self.set_vertex_buffer(0, model.meshes[0].vertex_buffer.slice(..));
-        self.set_index_buffer(
-            model.meshes[0].index_buffer.slice(..),
-            wgpu::IndexFormat::Uint32,
-        );
+        // Empty models (no triangles) have no mesh to draw
+        let Some(mesh) = model.meshes.first() else {
+            return;
+        };
+        self.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        self.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         self.set_bind_group(0, camera_bind_group, &[]);
         self.set_bind_group(1, light_bind_group, &[]);
-        self.draw_indexed(0..model.meshes[0].num_elements, 0, instances);
+        self.draw_indexed(0..mesh.num_elements, 0, instances);
     }
 }
 
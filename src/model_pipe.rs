@@ -5,27 +5,46 @@
 //! of 3D pipe segments with position, color and radius attributes.
 //!
 //! Key components:
-//! - `PipeVertex`: GPU vertex structure for pipes with position and color
-//! - `PipeSegment`: Definition of a pipe segment with start, end, color and radius
+//! - `PipeVertex`: GPU vertex structure for pipes with position, start and end color
+//! - `PipeSegment`: Definition of a pipe segment with start, end, color (optionally a
+//!   gradient to an end color) and radius
 //! - `PipeModel`: A collection of pipe segments rendered as 3D cylinders
 //! - `DrawPipes` trait: Rendering abstraction for pipe collections
+//! - `PipeCapStyle`: Flat ends, or spheres rounding the ends and the joints between segments
 //! - OpenModel integration: Uses OpenModel's create_pipe method for accurate pipe generation
 
 use wgpu::util::DeviceExt;
 use openmodel::geometry::{Line as OpenModelLine, Point as OpenModelPoint, Mesh as OpenModelMesh};
 use openmodel::primitives::Color as OpenModelColor;
+use crate::model::local_position;
 
 // Configuration constants
 pub const PIPE_RADIUS: f32 = 0.05;  // Default pipe radius/thickness
 #[allow(dead_code)]
 pub const PIPE_COLOR: [f32; 3] = [1.0, 0.0, 0.0];  // Bright red for debugging
 
+/// Longitude and latitude subdivisions of the spheres of rounded caps
+const CAP_LONGITUDE_SEGMENTS: u32 = 12;
+const CAP_LATITUDE_SEGMENTS: u32 = 6;
+
+/// How the ends of pipe segments are closed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PipeCapStyle {
+    /// Ends are cut off flat, as generated by OpenModel
+    #[default]
+    Flat,
+    /// A sphere of the pipe radius at every segment end, which rounds free ends and
+    /// fills the joints between connected segments
+    Rounded,
+}
+
 // Pipe segment definition
 #[derive(Debug, Clone)]
 pub struct PipeSegment {
     pub start: [f32; 3],
     pub end: [f32; 3],
     pub color: [f32; 3],
+    pub end_color: Option<[f32; 3]>, // Color at `end`, blended from `color` along the pipe; None keeps it flat
     pub radius: f32,
 }
 
@@ -33,11 +52,34 @@ impl PipeSegment {
     /// Create a new PipeSegment
     #[allow(dead_code)]
     pub fn new(start: [f32; 3], end: [f32; 3], color: [f32; 3], radius: f32) -> Self {
-        Self { start, end, color, radius }
+        Self { start, end, color, end_color: None, radius }
+    }
+
+    /// Blend the color from `color` at the start to `end_color` at the end
+    #[allow(dead_code)]
+    pub fn with_end_color(mut self, end_color: [f32; 3]) -> Self {
+        self.end_color = Some(end_color);
+        self
+    }
+
+    /// Color at the end of the segment
+    pub fn end_color(&self) -> [f32; 3] {
+        self.end_color.unwrap_or(self.color)
     }
 
-    /// Create a PipeSegment from an OpenModel Line
-    pub fn from_openmodel_line(line: &OpenModelLine) -> Self {
+    /// Position of `point` along the segment, 0 at the start and 1 at the end
+    fn axial_parameter(&self, point: [f32; 3]) -> f32 {
+        let axis: [f32; 3] = std::array::from_fn(|i| self.end[i] - self.start[i]);
+        let length_squared: f32 = axis.iter().map(|a| a * a).sum();
+        if length_squared <= f32::EPSILON {
+            return 0.0;
+        }
+        let along: f32 = (0..3).map(|i| (point[i] - self.start[i]) * axis[i]).sum();
+        (along / length_squared).clamp(0.0, 1.0)
+    }
+
+    /// Create a PipeSegment from an OpenModel Line, relative to `origin_offset`
+    pub fn from_openmodel_line(line: &OpenModelLine, origin_offset: [f64; 3]) -> Self {
         let color = if line.data.has_color() {
             let color_data = line.data.get_color();
             [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0]
@@ -49,36 +91,45 @@ impl PipeSegment {
         let radius = if radius > 0.0 { radius } else { PIPE_RADIUS }; // Use default if no thickness
 
         Self {
-            start: [line.x0 as f32, line.y0 as f32, line.z0 as f32],
-            end: [line.x1 as f32, line.y1 as f32, line.z1 as f32],
+            start: local_position([line.x0, line.y0, line.z0], origin_offset),
+            end: local_position([line.x1, line.y1, line.z1], origin_offset),
             color,
+            end_color: None,
             radius,
         }
     }
 
-    /// Create a PipeSegment from an OpenModel Line with specified color and radius
+    /// Create a PipeSegment from an OpenModel Line with specified color and radius, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_line_with_params(line: &OpenModelLine, color: &OpenModelColor, radius: f32) -> Self {
+    pub fn from_openmodel_line_with_params(line: &OpenModelLine, color: &OpenModelColor, radius: f32, origin_offset: [f64; 3]) -> Self {
         let color_array = [color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0];
         
         Self {
-            start: [line.x0 as f32, line.y0 as f32, line.z0 as f32],
-            end: [line.x1 as f32, line.y1 as f32, line.z1 as f32],
+            start: local_position([line.x0, line.y0, line.z0], origin_offset),
+            end: local_position([line.x1, line.y1, line.z1], origin_offset),
             color: color_array,
+            end_color: None,
             radius,
         }
     }
 }
 
-// Vertex structure for cylinders - simplified for flat color shader
+// Vertex structure for cylinders - the shader blends from `color` to `end_color` by `axial`
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct PipeVertex {
     pub position: [f32; 3],
     pub color: [f32; 3],
+    pub end_color: [f32; 3],
+    pub axial: f32, // 0 at the segment start, 1 at its end
 }
 
 impl PipeVertex {
+    /// A vertex with a single color
+    pub fn new(position: [f32; 3], color: [f32; 3]) -> Self {
+        Self { position, color, end_color: color, axial: 0.0 }
+    }
+
     #[allow(dead_code)]
     pub fn desc() -> wgpu::VertexBufferLayout<'static> {
         use std::mem;
@@ -98,6 +149,18 @@ impl PipeVertex {
                     shader_location: 1,
                     format: wgpu::VertexFormat::Float32x3,
                 },
+                // end_color
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // axial
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32,
+                },
             ],
         }
     }
@@ -109,6 +172,7 @@ pub struct PipeModel {
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub num_indices: u32,
+    pub bounds: Option<([f32; 3], [f32; 3])>, // Axis-aligned bounds (min, max) of the pipe surfaces
 }
 
 impl PipeModel {
@@ -116,6 +180,16 @@ impl PipeModel {
         device: &wgpu::Device, 
         name: &str, 
         pipe_segments: &[PipeSegment],
+    ) -> Self {
+        Self::with_cap_style(device, name, pipe_segments, PipeCapStyle::Flat)
+    }
+
+    /// Create a pipe model whose segment ends are closed with `cap_style`
+    pub fn with_cap_style(
+        device: &wgpu::Device,
+        name: &str,
+        pipe_segments: &[PipeSegment],
+        cap_style: PipeCapStyle,
     ) -> Self {
         // Generate vertices and indices for all pipe segments using OpenModel
         let mut all_vertices = Vec::new();
@@ -146,9 +220,12 @@ impl PipeModel {
                                 all_indices.push(vertex_offset + existing_local_index);
                             } else {
                                 if let Some(position) = openmodel_mesh.vertex_position(vertex_key) {
+                                    let position = [position.x as f32, position.y as f32, position.z as f32];
                                     let pipe_vertex = PipeVertex {
-                                        position: [position.x as f32, position.y as f32, position.z as f32],
+                                        position,
                                         color: segment.color,
+                                        end_color: segment.end_color(),
+                                        axial: segment.axial_parameter(position),
                                     };
                                     
                                     all_vertices.push(pipe_vertex);
@@ -164,6 +241,10 @@ impl PipeModel {
             
             vertex_offset += next_local_index;
         }
+
+        if cap_style == PipeCapStyle::Rounded {
+            append_round_caps(&mut all_vertices, &mut all_indices, pipe_segments);
+        }
         
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some(&format!("{} Vertex Buffer", name)),
@@ -182,32 +263,81 @@ impl PipeModel {
             vertex_buffer,
             index_buffer,
             num_indices: all_indices.len() as u32,
+            bounds: crate::model_polygon::position_bounds(all_vertices.iter().map(|vertex| vertex.position)),
         }
     }
 
-    /// Create a PipeModel from an OpenModel Line
+    /// Create a PipeModel from an OpenModel Line, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_line(device: &wgpu::Device, name: &str, line: &OpenModelLine) -> Self {
-        let pipe_segment = PipeSegment::from_openmodel_line(line);
+    pub fn from_openmodel_line(device: &wgpu::Device, name: &str, line: &OpenModelLine, origin_offset: [f64; 3]) -> Self {
+        let pipe_segment = PipeSegment::from_openmodel_line(line, origin_offset);
         Self::new(device, name, &[pipe_segment])
     }
 
-    /// Create a PipeModel from a collection of OpenModel Lines
-    pub fn from_openmodel_lines(device: &wgpu::Device, name: &str, lines: &[OpenModelLine]) -> Self {
+    /// Create a PipeModel from a collection of OpenModel Lines, relative to `origin_offset`
+    pub fn from_openmodel_lines(device: &wgpu::Device, name: &str, lines: &[OpenModelLine], origin_offset: [f64; 3]) -> Self {
         let pipe_segments: Vec<PipeSegment> = lines.iter()
-            .map(|line| PipeSegment::from_openmodel_line(line))
+            .map(|line| PipeSegment::from_openmodel_line(line, origin_offset))
             .collect();
         Self::new(device, name, &pipe_segments)
     }
 
-    /// Create a PipeModel from an OpenModel Line with specified color and radius
+    /// Create a PipeModel from an OpenModel Line with specified color and radius, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_line_with_params(device: &wgpu::Device, name: &str, line: &OpenModelLine, color: &OpenModelColor, radius: f32) -> Self {
-        let pipe_segment = PipeSegment::from_openmodel_line_with_params(line, color, radius);
+    pub fn from_openmodel_line_with_params(device: &wgpu::Device, name: &str, line: &OpenModelLine, color: &OpenModelColor, radius: f32, origin_offset: [f64; 3]) -> Self {
+        let pipe_segment = PipeSegment::from_openmodel_line_with_params(line, color, radius, origin_offset);
         Self::new(device, name, &[pipe_segment])
     }
 }
 
+/// Append one sphere per distinct segment end, with the largest radius of the segments meeting there
+///
+/// Each sphere takes the color of that segment at that end.
+pub fn append_round_caps(vertices: &mut Vec<PipeVertex>, indices: &mut Vec<u32>, pipe_segments: &[PipeSegment]) {
+    // Keyed by the exact coordinates, so segments sharing an endpoint share one joint sphere
+    let mut ends: std::collections::BTreeMap<[u32; 3], (f32, [f32; 3])> = std::collections::BTreeMap::new();
+    for segment in pipe_segments {
+        for (end, color) in [(segment.start, segment.color), (segment.end, segment.end_color())] {
+            let key = end.map(f32::to_bits);
+            let cap = ends.entry(key).or_insert((segment.radius, color));
+            if segment.radius > cap.0 {
+                *cap = (segment.radius, color);
+            }
+        }
+    }
+    for (key, (radius, color)) in ends {
+        append_sphere(vertices, indices, key.map(f32::from_bits), radius, color);
+    }
+}
+
+/// Append a UV sphere wound counter-clockwise as seen from outside
+fn append_sphere(vertices: &mut Vec<PipeVertex>, indices: &mut Vec<u32>, center: [f32; 3], radius: f32, color: [f32; 3]) {
+    let base = vertices.len() as u32;
+    for latitude in 0..=CAP_LATITUDE_SEGMENTS {
+        let theta = std::f32::consts::PI * latitude as f32 / CAP_LATITUDE_SEGMENTS as f32;
+        for longitude in 0..=CAP_LONGITUDE_SEGMENTS {
+            let phi = 2.0 * std::f32::consts::PI * longitude as f32 / CAP_LONGITUDE_SEGMENTS as f32;
+            vertices.push(PipeVertex::new(
+                [
+                    center[0] + radius * theta.sin() * phi.cos(),
+                    center[1] + radius * theta.sin() * phi.sin(),
+                    center[2] + radius * theta.cos(),
+                ],
+                color,
+            ));
+        }
+    }
+
+    let row = CAP_LONGITUDE_SEGMENTS + 1;
+    for latitude in 0..CAP_LATITUDE_SEGMENTS {
+        for longitude in 0..CAP_LONGITUDE_SEGMENTS {
+            let a = base + latitude * row + longitude;
+            let b = a + row;
+            indices.extend_from_slice(&[a, b, b + 1, a, b + 1, a + 1]);
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub trait DrawPipes<'a> {
     fn draw_pipes(
@@ -7,24 +7,32 @@
 //! Key components:
 //! - `PointVertex`: GPU vertex structure for point clouds with position, color, and size
 //! - `PointModel`: A collection of points with rendering properties
+//! - `QuadPointModel`: Billboard point cloud split into `QuadPointChunk`s that fit the device buffer limits
 //! - `DrawPoints` trait: Rendering abstraction for point clouds
 //! - OpenModel integration: Bridge between OpenModel Point/PointCloud and GPU structures
 //! - `generate_point_cloud`: Utility function to generate point clouds from instances
+//! - `PointRng` / `decimate_points`: Seeded sampling, so jittered and decimated clouds are
+//!   the same on every run for a given seed
 
+use std::collections::HashMap;
 use wgpu::util::DeviceExt;
 use crate::instance::Instance;
+use crate::lib_pipeline::BlendMode;
+use crate::model::local_position;
 use openmodel::geometry::{Point as OpenModelPoint, PointCloud as OpenModelPointCloud};
 use openmodel::primitives::Color as OpenModelColor;
 // use cgmath::prelude::*;  // Not currently used
 
 // Configuration constants
 pub const POINT_SIZE: f32 = 0.02;  // Default point size
+pub const MAX_POINTS_PER_CHUNK: usize = 1_000_000; // Upper bound of points per GPU buffer pair
+pub const DEFAULT_POINT_SEED: u64 = 0x5EED; // Seed of the random point sampling unless one is given
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct PointVertex {
     pub position: [f32; 3],
-    pub color: [f32; 3],
+    pub color: [f32; 4], // RGBA, alpha 1.0 is opaque
     pub size: f32,
 }
 
@@ -33,9 +41,10 @@ pub struct PointVertex {
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct QuadPointVertex {
     pub position: [f32; 3],      // Center position of the point
-    pub color: [f32; 3],        // Color of the point
+    pub color: [f32; 4],        // RGBA color of the point
     pub corner: [f32; 2],       // Corner offset (-1,-1 to 1,1)
     pub size: f32,              // Size of the point
+    pub density: f32,           // Local density in [0, 1], or NO_DENSITY
 }
 
 #[allow(dead_code)]
@@ -54,10 +63,10 @@ impl PointVertex {
                 wgpu::VertexAttribute {
                     offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
                     shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3,
+                    format: wgpu::VertexFormat::Float32x4,
                 },
                 wgpu::VertexAttribute {
-                    offset: mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    offset: mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
                     shader_location: 2,
                     format: wgpu::VertexFormat::Float32,
                 },
@@ -65,20 +74,21 @@ impl PointVertex {
         }
     }
 
-    /// Create a PointVertex from an OpenModel Point with default color and size
-    pub fn from_openmodel_point(point: &OpenModelPoint) -> Self {
+    /// Create a PointVertex from an OpenModel Point with default color and size, relative to `origin_offset`
+    pub fn from_openmodel_point(point: &OpenModelPoint, origin_offset: [f64; 3]) -> Self {
         PointVertex {
-            position: [point.x as f32, point.y as f32, point.z as f32],
-            color: [1.0, 1.0, 1.0], // Default white color
+            position: local_position([point.x, point.y, point.z], origin_offset),
+            color: [1.0, 1.0, 1.0, 1.0], // Default opaque white color
             size: POINT_SIZE,
         }
     }
 
-    /// Create a PointVertex from an OpenModel Point with specified color and size
-    pub fn from_openmodel_point_with_color(point: &OpenModelPoint, color: &OpenModelColor, size: f32) -> Self {
+    /// Create a PointVertex from an OpenModel Point with specified color and size, relative to `origin_offset`
+    pub fn from_openmodel_point_with_color(point: &OpenModelPoint, color: &OpenModelColor, size: f32, origin_offset: [f64; 3]) -> Self {
+        let (r, g, b, a) = color.to_float();
         PointVertex {
-            position: [point.x as f32, point.y as f32, point.z as f32],
-            color: [color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0],
+            position: local_position([point.x, point.y, point.z], origin_offset),
+            color: [r, g, b, a],
             size,
         }
     }
@@ -100,24 +110,29 @@ impl QuadPointVertex {
                 wgpu::VertexAttribute {
                     offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
                     shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3,
+                    format: wgpu::VertexFormat::Float32x4,
                 },
                 wgpu::VertexAttribute {
-                    offset: mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    offset: mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
                     shader_location: 2,
                     format: wgpu::VertexFormat::Float32x2,
                 },
                 wgpu::VertexAttribute {
-                    offset: mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                    offset: mem::size_of::<[f32; 9]>() as wgpu::BufferAddress,
                     shader_location: 3,
                     format: wgpu::VertexFormat::Float32,
                 },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 10]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32,
+                },
             ],
         }
     }
     
     /// Converts a single point into 4 quad vertices (for a billboarded point)
-    pub fn from_point(point: &PointVertex) -> [Self; 4] {
+    pub fn from_point(point: &PointVertex, density: f32) -> [Self; 4] {
         // Create the 4 corners of a quad
         let corners = [
             [-1.0f32, -1.0f32], // Bottom-left
@@ -132,15 +147,16 @@ impl QuadPointVertex {
             color: point.color,
             corner,
             size: point.size,
+            density,
         })
     }
     
     /// Converts a slice of points into quad vertices
-    pub fn points_to_quads(points: &[PointVertex]) -> Vec<Self> {
+    pub fn points_to_quads(points: &[PointVertex], densities: &[f32]) -> Vec<Self> {
         let mut quad_vertices = Vec::with_capacity(points.len() * 4);
         
-        for point in points {
-            let quad_verts = Self::from_point(point);
+        for (point, density) in points.iter().zip(densities) {
+            let quad_verts = Self::from_point(point, *density);
             quad_vertices.extend_from_slice(&quad_verts);
         }
         
@@ -148,20 +164,92 @@ impl QuadPointVertex {
     }
 }
 
+/// Voxel cells along the largest bounding box axis for the point density estimate
+pub const DENSITY_GRID_RESOLUTION: u32 = 128;
+
+/// Density of points without an estimate (e.g. debug points); they keep their color in density mode
+pub const NO_DENSITY: f32 = -1.0;
+
+/// How the point shader colors points
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PointColorMode {
+    /// Per-point color from the geometry
+    #[default]
+    Vertex,
+    /// Local neighbor density mapped through the viridis colormap
+    Density,
+}
+
+/// How the point shader draws each point
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PointShape {
+    /// Flat screen-facing disc, blended as set by the model's `blend_mode`
+    #[default]
+    Disc,
+    /// Opaque sphere impostor, lit like the meshes and writing per-fragment depth
+    Sphere,
+}
+
+/// Local density of every point in [0, 1], computed on a voxel grid
+///
+/// The bounding box is split into cubic voxels, `resolution` along its largest
+/// axis, and each point gets the point count of its voxel, log-scaled against
+/// the fullest voxel so sparse regions stay distinguishable.
+pub fn point_densities(points: &[PointVertex], resolution: u32) -> Vec<f32> {
+    let Some((min, max)) = points.iter().fold(None, |bounds: Option<([f32; 3], [f32; 3])>, point| {
+        let (mut min, mut max) = bounds.unwrap_or((point.position, point.position));
+        for i in 0..3 {
+            min[i] = min[i].min(point.position[i]);
+            max[i] = max[i].max(point.position[i]);
+        }
+        Some((min, max))
+    }) else {
+        return Vec::new();
+    };
+
+    let largest_extent = (0..3).map(|i| max[i] - min[i]).fold(0.0, f32::max);
+    if largest_extent <= 0.0 {
+        return vec![1.0; points.len()]; // All points coincide
+    }
+    let voxel_size = largest_extent / resolution.max(1) as f32;
+    let voxel_of = |position: [f32; 3]| -> [u32; 3] {
+        [0, 1, 2].map(|i| ((position[i] - min[i]) / voxel_size) as u32)
+    };
+
+    let mut counts: HashMap<[u32; 3], u32> = HashMap::new();
+    for point in points {
+        *counts.entry(voxel_of(point.position)).or_insert(0) += 1;
+    }
+    let max_count = counts.values().copied().max().unwrap_or(1) as f32;
+
+    points.iter()
+        .map(|point| (1.0 + counts[&voxel_of(point.position)] as f32).ln() / (1.0 + max_count).ln())
+        .collect()
+}
+
 pub struct PointModel {
     pub _name: String, // Using underscore to indicate unused field
     pub vertex_buffer: wgpu::Buffer,
     pub num_vertices: u32,
 }
 
-pub struct QuadPointModel {
-    pub _name: String,
+/// One vertex/index buffer pair of a quad point cloud, drawn with its own draw call
+pub struct QuadPointChunk {
     pub vertex_buffer: wgpu::Buffer,
     pub num_vertices: u32,
     pub indices: Option<wgpu::Buffer>,
     pub num_indices: u32,
 }
 
+/// Quad point cloud split into chunks so that no buffer exceeds the device limits
+pub struct QuadPointModel {
+    pub _name: String,
+    pub chunks: Vec<QuadPointChunk>,
+    pub blend_mode: BlendMode, // Selects the point pipeline variant
+    pub center: [f32; 3],      // Bounds center, used to sort it among transparent geometry
+    pub bounds: Option<([f32; 3], [f32; 3])>, // Axis-aligned bounds (min, max) of the point centers
+}
+
 #[allow(dead_code)]
 impl PointModel {
     pub fn new(device: &wgpu::Device, name: &str, vertices: &[PointVertex]) -> Self {
@@ -178,8 +266,8 @@ impl PointModel {
         }
     }
 
-    /// Create a PointModel from an OpenModel PointCloud
-    pub fn from_openmodel_pointcloud(device: &wgpu::Device, name: &str, pointcloud: &OpenModelPointCloud) -> Self {
+    /// Create a PointModel from an OpenModel PointCloud, with positions relative to `origin_offset`
+    pub fn from_openmodel_pointcloud(device: &wgpu::Device, name: &str, pointcloud: &OpenModelPointCloud, origin_offset: [f64; 3]) -> Self {
         let vertices: Vec<PointVertex> = pointcloud.points.iter().enumerate().map(|(i, point)| {
             let color = if i < pointcloud.colors.len() {
                 &pointcloud.colors[i]
@@ -187,16 +275,16 @@ impl PointModel {
                 // Default white color if no color specified
                 &OpenModelColor::new(255, 255, 255, 255)
             };
-            PointVertex::from_openmodel_point_with_color(point, color, POINT_SIZE)
+            PointVertex::from_openmodel_point_with_color(point, color, POINT_SIZE, origin_offset)
         }).collect();
 
         Self::new(device, name, &vertices)
     }
 
-    /// Create a PointModel from a collection of OpenModel Points with default colors
-    pub fn from_openmodel_points(device: &wgpu::Device, name: &str, points: &[OpenModelPoint]) -> Self {
+    /// Create a PointModel from a collection of OpenModel Points with default colors, relative to `origin_offset`
+    pub fn from_openmodel_points(device: &wgpu::Device, name: &str, points: &[OpenModelPoint], origin_offset: [f64; 3]) -> Self {
         let vertices: Vec<PointVertex> = points.iter()
-            .map(|point| PointVertex::from_openmodel_point(point))
+            .map(|point| PointVertex::from_openmodel_point(point, origin_offset))
             .collect();
 
         Self::new(device, name, &vertices)
@@ -210,66 +298,81 @@ impl PointModel {
         let point_count = self.num_vertices as usize;
         let placeholder_points = vec![PointVertex {
             position: [0.0, 0.0, 0.0],
-            color: [1.0, 1.0, 1.0],
+            color: [1.0, 1.0, 1.0, 1.0],
             size: 5.0,
         }; point_count];
         
-        // Convert points to quad vertices
-        let quad_vertices = QuadPointVertex::points_to_quads(&placeholder_points);
-        
-        // Create indices for the quads (2 triangles per quad)
-        let mut indices = Vec::with_capacity(point_count * 6);
-        
-        // For each point, create 2 triangles (6 indices)
-        for i in 0..point_count {
-            let base = (i * 4) as u16;
-            // First triangle (bottom-left, bottom-right, top-left)
-            indices.push(base + 0);
-            indices.push(base + 1);
-            indices.push(base + 2);
-            // Second triangle (bottom-right, top-right, top-left)
-            indices.push(base + 1);
-            indices.push(base + 3);
-            indices.push(base + 2);
-        }
-        
-        // Create buffers
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Quad Point Vertex Buffer"),
-            contents: bytemuck::cast_slice(&quad_vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-        
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Quad Point Index Buffer"),
-            contents: bytemuck::cast_slice(&indices),
-            usage: wgpu::BufferUsages::INDEX,
-        });
-        
-        QuadPointModel {
-            _name: self._name,
-            vertex_buffer,
-            num_vertices: quad_vertices.len() as u32,
-            indices: Some(index_buffer),
-            num_indices: indices.len() as u32,
-        }
+        QuadPointModel::new(device, &self._name, &placeholder_points)
     }
 }
 
 #[allow(dead_code)]
 impl QuadPointModel {
+    /// Create a quad point model, splitting the points into chunks that fit
+    /// within `device.limits().max_buffer_size` and keep u32 indices in range
     pub fn new(device: &wgpu::Device, name: &str, points: &[PointVertex]) -> Self {
+        Self::with_densities(device, name, points, &vec![NO_DENSITY; points.len()])
+    }
+
+    /// Create a quad point model that also carries each point's local density
+    /// (see `point_densities`) for the density color mode
+    pub fn with_density(device: &wgpu::Device, name: &str, points: &[PointVertex], resolution: u32) -> Self {
+        Self::with_densities(device, name, points, &point_densities(points, resolution))
+    }
+
+    fn with_densities(device: &wgpu::Device, name: &str, points: &[PointVertex], densities: &[f32]) -> Self {
+        let points_per_chunk = Self::points_per_chunk(device.limits().max_buffer_size);
+
+        let chunks: Vec<QuadPointChunk> = points
+            .chunks(points_per_chunk)
+            .zip(densities.chunks(points_per_chunk))
+            .enumerate()
+            .map(|(chunk_index, (chunk_points, chunk_densities))| {
+                QuadPointChunk::new(device, &format!("{} [{}]", name, chunk_index), chunk_points, chunk_densities)
+            })
+            .collect();
+
+        if chunks.len() > 1 {
+            println!("Split point cloud '{}' ({} points) into {} chunks", name, points.len(), chunks.len());
+        }
+
+        let bounds = crate::model_polygon::position_bounds(points.iter().map(|point| point.position));
+        Self {
+            _name: String::from(name),
+            chunks,
+            blend_mode: BlendMode::default(),
+            center: crate::model_polygon::bounds_center(bounds),
+            bounds,
+        }
+    }
+
+    /// Largest number of points whose vertex buffer fits in `max_buffer_size`
+    pub fn points_per_chunk(max_buffer_size: u64) -> usize {
+        // Each point becomes 4 quad vertices; the vertex buffer is the larger of the two buffers
+        let bytes_per_point = (4 * std::mem::size_of::<QuadPointVertex>()) as u64;
+        let by_buffer_size = (max_buffer_size / bytes_per_point) as usize;
+        by_buffer_size.clamp(1, MAX_POINTS_PER_CHUNK)
+    }
+
+    /// Total number of quad vertices across all chunks
+    pub fn num_vertices(&self) -> u32 {
+        self.chunks.iter().map(|chunk| chunk.num_vertices).sum()
+    }
+}
+
+impl QuadPointChunk {
+    pub fn new(device: &wgpu::Device, name: &str, points: &[PointVertex], densities: &[f32]) -> Self {
         // Convert points to quad vertices
-        let quad_vertices = QuadPointVertex::points_to_quads(points);
+        let quad_vertices = QuadPointVertex::points_to_quads(points, densities);
         
-        // Create indices for the quads (2 triangles per quad)
+        // Create indices for the quads (2 triangles per quad), local to this chunk
         let mut indices: Vec<u32> = Vec::with_capacity(points.len() * 6);
         
         // For each point, create 2 triangles (6 indices)
         for i in 0..points.len() {
-            let base = (i * 4) as u32; // Use u32 instead of u16 to support more vertices
+            let base = (i * 4) as u32;
             // First triangle (bottom-left, bottom-right, top-left)
-            indices.push(base + 0);
+            indices.push(base);
             indices.push(base + 1);
             indices.push(base + 2);
             // Second triangle (bottom-right, top-right, top-left)
@@ -292,7 +395,6 @@ impl QuadPointModel {
         });
         
         Self {
-            _name: String::from(name),
             vertex_buffer,
             num_vertices: quad_vertices.len() as u32,
             indices: Some(index_buffer),
@@ -347,14 +449,17 @@ where
         quad_model: &'b QuadPointModel,
         camera_bind_group: &'b wgpu::BindGroup,
     ) {
-        self.set_vertex_buffer(0, quad_model.vertex_buffer.slice(..));
         self.set_bind_group(0, camera_bind_group, &[]);
         
-        if let Some(index_buffer) = &quad_model.indices {
-            self.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-            self.draw_indexed(0..quad_model.num_indices, 0, 0..1);
-        } else {
-            self.draw(0..quad_model.num_vertices, 0..1);
+        // One draw call per chunk
+        for chunk in &quad_model.chunks {
+            self.set_vertex_buffer(0, chunk.vertex_buffer.slice(..));
+            if let Some(index_buffer) = &chunk.indices {
+                self.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                self.draw_indexed(0..chunk.num_indices, 0, 0..1);
+            } else {
+                self.draw(0..chunk.num_vertices, 0..1);
+            }
         }
     }
 }
@@ -386,9 +491,55 @@ pub fn create_sample_openmodel_pointcloud() -> OpenModelPointCloud {
     OpenModelPointCloud::new(points, normals, colors)
 }
 
+/// Small seeded random number generator (SplitMix64) for point sampling
+///
+/// Implemented here rather than taken from a crate so a seed gives the same
+/// sequence on every platform and release, which reproducible snapshots rely on.
+#[derive(Debug, Clone)]
+pub struct PointRng {
+    state: u64,
+}
+
+impl PointRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0.0..1.0`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform value in `-extent..extent`
+    pub fn next_signed(&mut self, extent: f32) -> f32 {
+        (self.next_f32() * 2.0 - 1.0) * extent
+    }
+}
+
+/// Keep a random `keep_fraction` (0..=1) of `points`, the same subset for the same seed
+///
+/// The kept points stay in their original order.
+pub fn decimate_points(points: &[PointVertex], keep_fraction: f32, seed: u64) -> Vec<PointVertex> {
+    let keep_fraction = keep_fraction.clamp(0.0, 1.0);
+    let mut rng = PointRng::new(seed);
+    points.iter().filter(|_| rng.next_f32() < keep_fraction).copied().collect()
+}
+
 /// Generates point cloud vertices for a series of cube instances
+///
+/// Every cube is filled with a regular grid of points. `jitter` moves each point by up to
+/// that fraction of the grid step along every axis (0 keeps the regular grid); the offsets
+/// come from `seed`, so the same seed always gives the same cloud.
 #[allow(dead_code)]
-pub fn generate_point_cloud(instances: &[Instance]) -> Vec<PointVertex> {
+pub fn generate_point_cloud(instances: &[Instance], seed: u64, jitter: f32) -> Vec<PointVertex> {
     println!("DEBUG: Creating point clouds for {} cube instances", instances.len());
     
     let mut point_vertices = Vec::new();
@@ -397,6 +548,8 @@ pub fn generate_point_cloud(instances: &[Instance]) -> Vec<PointVertex> {
     let local_grid_size = 22; // Points along each axis per cube (22^3 * 10^2 ≈ 10.6 million points)
     let local_grid_extent = 1.01; // Size of cube is 1.0 (-0.5 to +0.5)
     let step = (2.0 * local_grid_extent) / (local_grid_size as f32 - 1.0);
+    let jitter_extent = jitter.max(0.0) * step;
+    let mut rng = PointRng::new(seed);
     
     // For each cube instance, create a small grid of points with the appropriate transformation
     for instance in instances {
@@ -411,9 +564,9 @@ pub fn generate_point_cloud(instances: &[Instance]) -> Vec<PointVertex> {
             for j in 0..local_grid_size {
                 for k in 0..local_grid_size {
                     // Calculate local position within the cube (-0.5 to 0.5)
-                    let local_x = -local_grid_extent + (i as f32) * step;
-                    let local_y = -local_grid_extent + (j as f32) * step;
-                    let local_z = -local_grid_extent + (k as f32) * step;
+                    let local_x = -local_grid_extent + (i as f32) * step + rng.next_signed(jitter_extent);
+                    let local_y = -local_grid_extent + (j as f32) * step + rng.next_signed(jitter_extent);
+                    let local_z = -local_grid_extent + (k as f32) * step + rng.next_signed(jitter_extent);
                     
                     // Transform the point using the rotation matrix
                     let point_local = cgmath::Vector4::new(local_x, local_y, local_z, 1.0);
@@ -431,7 +584,7 @@ pub fn generate_point_cloud(instances: &[Instance]) -> Vec<PointVertex> {
                     
                     point_vertices.push(PointVertex {
                         position: [world_x, world_y, world_z],
-                        color: [color_r, color_g, color_b],
+                        color: [color_r, color_g, color_b, 1.0],
                         size: POINT_SIZE, // Use the configurable point size
                     });
                 }
@@ -449,3 +602,42 @@ pub fn generate_point_cloud(instances: &[Instance]) -> Vec<PointVertex> {
     
     point_vertices
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_points(count: usize) -> Vec<PointVertex> {
+        (0..count)
+            .map(|i| PointVertex { position: [i as f32, 0.0, 0.0], color: [1.0; 4], size: POINT_SIZE })
+            .collect()
+    }
+
+    fn positions(points: &[PointVertex]) -> Vec<[f32; 3]> {
+        points.iter().map(|point| point.position).collect()
+    }
+
+    #[test]
+    fn decimation_keeps_the_same_points_for_the_same_seed() {
+        let points = grid_points(1000);
+        let kept = positions(&decimate_points(&points, 0.3, DEFAULT_POINT_SEED));
+
+        assert_eq!(kept, positions(&decimate_points(&points, 0.3, DEFAULT_POINT_SEED)));
+        assert_ne!(kept, positions(&decimate_points(&points, 0.3, DEFAULT_POINT_SEED + 1)));
+        assert!((200..400).contains(&kept.len()), "kept {} of 1000", kept.len());
+        assert!(kept.windows(2).all(|pair| pair[0][0] < pair[1][0]), "kept points must stay in order");
+        assert_eq!(decimate_points(&points, 1.0, 7).len(), 1000);
+        assert!(decimate_points(&points, 0.0, 7).is_empty());
+    }
+
+    #[test]
+    fn jittered_point_clouds_are_the_same_for_the_same_seed() {
+        let instances = [Instance::new(cgmath::Vector3::new(0.0, 0.0, 0.0), cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0))];
+        let cloud = positions(&generate_point_cloud(&instances, 42, 0.5));
+
+        assert_eq!(cloud, positions(&generate_point_cloud(&instances, 42, 0.5)));
+        assert_ne!(cloud, positions(&generate_point_cloud(&instances, 43, 0.5)));
+        // Without jitter the seed does not matter
+        assert_eq!(positions(&generate_point_cloud(&instances, 1, 0.0)), positions(&generate_point_cloud(&instances, 2, 0.0)));
+    }
+}
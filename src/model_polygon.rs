@@ -16,17 +16,32 @@ use wgpu::util::DeviceExt;
 // OpenModel imports for polygon geometry
 use openmodel::geometry::Pline as OpenModelPline;
 use openmodel::primitives::Color as OpenModelColor;
+use crate::model::local_position;
 
 // Configuration constants
 #[allow(dead_code)]
-pub const POLYGON_COLOR: [f32; 3] = [0.5, 0.5, 0.5];  // Default gray color
+pub const POLYGON_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.0];  // Default opaque gray color
+
+/// Primitive state of the polygon pipelines
+///
+/// Polygons are flat with no outward side and their shader takes the face normal from
+/// screen-space derivatives, so both windings are drawn and lit alike.
+pub const PRIMITIVE_STATE: wgpu::PrimitiveState = wgpu::PrimitiveState {
+    topology: wgpu::PrimitiveTopology::TriangleList,
+    strip_index_format: None,
+    front_face: wgpu::FrontFace::Ccw,
+    cull_mode: None, // Double-sided, so CW and CCW input both stay visible
+    polygon_mode: wgpu::PolygonMode::Fill,
+    unclipped_depth: false,
+    conservative: false,
+};
 
 // Polygon vertex definition
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct PolygonVertex {
     pub position: [f32; 3],
-    pub color: [f32; 3],
+    pub color: [f32; 4], // RGBA, alpha 1.0 is opaque
 }
 
 impl PolygonVertex {
@@ -47,7 +62,7 @@ impl PolygonVertex {
                 wgpu::VertexAttribute {
                     offset: mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
                     shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3,
+                    format: wgpu::VertexFormat::Float32x4,
                 },
             ],
         }
@@ -60,6 +75,10 @@ pub struct PolygonModel {
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub num_indices: u32,
+    pub lit: bool, // Shade with the light, or draw unlit with just vertex color
+    pub transparent: bool, // Any vertex alpha below 1; drawn in the transparent phase of the All mode
+    pub center: [f32; 3],  // Bounds center, used to sort transparent sets back to front
+    pub bounds: Option<([f32; 3], [f32; 3])>, // Axis-aligned bounds (min, max), None when empty
 }
 
 impl PolygonModel {
@@ -81,11 +100,16 @@ impl PolygonModel {
             usage: wgpu::BufferUsages::INDEX,
         });
         
+        let bounds = position_bounds(vertices.iter().map(|vertex| vertex.position));
         Self {
             name: String::from(name),
             vertex_buffer,
             index_buffer,
             num_indices: indices.len() as u32,
+            lit: true,
+            transparent: vertices.iter().any(|vertex| vertex.color[3] < 1.0),
+            center: bounds_center(bounds),
+            bounds,
         }
     }
     
@@ -95,7 +119,7 @@ impl PolygonModel {
         device: &wgpu::Device,
         name: &str,
         positions: &[[f32; 3]],
-        color: [f32; 3],
+        color: [f32; 4],
     ) -> Self {
         let vertices: Vec<PolygonVertex> = positions.iter()
             .map(|&pos| PolygonVertex { position: pos, color })
@@ -121,7 +145,7 @@ impl PolygonModel {
         device: &wgpu::Device,
         name: &str,
         polygons: &[Vec<[f32; 3]>],
-        colors: &[[f32; 3]],
+        colors: &[[f32; 4]],
     ) -> Self {
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
@@ -152,39 +176,39 @@ impl PolygonModel {
     }
 
     /// Create a PolygonModel from an OpenModel Pline (polyline)
-    /// Converts OpenModel Point coordinates (f64) to GPU vertex format (f32)
+    /// Converts OpenModel Point coordinates (f64) to GPU vertex format (f32), relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_pline(device: &wgpu::Device, name: &str, pline: &OpenModelPline) -> Self {
+    pub fn from_openmodel_pline(device: &wgpu::Device, name: &str, pline: &OpenModelPline, origin_offset: [f64; 3]) -> Self {
         let color = if pline.data.has_color() {
             let color_data = pline.data.get_color();
-            [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0]
+            [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0, 1.0]
         } else {
-            [1.0, 1.0, 1.0] // Default white
+            [1.0, 1.0, 1.0, 1.0] // Default opaque white
         };
 
         let positions: Vec<[f32; 3]> = pline.points.iter()
-            .map(|point| [point.x as f32, point.y as f32, point.z as f32])
+            .map(|point| local_position([point.x, point.y, point.z], origin_offset))
             .collect();
 
         Self::from_positions(device, name, &positions, color)
     }
 
-    /// Create a PolygonModel from multiple OpenModel Plines
+    /// Create a PolygonModel from multiple OpenModel Plines, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_plines(device: &wgpu::Device, name: &str, plines: &[OpenModelPline]) -> Self {
+    pub fn from_openmodel_plines(device: &wgpu::Device, name: &str, plines: &[OpenModelPline], origin_offset: [f64; 3]) -> Self {
         let mut polygons = Vec::new();
         let mut colors = Vec::new();
 
         for pline in plines {
             let color = if pline.data.has_color() {
                 let color_data = pline.data.get_color();
-                [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0]
+                [color_data[0] as f32 / 255.0, color_data[1] as f32 / 255.0, color_data[2] as f32 / 255.0, 1.0]
             } else {
-                [1.0, 1.0, 1.0] // Default white
+                [1.0, 1.0, 1.0, 1.0] // Default opaque white
             };
 
             let positions: Vec<[f32; 3]> = pline.points.iter()
-                .map(|point| [point.x as f32, point.y as f32, point.z as f32])
+                .map(|point| local_position([point.x, point.y, point.z], origin_offset))
                 .collect();
 
             polygons.push(positions);
@@ -194,14 +218,14 @@ impl PolygonModel {
         Self::from_polygon_list(device, name, &polygons, &colors)
     }
 
-    /// Create a PolygonModel from OpenModel Pline with custom color override
+    /// Create a PolygonModel from OpenModel Pline with custom color override, relative to `origin_offset`
     #[allow(dead_code)]
-    pub fn from_openmodel_pline_with_color(device: &wgpu::Device, name: &str, pline: &OpenModelPline, color: &OpenModelColor) -> Self {
-        let (r, g, b, _a) = color.to_float();
-        let gpu_color = [r, g, b];
+    pub fn from_openmodel_pline_with_color(device: &wgpu::Device, name: &str, pline: &OpenModelPline, color: &OpenModelColor, origin_offset: [f64; 3]) -> Self {
+        let (r, g, b, a) = color.to_float();
+        let gpu_color = [r, g, b, a];
 
         let positions: Vec<[f32; 3]> = pline.points.iter()
-            .map(|point| [point.x as f32, point.y as f32, point.z as f32])
+            .map(|point| local_position([point.x, point.y, point.z], origin_offset))
             .collect();
 
         Self::from_positions(device, name, &positions, gpu_color)
@@ -232,3 +256,20 @@ impl<'a, 'b: 'a> DrawPolygons<'a> for wgpu::RenderPass<'b> {
         self.draw_indexed(0..polygon_model.num_indices, 0, 0..1);
     }
 }
+
+/// Axis-aligned bounds (min, max) of `positions`, `None` when empty
+pub fn position_bounds(positions: impl Iterator<Item = [f32; 3]>) -> Option<([f32; 3], [f32; 3])> {
+    positions.fold(None, |bounds, position| {
+        let (mut min, mut max) = bounds.unwrap_or((position, position));
+        for i in 0..3 {
+            min[i] = min[i].min(position[i]);
+            max[i] = max[i].max(position[i]);
+        }
+        Some((min, max))
+    })
+}
+
+/// Center of `bounds` (the origin when empty)
+pub fn bounds_center(bounds: Option<([f32; 3], [f32; 3])>) -> [f32; 3] {
+    bounds.map_or([0.0; 3], |(min, max)| std::array::from_fn(|i| (min[i] + max[i]) * 0.5))
+}
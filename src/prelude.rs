@@ -0,0 +1,26 @@
+//! # Prelude
+//!
+//! The user-facing types in one import for applications embedding the viewer:
+//!
+//! ```ignore
+//! use wgpu_viewer::prelude::*;
+//! ```
+//!
+//! Everything here is also exported from the crate root.
+
+pub use crate::{run, RenderMode, State};
+pub use crate::{BackgroundClear, BackgroundTheme, BlendMode, GridVisibility, HiddenLineMode, MeshShadingMode, PipeCapStyle, PointColorMode, PointShape, SurfaceColorSpace, ViewerConfig};
+pub use crate::{Camera, CameraSettings, ControllerMode, ProjectionMode, StandardView, LightOrbit, LightSettings, LightSource, ViewSettings, MAX_LIGHTS};
+pub use crate::{DragModifier, MouseBinding, MouseBindingPreset};
+pub use crate::Instance;
+pub use crate::{load_geometry_file, parse_geometry_json, validate_mesh, GeometryCounts, LoadProgress, LoadStage, MeshReport};
+pub use crate::{encode_geometry_binary, load_geometry_binary, parse_geometry_binary};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::export_geometry_binary;
+pub use crate::{
+    GeometryData, LineData, LineVertexData, MeshData, MeshVertexData, Metadata, PipeData, PipeSegmentData,
+    PointData, PointVertexData, PolygonData, PolygonMeshData, PolygonVertexData,
+};
+pub use crate::{SceneDescription, SceneFileEntry, SceneTransform};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::TurntableOptions;
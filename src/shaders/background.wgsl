@@ -0,0 +1,30 @@
+// Vertical gradient background drawn behind the scene
+
+struct Gradient {
+    top: vec4<f32>,    // Color at the top edge of the window, alpha unused
+    bottom: vec4<f32>, // Color at the bottom edge of the window
+};
+@group(3) @binding(0)
+var<uniform> gradient: Gradient;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) screen_uv: vec2<f32>, // 0..1 across the window, y down
+};
+
+// One triangle covering the whole window, no vertex buffer
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 1.0, 1.0);
+    out.screen_uv = uv;
+    return out;
+}
+
+// Written like a clear color: no exposure and no manual sRGB encoding, so the
+// gradient ends match solid backgrounds of the same colors
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(mix(gradient.top.rgb, gradient.bottom.rgb, clamp(in.screen_uv.y, 0.0, 1.0)), 1.0);
+}
@@ -0,0 +1,79 @@
+// Full-screen reference image drawn behind the scene
+
+struct CameraUniform {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Only using x component
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+struct Backplate {
+    opacity: f32,
+    image_aspect: f32, // Image width / height
+    _padding: vec2<f32>,
+};
+@group(3) @binding(0)
+var backplate_texture: texture_2d<f32>;
+@group(3) @binding(1)
+var backplate_sampler: sampler;
+@group(3) @binding(2)
+var<uniform> backplate: Backplate;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) screen_uv: vec2<f32>, // 0..1 across the window, y down
+};
+
+// One triangle covering the whole window, no vertex buffer
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 1.0, 1.0);
+    out.screen_uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Fit the whole image into the window, keeping its aspect ratio (letterboxed)
+    let window_aspect = camera.viewport_size.x / max(camera.viewport_size.y, 1.0);
+    var scale = vec2<f32>(1.0, 1.0);
+    if (window_aspect > backplate.image_aspect) {
+        scale.x = window_aspect / backplate.image_aspect;
+    } else {
+        scale.y = backplate.image_aspect / window_aspect;
+    }
+    let uv = (in.screen_uv - 0.5) * scale + 0.5;
+
+    let color = textureSample(backplate_texture, backplate_sampler, uv);
+    if (any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0))) {
+        discard;
+    }
+    return encode_output(vec4<f32>(color.rgb, color.a * backplate.opacity));
+}
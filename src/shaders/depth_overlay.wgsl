@@ -0,0 +1,57 @@
+// Depth overlay - the multisampled depth buffer as linear grayscale over the whole window
+
+@group(0) @binding(0)
+var depth_texture: texture_depth_multisampled_2d;
+
+struct DepthOverlay {
+    znear: f32,
+    zfar: f32,
+    orthographic: f32, // 1.0 when depth is already linear
+    _padding: f32,
+};
+@group(0) @binding(1)
+var<uniform> overlay: DepthOverlay;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(1) @binding(0)
+var<uniform> globals: Globals;
+
+// One triangle covering the whole window, no vertex buffer
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+}
+
+// Distance from the camera for a 0..1 depth value, normalized to 0 at znear and 1 at zfar
+fn linearize(depth: f32) -> f32 {
+    if (overlay.orthographic > 0.5) {
+        return depth;
+    }
+    let distance = overlay.znear * overlay.zfar / (overlay.zfar - depth * (overlay.zfar - overlay.znear));
+    return (distance - overlay.znear) / (overlay.zfar - overlay.znear);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = vec2<u32>(position.xy);
+    // The nearest sample, so silhouettes show the surface rather than the background
+    var nearest = 1.0;
+    for (var sample = 0u; sample < textureNumSamples(depth_texture); sample++) {
+        nearest = min(nearest, textureLoad(depth_texture, coords, i32(sample)));
+    }
+    var gray = clamp(linearize(nearest), 0.0, 1.0);
+    if (globals.encode_srgb > 0.5) {
+        gray = pow(gray, 1.0 / 2.2);
+    }
+    return vec4<f32>(gray, gray, gray, 1.0);
+}
@@ -0,0 +1,22 @@
+// Depth pick shader - reads the depth under one pixel of the multisampled depth buffer
+
+@group(0) @binding(0)
+var depth_texture: texture_depth_multisampled_2d;
+
+// Pixel to read (only xy used)
+@group(0) @binding(1)
+var<uniform> pixel: vec4<u32>;
+
+@group(0) @binding(2)
+var<storage, read_write> depth: f32;
+
+@compute @workgroup_size(1)
+fn cs_main() {
+    let coords = min(pixel.xy, textureDimensions(depth_texture) - vec2<u32>(1u));
+    // The nearest sample, so edges pick the surface rather than the background
+    var nearest = 1.0;
+    for (var sample = 0u; sample < textureNumSamples(depth_texture); sample++) {
+        nearest = min(nearest, textureLoad(depth_texture, coords, i32(sample)));
+    }
+    depth = nearest;
+}
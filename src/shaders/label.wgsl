@@ -0,0 +1,116 @@
+// Vertex shader for camera-facing text labels
+
+struct CameraUniform {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Aspect ratio (x), render target pixels per output pixel (y)
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+// Font atlas, white glyphs with coverage in the alpha channel
+@group(3) @binding(0)
+var font_texture: texture_2d<f32>;
+@group(3) @binding(1)
+var font_sampler: sampler;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
+
+struct VertexInput {
+    @location(0) anchor: vec3<f32>,   // World position the label is attached to
+    @location(1) offset: vec2<f32>,   // Corner offset from the anchor in pixels
+    @location(2) tex_coords: vec2<f32>,
+    @location(3) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) world_position: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    vertex: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Offset in screen space so labels always face the camera at a constant pixel size
+    let clip = camera.view_proj * vec4<f32>(vertex.anchor, 1.0);
+    let offset = vertex.offset * camera.aspect_ratio.y * 2.0 / camera.viewport_size.xy;
+    out.clip_position = vec4<f32>(clip.xy + offset * clip.w, clip.z, clip.w);
+
+    out.tex_coords = vertex.tex_coords;
+    out.color = vertex.color;
+    out.world_position = vertex.anchor;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = textureSample(font_texture, font_sampler, in.tex_coords).a;
+
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    if (coverage * in.color.a < 0.01) {
+        discard;
+    }
+    return encode_output(vec4<f32>(in.color.rgb, in.color.a * coverage));
+}
+
+// Screen-anchored text: the anchor xy is a position in normalized device coordinates
+@vertex
+fn vs_screen(
+    vertex: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let offset = vertex.offset * camera.aspect_ratio.y * 2.0 / camera.viewport_size.xy;
+    out.clip_position = vec4<f32>(vertex.anchor.xy + offset, 0.0, 1.0);
+    out.tex_coords = vertex.tex_coords;
+    out.color = vertex.color;
+    out.world_position = vertex.anchor;
+    return out;
+}
+
+// Screen text is not part of the scene: no clipping plane and no exposure.
+// Negative texture coordinates draw a solid quad, e.g. a panel behind the text.
+@fragment
+fn fs_screen(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Sampled in uniform control flow, as textureSample requires
+    let glyph_coverage = textureSample(font_texture, font_sampler, max(in.tex_coords, vec2<f32>(0.0))).a;
+    let coverage = select(glyph_coverage, 1.0, in.tex_coords.x < 0.0);
+    if (coverage * in.color.a < 0.01) {
+        discard;
+    }
+    var color = in.color.rgb;
+    if (globals.encode_srgb > 0.5) {
+        color = pow(color, vec3<f32>(1.0 / 2.2));
+    }
+    return vec4<f32>(color, in.color.a * coverage);
+}
@@ -7,12 +7,40 @@ struct Camera {
 @group(0) @binding(0)
 var<uniform> camera: Camera;
 
-struct Light {
+const MAX_LIGHTS: u32 = 8u; // Must match MAX_LIGHTS in lib_render.rs
+
+struct LightSource {
     position: vec3<f32>,
     color: vec3<f32>,
 }
+struct Lights {
+    lights: array<LightSource, MAX_LIGHTS>,
+    count: u32, // Number of active entries in `lights`
+}
 @group(1) @binding(0)
-var<uniform> light: Light;
+var<uniform> lights: Lights;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -26,8 +54,10 @@ struct VertexOutput {
 @vertex
 fn vs_main(
     model: VertexInput,
+    @builtin(instance_index) instance_index: u32, // One instance per active light
 ) -> VertexOutput {
     let scale = 0.25;
+    let light = lights.lights[instance_index];
     var out: VertexOutput;
     out.clip_position = camera.view_proj * vec4<f32>(model.position * scale + light.position, 1.0);
     out.color = light.color;
@@ -38,5 +68,5 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color, 1.0);
+    return encode_output(vec4<f32>(in.color, 1.0));
 }
\ No newline at end of file
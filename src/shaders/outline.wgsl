@@ -0,0 +1,106 @@
+// Selection outline: the back faces of a mesh pushed out along their normals by a
+// constant pixel width, so only a rim around the silhouette shows past the mesh
+
+struct Camera {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Aspect ratio (x), render target pixels per output pixel (y)
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
+}
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+struct Outline {
+    color: vec4<f32>,
+    width: f32, // Pixels
+    _padding_0: f32,
+    _padding_1: f32,
+    _padding_2: f32,
+}
+@group(3) @binding(0)
+var<uniform> outline: Outline;
+
+// The outline is a flat color, so the exposure is not applied
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(color.rgb, vec3<f32>(1.0 / 2.2)), color.a);
+    }
+    return color;
+}
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(2) normal: vec3<f32>,
+}
+
+struct InstanceInput {
+    @location(5) model_matrix_0: vec4<f32>,
+    @location(6) model_matrix_1: vec4<f32>,
+    @location(7) model_matrix_2: vec4<f32>,
+    @location(8) model_matrix_3: vec4<f32>,
+    @location(9) normal_matrix_0: vec3<f32>,
+    @location(10) normal_matrix_1: vec3<f32>,
+    @location(11) normal_matrix_2: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+}
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+) -> VertexOutput {
+    let model_matrix = mat4x4<f32>(
+        instance.model_matrix_0,
+        instance.model_matrix_1,
+        instance.model_matrix_2,
+        instance.model_matrix_3,
+    );
+    let normal_matrix = mat3x3<f32>(
+        instance.normal_matrix_0,
+        instance.normal_matrix_1,
+        instance.normal_matrix_2,
+    );
+    let world_position = model_matrix * vec4<f32>(model.position, 1.0);
+    let world_normal = normal_matrix * model.normal;
+
+    // Push the vertex along its screen-space normal; scaling by w keeps the width in pixels at any depth
+    let clip = camera.view_proj * world_position;
+    let clip_normal = (camera.view_proj * vec4<f32>(world_normal, 0.0)).xy;
+    var direction = vec2<f32>(0.0);
+    if (length(clip_normal) > 1e-6) {
+        direction = normalize(clip_normal);
+    }
+    let offset = direction * outline.width * camera.aspect_ratio.y * 2.0 / max(camera.viewport_size.xy, vec2<f32>(1.0));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(clip.xy + offset * clip.w, clip.zw);
+    out.world_position = world_position.xyz;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+    return encode_output(outline.color);
+}
@@ -3,18 +3,46 @@
 struct Camera {
     view_pos: vec4<f32>,
     view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Only using x component
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
 };
 @group(0) @binding(0)
 var<uniform> camera: Camera;
 
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec3<f32>,
+    @location(2) end_color: vec3<f32>,
+    @location(3) axial: f32, // 0 at the segment start, 1 at its end
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec3<f32>,
+    @location(1) world_position: vec3<f32>,
 };
 
 @vertex
@@ -29,11 +57,18 @@ fn vs_main(
     let world_position = vec4<f32>(model.position, 1.0);
     out.clip_position = camera.view_proj * world_position;
     
-    out.color = model.color;
+    // Blend along the pipe axis; flat pipes have end_color equal to color
+    out.color = mix(model.color, model.end_color, model.axial);
+    out.world_position = world_position.xyz;
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color, 1.0);
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    return encode_output(vec4<f32>(in.color, 1.0));
 }
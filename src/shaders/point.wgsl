@@ -1,9 +1,12 @@
 // Vertex shader for points with proper sizing support - HARDCODED VALUES TEST
+// Points are drawn as discs (fs_main) or as lit sphere impostors (fs_sphere)
 
 struct CameraUniform {
     view_position: vec4<f32>,
     view_proj: mat4x4<f32>,
     aspect_ratio: vec4<f32>, // Only using x component
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
 };
 
 // Point size now comes from vertex data
@@ -11,19 +14,75 @@ struct CameraUniform {
 @group(0) @binding(0)
 var<uniform> camera: CameraUniform;
 
+const MAX_LIGHTS: u32 = 8u; // Must match MAX_LIGHTS in lib_render.rs
+
+struct LightSource {
+    position: vec3<f32>,
+    color: vec3<f32>,
+}
+struct Lights {
+    lights: array<LightSource, MAX_LIGHTS>,
+    count: u32, // Number of active entries in `lights`
+}
+@group(1) @binding(0)
+var<uniform> lights: Lights; // Only read by the sphere impostors
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    point_color_mode: f32, // 1.0 colors points by density instead of their vertex color
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
+
 // Config is now hardcoded as constants
 
+// Viridis colormap (polynomial fit), t in [0, 1]; returns linear color
+fn viridis(t: f32) -> vec3<f32> {
+    let c0 = vec3<f32>(0.2777273272234177, 0.005407344544966578, 0.3340998053353061);
+    let c1 = vec3<f32>(0.1050930431085774, 1.404613529898575, 1.384590162594685);
+    let c2 = vec3<f32>(-0.3308618287255563, 0.214847559468213, 0.09509516302823659);
+    let c3 = vec3<f32>(-4.634230498983486, -5.799100973351585, -19.33244095627987);
+    let c4 = vec3<f32>(6.228269936347081, 14.17993336680509, 56.69055260068105);
+    let c5 = vec3<f32>(4.776384997670288, -13.74514537774601, -65.35303263337234);
+    let c6 = vec3<f32>(-5.435455855934631, 4.645852612178535, 26.3124352495832);
+    let srgb = c0 + t * (c1 + t * (c2 + t * (c3 + t * (c4 + t * (c5 + t * c6)))));
+    return pow(clamp(srgb, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(2.2));
+}
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
-    @location(1) color: vec3<f32>,
+    @location(1) color: vec4<f32>,
     @location(2) corner: vec2<f32>,  // Corner offset [-1,-1] to [1,1]
     @location(3) size: f32,
+    @location(4) density: f32,  // Local density in [0, 1], negative when not computed
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) color: vec3<f32>,
+    @location(0) color: vec4<f32>,
     @location(1) tex_coords: vec2<f32>,  // Normalized coordinates for the fragment shader
+    @location(2) world_position: vec3<f32>, // Point center, so points are clipped as a whole
+    @location(3) point_size: f32, // Half width of the quad in normalized device coordinates
+};
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @builtin(frag_depth) depth: f32,
 };
 
 @vertex
@@ -36,8 +95,8 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
     // Transform to clip space
     let clip_pos = camera.view_proj * world_position;
     
-    // Use size from vertex attributes
-    let point_size = vertex.size;
+    // Use size from vertex attributes, scaled by the global point size
+    let point_size = vertex.size * globals.point_size;
     
     // Use dynamic aspect ratio from camera uniform
     let dynamic_aspect_ratio = camera.aspect_ratio.x;
@@ -50,8 +109,13 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
         clip_pos.w
     );
     
-    // Pass color to fragment shader
+    // Pass color to fragment shader, replaced by the density heatmap when enabled
     out.color = vertex.color;
+    if (globals.point_color_mode > 0.5 && vertex.density >= 0.0) {
+        out.color = vec4<f32>(viridis(vertex.density), vertex.color.a);
+    }
+    out.world_position = vertex.position;
+    out.point_size = point_size;
     
     // Create texture coordinates from corner ([-1,-1] to [1,1]) to ([0,0] to [1,1])
     out.tex_coords = vertex.corner * 0.5 + 0.5;
@@ -61,6 +125,11 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
     // Calculate distance from center for circle shape
     // Convert tex_coords from [0,1] back to [-1,1] for distance calculation
     let point_coord = (in.tex_coords - 0.5) * 2.0;
@@ -75,5 +144,53 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let alpha = 1.0 - smoothstep(0.8, 1.0, distance_from_center);
     
     // Return color with calculated alpha
-    return vec4<f32>(in.color, alpha);
+    return encode_output(vec4<f32>(in.color.rgb, alpha * in.color.a));
+}
+
+// Sphere impostors: each quad is shaded as the sphere it covers, with the sphere's
+// normal and depth computed from the quad coordinate, so overlapping spheres intersect
+@fragment
+fn fs_sphere(in: VertexOutput) -> FragmentOutput {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    let point_coord = (in.tex_coords - 0.5) * 2.0;
+    let distance_squared = dot(point_coord, point_coord);
+    if (distance_squared > 1.0) {
+        discard;
+    }
+
+    // Screen-aligned basis in world space from the rows of the view-projection matrix;
+    // the length of the first row is the projection's x scale
+    let row_x = vec3<f32>(camera.view_proj[0].x, camera.view_proj[1].x, camera.view_proj[2].x);
+    let row_y = vec3<f32>(camera.view_proj[0].y, camera.view_proj[1].y, camera.view_proj[2].y);
+    let right = normalize(row_x);
+    let up = normalize(row_y);
+    let toward_camera = cross(right, up);
+
+    // World radius that projects to the quad's half width at the point's depth
+    let center_clip = camera.view_proj * vec4<f32>(in.world_position, 1.0);
+    let radius = in.point_size * center_clip.w / length(row_x);
+
+    let normal = normalize(right * point_coord.x + up * point_coord.y + toward_camera * sqrt(1.0 - distance_squared));
+    let surface = in.world_position + normal * radius;
+    let surface_clip = camera.view_proj * vec4<f32>(surface, 1.0);
+
+    // Same lighting terms as the meshes: ambient, wrapped diffuse and specular per light
+    let view_dir = normalize(camera.view_position.xyz - surface);
+    var lighting = vec3<f32>(0.0);
+    for (var i = 0u; i < min(lights.count, MAX_LIGHTS); i = i + 1u) {
+        let light = lights.lights[i];
+        let light_dir = normalize(light.position - surface);
+        let diffuse = 0.1 + 0.9 * max(dot(normal, light_dir), 0.0);
+        let specular = 0.4 * pow(max(dot(normal, normalize(light_dir + view_dir)), 0.0), 32.0);
+        lighting += (0.35 + diffuse + specular) * light.color;
+    }
+
+    var out: FragmentOutput;
+    out.color = encode_output(vec4<f32>(in.color.rgb * lighting, 1.0));
+    out.depth = surface_clip.z / surface_clip.w;
+    return out;
 }
@@ -3,27 +3,58 @@
 struct CameraUniform {
     view_pos: vec4<f32>,
     view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Only using x component
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
 };
 @group(0) @binding(0)
 var<uniform> camera: CameraUniform;
 
-struct Light {
+const MAX_LIGHTS: u32 = 8u; // Must match MAX_LIGHTS in lib_render.rs
+
+struct LightSource {
     position: vec3<f32>,
     color: vec3<f32>,
 };
+struct Lights {
+    lights: array<LightSource, MAX_LIGHTS>,
+    count: u32, // Number of active entries in `lights`
+};
 @group(1) @binding(0)
-var<uniform> light: Light;
+var<uniform> lights: Lights;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
 
 // Vertex shader input
 struct VertexInput {
     @location(0) position: vec3<f32>,
-    @location(1) color: vec3<f32>,
+    @location(1) color: vec4<f32>,
 };
 
 // Output from vertex to fragment shader
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) color: vec3<f32>,
+    @location(0) color: vec4<f32>,
     @location(1) world_position: vec3<f32>,
 };
 
@@ -41,19 +72,18 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
     // Compute face normal using derivatives (same as mesh shader)
     let pos_dx = dpdx(in.world_position);
     let pos_dy = dpdy(in.world_position);
     let face_normal = normalize(cross(pos_dy, pos_dx)); // Note: order matters for winding
     
     // Use the vertex color passed from the vertex shader
-    let object_color = vec4<f32>(in.color, 1.0);
-    
-    // Lighting calculation (same as mesh shader)
-    let light_dir = normalize(light.position - in.world_position);
-    
-    // Increased ambient for better visibility of non-directly lit faces
-    let ambient = 0.35 * light.color;
+    let object_color = in.color;
     
     // Hemisphere lighting - adds subtle blue-ish light from below (sky) and warm light from above (ground)
     let hemisphere_factor = 0.5 + 0.5 * dot(face_normal, vec3<f32>(0.0, 1.0, 0.0));
@@ -61,17 +91,30 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let ground_color = vec3<f32>(0.4, 0.3, 0.2); // Warm color for ground bounce
     let hemisphere = mix(ground_color, sky_color, hemisphere_factor) * 0.2;
     
-    // Enhanced diffuse lighting with softer falloff
-    let diff = max(dot(face_normal, light_dir), 0.0);
-    // Use a modified diffuse term that has some light even at glancing angles
-    let wrapped_diff = max(0.1 + 0.9 * diff, 0.0); 
-    let diffuse = wrapped_diff * light.color;
-    
-    // Enhanced specular highlight
     let view_dir = normalize(camera.view_pos.xyz - in.world_position);
-    let half_dir = normalize(light_dir + view_dir);
-    let spec = pow(max(dot(face_normal, half_dir), 0.0), 32.0);
-    let specular = 0.4 * spec * light.color;
+
+    // Accumulate ambient, diffuse and specular contributions from every active light
+    var ambient = vec3<f32>(0.0);
+    var diffuse = vec3<f32>(0.0);
+    var specular = vec3<f32>(0.0);
+    for (var i = 0u; i < min(lights.count, MAX_LIGHTS); i = i + 1u) {
+        let light = lights.lights[i];
+        let light_dir = normalize(light.position - in.world_position);
+
+        // Increased ambient for better visibility of non-directly lit faces
+        ambient += 0.35 * light.color;
+
+        // Enhanced diffuse lighting with softer falloff
+        let diff = max(dot(face_normal, light_dir), 0.0);
+        // Use a modified diffuse term that has some light even at glancing angles
+        let wrapped_diff = max(0.1 + 0.9 * diff, 0.0);
+        diffuse += wrapped_diff * light.color;
+
+        // Enhanced specular highlight
+        let half_dir = normalize(light_dir + view_dir);
+        let spec = pow(max(dot(face_normal, half_dir), 0.0), 32.0);
+        specular += 0.4 * spec * light.color;
+    }
     
     // Fresnel effect to brighten edges for more natural look
     let fresnel_factor = pow(1.0 - max(0.0, dot(view_dir, face_normal)), 2.0) * 0.2;
@@ -79,5 +122,16 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Combine all lighting components
     let result = (ambient + hemisphere + diffuse + specular + fresnel_factor) * object_color.xyz;
     
-    return vec4<f32>(result, 1.0); // Enhanced color with lighting
+    return encode_output(vec4<f32>(result, object_color.a)); // Enhanced color with lighting, vertex alpha
+}
+
+// Unlit polygons (annotations): vertex color only, no lighting
+@fragment
+fn fs_unlit(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    return encode_output(in.color);
 }
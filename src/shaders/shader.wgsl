@@ -3,16 +3,54 @@
 struct Camera {
     view_pos: vec4<f32>,
     view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Only using x component
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
 }
 @group(0) @binding(0)
 var<uniform> camera: Camera;
 
-struct Light {
+const MAX_LIGHTS: u32 = 8u; // Must match MAX_LIGHTS in lib_render.rs
+
+struct LightSource {
     position: vec3<f32>,
     color: vec3<f32>,
 }
+struct Lights {
+    lights: array<LightSource, MAX_LIGHTS>,
+    count: u32, // Number of active entries in `lights`
+}
 @group(1) @binding(0)
-var<uniform> light: Light;
+var<uniform> lights: Lights;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    mesh_shading_mode: f32, // 1.0 shows the texture coordinates as red/green, 2.0 the normal length as grey, instead of shading
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+// Only bound by the transparent mesh pipeline (same layout as the wireframe color)
+struct MeshTint {
+    color: vec4<f32>, // Alpha is the opacity of the model
+}
+@group(3) @binding(0)
+var<uniform> mesh_tint: MeshTint;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -29,14 +67,17 @@ struct InstanceInput {
     @location(9) normal_matrix_0: vec3<f32>,
     @location(10) normal_matrix_1: vec3<f32>,
     @location(11) normal_matrix_2: vec3<f32>,
+    @location(13) color: vec3<f32>, // Tint multiplied into the vertex color, white by default
 }
 
 struct VertexOutput {
-    @builtin(position) clip_position: vec4<f32>,
+    @builtin(position) @invariant clip_position: vec4<f32>, // Invariant so the depth pre-pass matches exactly
     @location(0) world_normal: vec3<f32>,
     @location(1) world_position: vec3<f32>,
     @location(2) @interpolate(flat) flat_normal: vec3<f32>, // Explicitly use flat interpolation
     @location(3) color: vec3<f32>,
+    @location(4) tex_coords: vec2<f32>,
+    @location(5) normal_length: f32, // Length of the imported normal, before normalization
 }
 
 @vertex
@@ -65,25 +106,48 @@ fn vs_main(
     out.world_normal = world_normal;
     out.world_position = world_position.xyz;
     out.flat_normal = world_normal; // For flat shading - will be flat interpolated
-    out.color = model.color;
+    out.color = model.color * instance.color;
+    out.tex_coords = model.tex_coords;
+    out.normal_length = length(model.normal);
     
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return shade_mesh(in);
+}
+
+// Translucent meshes: the same shading, blended with the opacity of the model
+@fragment
+fn fs_transparent(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = shade_mesh(in);
+    return vec4<f32>(color.rgb, color.a * mesh_tint.color.a);
+}
+
+// Lit (or UV debug) color of a mesh fragment, opaque
+fn shade_mesh(in: VertexOutput) -> vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    // Normal length debug view: unit normals are mid-grey, shorter ones darker, longer ones brighter
+    if (globals.mesh_shading_mode > 1.5) {
+        return encode_output(vec4<f32>(vec3<f32>(clamp(in.normal_length * 0.5, 0.0, 1.0)), 1.0));
+    }
+
+    // UV debug view: u as red, v as green, to check texture coordinates before textures are bound
+    if (globals.mesh_shading_mode > 0.5) {
+        return encode_output(vec4<f32>(in.tex_coords, 0.0, 1.0));
+    }
+
     // Use the flat interpolated normal for consistent face shading
     let face_normal = normalize(in.flat_normal);
     
     // Use the vertex color passed from the vertex shader
     let object_color = vec4<f32>(in.color, 1.0);
     
-    // Lighting calculation for more natural shading
-    let light_dir = normalize(light.position - in.world_position);
-    
-    // Increased ambient for better visibility of non-directly lit faces
-    let ambient = 0.35 * light.color;
-    
     // Hemisphere lighting - adds subtle blue-ish light from below (sky) and warm light from above (ground)
     // This simulates environment indirect lighting
     let hemisphere_up = vec3<f32>(0.0, 1.0, 0.0);
@@ -92,17 +156,30 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let ground_color = vec3<f32>(0.4, 0.3, 0.2); // Warm color for ground bounce
     let hemisphere = mix(ground_color, sky_color, hemisphere_factor) * 0.2;
     
-    // Enhanced diffuse lighting with softer falloff
-    let diff = max(dot(face_normal, light_dir), 0.0);
-    // Use a modified diffuse term that has some light even at glancing angles
-    let wrapped_diff = max(0.1 + 0.9 * diff, 0.0); 
-    let diffuse = wrapped_diff * light.color;
-    
-    // Enhanced specular highlight
     let view_dir = normalize(camera.view_pos.xyz - in.world_position);
-    let half_dir = normalize(light_dir + view_dir);
-    let spec = pow(max(dot(face_normal, half_dir), 0.0), 32.0);
-    let specular = 0.4 * spec * light.color;
+
+    // Accumulate ambient, diffuse and specular contributions from every active light
+    var ambient = vec3<f32>(0.0);
+    var diffuse = vec3<f32>(0.0);
+    var specular = vec3<f32>(0.0);
+    for (var i = 0u; i < min(lights.count, MAX_LIGHTS); i = i + 1u) {
+        let light = lights.lights[i];
+        let light_dir = normalize(light.position - in.world_position);
+
+        // Increased ambient for better visibility of non-directly lit faces
+        ambient += 0.35 * light.color;
+
+        // Enhanced diffuse lighting with softer falloff
+        let diff = max(dot(face_normal, light_dir), 0.0);
+        // Use a modified diffuse term that has some light even at glancing angles
+        let wrapped_diff = max(0.1 + 0.9 * diff, 0.0);
+        diffuse += wrapped_diff * light.color;
+
+        // Enhanced specular highlight
+        let half_dir = normalize(light_dir + view_dir);
+        let spec = pow(max(dot(face_normal, half_dir), 0.0), 32.0);
+        specular += 0.4 * spec * light.color;
+    }
     
     // Fresnel effect to brighten edges for more natural look
     let fresnel_factor = pow(1.0 - max(0.0, dot(view_dir, face_normal)), 2.0) * 0.2;
@@ -120,8 +197,43 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let edge_threshold = 0.15;
     
     if (edge_factor > edge_threshold) {
-        return vec4<f32>(0.0, 0.0, 0.0, 1.0); // Black edge
+        return encode_output(vec4<f32>(0.0, 0.0, 0.0, 1.0)); // Black edge
     }
     
-    return vec4<f32>(result, 1.0); // Enhanced color with more visible shading
-}
\ No newline at end of file
+    return encode_output(vec4<f32>(result, 1.0)); // Enhanced color with more visible shading
+}
+
+// Face orientation debug view: front faces green, back faces red.
+// Drawn without culling so inverted winding shows up as red patches.
+@fragment
+fn fs_face_orientation(
+    in: VertexOutput,
+    @builtin(front_facing) front_facing: bool,
+) -> @location(0) vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    let face_color = select(vec3<f32>(0.85, 0.15, 0.15), vec3<f32>(0.2, 0.75, 0.25), front_facing);
+
+    // Keep a little shading so the shape stays readable
+    let face_normal = normalize(in.flat_normal);
+    var shade = 1.0;
+    if (lights.count > 0u) {
+        let light_dir = normalize(lights.lights[0].position - in.world_position);
+        shade = 0.6 + 0.4 * abs(dot(face_normal, light_dir));
+    }
+
+    return encode_output(vec4<f32>(face_color * shade, 1.0));
+}
+
+// Depth pre-pass: only depth is written (color writes are masked off), but the
+// clipping plane must discard the same fragments as the color pass
+@fragment
+fn fs_depth_only(in: VertexOutput) -> @location(0) vec4<f32> {
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+    return vec4<f32>(0.0);
+}
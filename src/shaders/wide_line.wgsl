@@ -0,0 +1,144 @@
+// Vertex shader for screen-space wide lines
+
+struct CameraUniform {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Aspect ratio (x), render target pixels per output pixel (y)
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
+};
+@group(0) @binding(0)
+var<uniform> camera: CameraUniform;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
+
+struct VertexInput {
+    @location(0) start: vec3<f32>,
+    @location(1) end: vec3<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) corner: vec2<f32>, // x: 0 = start, 1 = end; y: side of the line (-1 or 1)
+    @location(4) width: f32,        // Line width in pixels
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) world_position: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    vertex: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let start_clip = camera.view_proj * vec4<f32>(vertex.start, 1.0);
+    let end_clip = camera.view_proj * vec4<f32>(vertex.end, 1.0);
+    let viewport = camera.viewport_size.xy;
+
+    // Direction of the segment in pixel space
+    let start_screen = start_clip.xy / start_clip.w * viewport * 0.5;
+    let end_screen = end_clip.xy / end_clip.w * viewport * 0.5;
+    var direction = end_screen - start_screen;
+    if (length(direction) < 0.0001) {
+        direction = vec2<f32>(1.0, 0.0);
+    }
+    direction = normalize(direction);
+    let normal = vec2<f32>(-direction.y, direction.x);
+
+    // Offset the selected endpoint by half the width on either side, converted back to NDC
+    let clip = select(start_clip, end_clip, vertex.corner.x > 0.5);
+    let offset = normal * vertex.corner.y * vertex.width * camera.aspect_ratio.y / viewport;
+    out.clip_position = vec4<f32>(clip.xy + offset * clip.w, clip.z, clip.w);
+
+    out.color = vertex.color;
+    out.world_position = select(vertex.start, vertex.end, vertex.corner.x > 0.5);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    return encode_output(in.color);
+}
+
+// Opacity of grid lines, so the grid reads as background rather than data
+const GRID_OPACITY: f32 = 0.45;
+// Pixels added on each side of the grid quads to fit the faded edge
+const GRID_FEATHER: f32 = 1.0;
+
+struct GridVertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) world_position: vec3<f32>,
+    @location(2) edge_distance: f32, // Pixels from the line center, signed by side
+    @location(3) half_width: f32,
+};
+
+// Grid lines keep `globals.grid_line_width` pixels at any distance; the quad is one
+// feather wider on each side so the fragment shader can fade the edges smoothly
+@vertex
+fn vs_grid(
+    vertex: VertexInput,
+) -> GridVertexOutput {
+    var out: GridVertexOutput;
+
+    let start_clip = camera.view_proj * vec4<f32>(vertex.start, 1.0);
+    let end_clip = camera.view_proj * vec4<f32>(vertex.end, 1.0);
+    let viewport = camera.viewport_size.xy;
+
+    var direction = end_clip.xy / end_clip.w * viewport - start_clip.xy / start_clip.w * viewport;
+    if (length(direction) < 0.0001) {
+        direction = vec2<f32>(1.0, 0.0);
+    }
+    direction = normalize(direction);
+    let normal = vec2<f32>(-direction.y, direction.x);
+
+    let half_width = globals.grid_line_width * camera.aspect_ratio.y * 0.5;
+    let extent = half_width + GRID_FEATHER;
+    let clip = select(start_clip, end_clip, vertex.corner.x > 0.5);
+    let offset = normal * vertex.corner.y * 2.0 * extent / viewport;
+    out.clip_position = vec4<f32>(clip.xy + offset * clip.w, clip.z, clip.w);
+
+    out.color = vertex.color;
+    out.world_position = select(vertex.start, vertex.end, vertex.corner.x > 0.5);
+    out.edge_distance = vertex.corner.y * extent;
+    out.half_width = half_width;
+    return out;
+}
+
+@fragment
+fn fs_grid(in: GridVertexOutput) -> @location(0) vec4<f32> {
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    // Full coverage inside the line, fading to zero over one pixel at its edges, so lines
+    // thinner than a pixel turn fainter instead of breaking up
+    let coverage = clamp(in.half_width + 0.5 - abs(in.edge_distance), 0.0, 1.0);
+    return encode_output(vec4<f32>(in.color.rgb, in.color.a * GRID_OPACITY * coverage));
+}
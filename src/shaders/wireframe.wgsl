@@ -0,0 +1,110 @@
+// Vertex shader for the per-model wireframe overlay
+
+struct Camera {
+    view_pos: vec4<f32>,
+    view_proj: mat4x4<f32>,
+    aspect_ratio: vec4<f32>,  // Aspect ratio (x), render target pixels per output pixel (y)
+    viewport_size: vec4<f32>, // Viewport width and height in pixels (xy), top-left corner in the window (zw)
+    clip_plane: vec4<f32>,    // Plane equation (xyz normal, w offset); all zero disables clipping
+}
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct Globals {
+    point_size: f32, // Multiplier for the per-vertex point size
+    time: f32,       // Elapsed time in seconds
+    encode_srgb: f32, // 1.0 when output must be gamma encoded manually
+    _padding: f32,
+    exposure: f32,   // Brightness multiplier applied to the final color
+    grid_line_width: f32, // Pixel width of the reference grid lines
+    _padding_2: f32,
+    _padding_3: f32,
+};
+@group(2) @binding(0)
+var<uniform> globals: Globals;
+
+struct Wireframe {
+    color: vec4<f32>,
+}
+@group(3) @binding(0)
+var<uniform> wireframe: Wireframe;
+
+// Line topologies cannot use a pipeline depth bias, so the edges are pulled
+// towards the camera in clip space to win the depth test against their own faces
+const WIREFRAME_DEPTH_BIAS: f32 = 0.0005;
+
+// Dash length plus gap in pixels for occluded (hidden) edges
+const DASH_PERIOD: f32 = 8.0;
+
+// Apply the exposure, then encode linear color to sRGB when the surface format is linear (non-sRGB)
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    let exposed = vec4<f32>(color.rgb * globals.exposure, color.a);
+    if (globals.encode_srgb > 0.5) {
+        return vec4<f32>(pow(exposed.rgb, vec3<f32>(1.0 / 2.2)), exposed.a);
+    }
+    return exposed;
+}
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+}
+
+struct InstanceInput {
+    @location(5) model_matrix_0: vec4<f32>,
+    @location(6) model_matrix_1: vec4<f32>,
+    @location(7) model_matrix_2: vec4<f32>,
+    @location(8) model_matrix_3: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) @interpolate(flat) line_start: vec2<f32>, // Pixel position of the segment's first vertex
+}
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+) -> VertexOutput {
+    let model_matrix = mat4x4<f32>(
+        instance.model_matrix_0,
+        instance.model_matrix_1,
+        instance.model_matrix_2,
+        instance.model_matrix_3,
+    );
+    let world_position = model_matrix * vec4<f32>(model.position, 1.0);
+
+    var out: VertexOutput;
+    let clip = camera.view_proj * world_position;
+    out.clip_position = vec4<f32>(clip.xy, clip.z - WIREFRAME_DEPTH_BIAS * clip.w, clip.w);
+    out.world_position = world_position.xyz;
+
+    // Framebuffer coordinates have y pointing down
+    let ndc = clip.xy / clip.w;
+    out.line_start = vec2<f32>(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * camera.viewport_size.xy + camera.viewport_size.zw;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Discard fragments on the negative side of the clipping plane
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+
+    return encode_output(wireframe.color);
+}
+
+// Occluded edges of the hidden line view, dashed by the pixel distance along the segment
+@fragment
+fn fs_dashed(in: VertexOutput) -> @location(0) vec4<f32> {
+    if (dot(vec4<f32>(in.world_position, 1.0), camera.clip_plane) < 0.0) {
+        discard;
+    }
+    if (fract(distance(in.clip_position.xy, in.line_start) / (DASH_PERIOD * camera.aspect_ratio.y)) > 0.5) {
+        discard;
+    }
+
+    return encode_output(wireframe.color);
+}